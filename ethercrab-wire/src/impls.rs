@@ -252,7 +252,7 @@ impl EtherCrabWireWrite for &[u8] {
 // Blanket impl for references
 impl<T> EtherCrabWireWrite for &T
 where
-    T: EtherCrabWireWrite,
+    T: EtherCrabWireWrite + ?Sized,
 {
     fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
         EtherCrabWireWrite::pack_to_slice_unchecked(*self, buf)
@@ -293,6 +293,25 @@ where
     }
 }
 
+impl<const N: usize, T> EtherCrabWireWrite for heapless::Vec<T, N>
+where
+    T: EtherCrabWireWrite,
+{
+    fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
+        let mut pos = 0;
+
+        for item in self.iter() {
+            pos += item.pack_to_slice_unchecked(&mut buf[pos..]).len();
+        }
+
+        &buf[0..pos]
+    }
+
+    fn packed_len(&self) -> usize {
+        self.iter().map(|item| item.packed_len()).sum()
+    }
+}
+
 // MSRV: generic_const_exprs: When we can do `N * T::PACKED_LEN`, this specific impl for `u8` can be
 // replaced with `T: EtherCrabWireSized`.
 impl<const N: usize, T> EtherCrabWireSized for heapless::Vec<T, N>
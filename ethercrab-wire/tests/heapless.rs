@@ -1,4 +1,4 @@
-use ethercrab_wire::EtherCrabWireRead;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWrite};
 
 #[test]
 fn heapless_str() {
@@ -9,3 +9,16 @@ fn heapless_str() {
         Ok("Hello world".try_into().unwrap())
     );
 }
+
+#[test]
+fn heapless_vec_pack() {
+    let input = heapless::Vec::<u16, 8>::from_slice(&[0x0002, 0x1600, 0x1601]).unwrap();
+
+    let mut buf = [0u8; 16];
+
+    assert_eq!(input.packed_len(), 6);
+    assert_eq!(
+        input.pack_to_slice(&mut buf),
+        Ok([0x02, 0x00, 0x00, 0x16, 0x01, 0x16].as_slice())
+    );
+}
@@ -122,6 +122,7 @@ fn cia402_control_word() {
 
 #[test]
 fn sized() {
+    #[allow(unused)]
     #[derive(ethercrab_wire::EtherCrabWireRead)]
     #[wire(bytes = 9)]
     struct DriveState {
@@ -141,6 +142,7 @@ fn sized() {
         di3: bool,
     }
 
+    #[allow(unused)]
     #[derive(Copy, Clone, ethercrab_wire::EtherCrabWireWrite)]
     #[wire(bytes = 1)]
     #[repr(u8)]
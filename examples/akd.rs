@@ -4,7 +4,7 @@ use env_logger::Env;
 use ethercrab::{
     error::{Error, MailboxError},
     std::{ethercat_now, tx_rx_task},
-    MainDevice, MainDeviceConfig, PduStorage, Timeouts,
+    MainDevice, MainDeviceConfig, PdoAssignment, PdoMapping, PduStorage, Timeouts,
 };
 use std::{sync::Arc, time::Duration};
 use tokio::time::MissedTickBehavior;
@@ -78,15 +78,15 @@ async fn main() -> Result<(), Error> {
 
         // AKD config
         if subdevice.name() == "AKD" {
-            subdevice.sdo_write(0x1c12, 0, 0u8).await?;
-            // 0x1702 = fixed velocity mapping
-            subdevice.sdo_write(0x1c12, 1, 0x1702u16).await?;
-            subdevice.sdo_write(0x1c12, 0, 0x01u8).await?;
-
             // Must set both read AND write SDOs for AKD otherwise it times out going into OP
-            subdevice.sdo_write(0x1c13, 0, 0u8).await?;
-            subdevice.sdo_write(0x1c13, 1, 0x1B01u16).await?;
-            subdevice.sdo_write(0x1c13, 0, 0x01u8).await?;
+            subdevice
+                .configure_pdos(
+                    &PdoAssignment::new()
+                        // 0x1702 = fixed velocity mapping
+                        .rx_pdos(&[PdoMapping::fixed(0x1702)])
+                        .tx_pdos(&[PdoMapping::fixed(0x1b01)]),
+                )
+                .await?;
 
             // Opmode - Cyclic Synchronous Position
             // subdevice.write_sdo(0x6060, 0, 0x08).await?;
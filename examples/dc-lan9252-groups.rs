@@ -218,7 +218,7 @@ fn main() -> Result<(), Error> {
                 let mut max_deviation = 0;
 
                 for s1 in fast_group.iter(&maindevice) {
-                    let diff = match s1
+                    let diff = s1
                         .register_read::<u32>(RegisterAddress::DcSystemTimeDifference)
                         .await
                         // The returned value is NOT in two's compliment, rather the upper bit
@@ -236,7 +236,9 @@ fn main() -> Result<(), Error> {
                             } else {
                                 value as i32
                             }
-                        }) {
+                        });
+
+                    let diff = match diff {
                         Ok(diff) => diff,
                         Err(Error::WorkingCounter { .. }) => 0,
                         Err(e) => return Err(e),
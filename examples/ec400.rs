@@ -12,7 +12,7 @@ use ethercrab::{
     ds402::{Ds402, Ds402Sm},
     error::Error,
     std::{ethercat_now, tx_rx_task},
-    MainDevice, MainDeviceConfig, PduStorage, Timeouts,
+    MainDevice, MainDeviceConfig, PdoAssignment, PdoMapping, PdoMappingEntry, PduStorage, Timeouts,
 };
 use std::{
     sync::{
@@ -68,30 +68,31 @@ async fn main() -> Result<(), Error> {
     for subdevice in group.iter(&maindevice) {
         if subdevice.name() == "ELP-EC400S" {
             // CSV described a bit better in section 7.6.2.2 Related Objects of the manual
-            subdevice.sdo_write(0x1600, 0, 0u8).await?;
-            // Control word, u16
-            // NOTE: The lower word specifies the field length
-            subdevice.sdo_write(0x1600, 1, 0x6040_0010u32).await?;
-            // Target velocity, i32
-            subdevice.sdo_write(0x1600, 2, 0x60ff_0020u32).await?;
-            subdevice.sdo_write(0x1600, 0, 2u8).await?;
-
-            subdevice.sdo_write(0x1a00, 0, 0u8).await?;
-            // Status word, u16
-            subdevice.sdo_write(0x1a00, 1, 0x6041_0010u32).await?;
-            // Actual position, i32
-            subdevice.sdo_write(0x1a00, 2, 0x6064_0020u32).await?;
-            // Actual velocity, i32
-            subdevice.sdo_write(0x1a00, 3, 0x606c_0020u32).await?;
-            subdevice.sdo_write(0x1a00, 0, 0x03u8).await?;
-
-            subdevice.sdo_write(0x1c12, 0, 0u8).await?;
-            subdevice.sdo_write(0x1c12, 1, 0x1600).await?;
-            subdevice.sdo_write(0x1c12, 0, 1u8).await?;
-
-            subdevice.sdo_write(0x1c13, 0, 0u8).await?;
-            subdevice.sdo_write(0x1c13, 1, 0x1a00).await?;
-            subdevice.sdo_write(0x1c13, 0, 1u8).await?;
+            subdevice
+                .configure_pdos(
+                    &PdoAssignment::new()
+                        .rx_pdos(&[PdoMapping::new(
+                            0x1600,
+                            &[
+                                // Control word, u16
+                                PdoMappingEntry::new(0x6040, 0, 16),
+                                // Target velocity, i32
+                                PdoMappingEntry::new(0x60ff, 0, 32),
+                            ],
+                        )])
+                        .tx_pdos(&[PdoMapping::new(
+                            0x1a00,
+                            &[
+                                // Status word, u16
+                                PdoMappingEntry::new(0x6041, 0, 16),
+                                // Actual position, i32
+                                PdoMappingEntry::new(0x6064, 0, 32),
+                                // Actual velocity, i32
+                                PdoMappingEntry::new(0x606c, 0, 32),
+                            ],
+                        )]),
+                )
+                .await?;
 
             // Opmode - Cyclic Synchronous Position
             // subdevice.write_sdo(0x6060, 0, 0x08).await?;
@@ -0,0 +1,409 @@
+//! ADS over EtherCAT (AoE), defined in ETG1000.6 Section 5.8 and the Beckhoff ADS specification.
+
+/// AMS state flags for an ADS request sent over EtherCAT.
+pub const STATE_FLAGS_REQUEST: u16 = 0x0004;
+
+/// The state flag set in ADS responses.
+pub const STATE_FLAG_RESPONSE: u16 = 0x0001;
+
+/// An AMS NetId, used with a port to address an ADS device.
+///
+/// NetIds are usually written as six dot separated numbers, e.g. `5.12.34.56.1.1`.
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::AmsNetId;
+///
+/// let net_id = AmsNetId([5, 12, 34, 56, 1, 1]);
+///
+/// assert_eq!(net_id.to_string(), "5.12.34.56.1.1");
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AmsNetId(pub [u8; 6]);
+
+impl From<[u8; 6]> for AmsNetId {
+    fn from(value: [u8; 6]) -> Self {
+        Self(value)
+    }
+}
+
+impl core::fmt::Display for AmsNetId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+
+        write!(f, "{}.{}.{}.{}.{}.{}", a, b, c, d, e, g)
+    }
+}
+
+/// The AMS NetId and port of an ADS device.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AmsAddress {
+    /// AMS NetId.
+    pub net_id: AmsNetId,
+    /// AMS port.
+    pub port: u16,
+}
+
+impl AmsAddress {
+    /// Create a new address from a NetId and port.
+    pub const fn new(net_id: [u8; 6], port: u16) -> Self {
+        Self {
+            net_id: AmsNetId(net_id),
+            port,
+        }
+    }
+}
+
+impl core::fmt::Display for AmsAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.net_id, self.port)
+    }
+}
+
+/// ADS command ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u16)]
+pub enum AdsCommand {
+    /// Invalid command.
+    Invalid = 0x0000,
+    /// Read the name and version of the device.
+    ReadDeviceInfo = 0x0001,
+    /// Read data from an index group and offset.
+    Read = 0x0002,
+    /// Write data to an index group and offset.
+    Write = 0x0003,
+    /// Read the ADS and device state.
+    ReadState = 0x0004,
+    /// Change the ADS and device state.
+    WriteControl = 0x0005,
+    /// Add a device notification.
+    AddNotification = 0x0006,
+    /// Delete a device notification.
+    DeleteNotification = 0x0007,
+    /// Device notification.
+    Notification = 0x0008,
+    /// Write data then read data back in one request.
+    ReadWrite = 0x0009,
+    /// Unknown command.
+    #[wire(catch_all)]
+    Unknown(u16),
+}
+
+/// AMS header, sent after the mailbox header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 32)]
+pub struct AmsHeader {
+    #[wire(bytes = 6)]
+    pub target_net_id: [u8; 6],
+    #[wire(bytes = 2)]
+    pub target_port: u16,
+    #[wire(bytes = 6)]
+    pub source_net_id: [u8; 6],
+    #[wire(bytes = 2)]
+    pub source_port: u16,
+    #[wire(bytes = 2)]
+    pub command: AdsCommand,
+    #[wire(bytes = 2)]
+    pub state_flags: u16,
+    /// Length of the ADS data following this header.
+    #[wire(bytes = 4)]
+    pub length: u32,
+    #[wire(bytes = 4)]
+    pub error_code: AdsErrorCode,
+    /// Set by the requester and copied into the response to match the two together.
+    #[wire(bytes = 4)]
+    pub invoke_id: u32,
+}
+
+impl AmsHeader {
+    /// Create a request header with `length` bytes of ADS data following it.
+    pub fn request(
+        target: AmsAddress,
+        source: AmsAddress,
+        command: AdsCommand,
+        length: usize,
+        invoke_id: u32,
+    ) -> Self {
+        Self {
+            target_net_id: target.net_id.0,
+            target_port: target.port,
+            source_net_id: source.net_id.0,
+            source_port: source.port,
+            command,
+            state_flags: STATE_FLAGS_REQUEST,
+            length: length as u32,
+            error_code: AdsErrorCode::NoError,
+            invoke_id,
+        }
+    }
+
+    /// Whether this header is a response.
+    pub fn is_response(&self) -> bool {
+        self.state_flags & STATE_FLAG_RESPONSE != 0
+    }
+}
+
+/// ADS Read request body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireWrite)]
+#[wire(bytes = 12)]
+pub struct AdsReadRequest {
+    #[wire(bytes = 4)]
+    pub index_group: u32,
+    #[wire(bytes = 4)]
+    pub index_offset: u32,
+    #[wire(bytes = 4)]
+    pub length: u32,
+}
+
+/// ADS Write request body, followed by the data to write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireWrite)]
+#[wire(bytes = 12)]
+pub struct AdsWriteRequest {
+    #[wire(bytes = 4)]
+    pub index_group: u32,
+    #[wire(bytes = 4)]
+    pub index_offset: u32,
+    #[wire(bytes = 4)]
+    pub length: u32,
+}
+
+/// ADS ReadWrite request body, followed by the data to write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireWrite)]
+#[wire(bytes = 16)]
+pub struct AdsReadWriteRequest {
+    #[wire(bytes = 4)]
+    pub index_group: u32,
+    #[wire(bytes = 4)]
+    pub index_offset: u32,
+    #[wire(bytes = 4)]
+    pub read_length: u32,
+    #[wire(bytes = 4)]
+    pub write_length: u32,
+}
+
+/// The ADS and device state of a SubDevice, read with
+/// [`SubDeviceRef::aoe_read_state`](crate::SubDeviceRef::aoe_read_state).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 4)]
+pub struct AdsDeviceState {
+    /// ADS state.
+    #[wire(bytes = 2)]
+    pub ads_state: AdsState,
+    /// Device specific state.
+    #[wire(bytes = 2)]
+    pub device_state: u16,
+}
+
+/// ADS state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u16)]
+pub enum AdsState {
+    /// Invalid.
+    Invalid = 0,
+    /// Idle.
+    Idle = 1,
+    /// Reset.
+    Reset = 2,
+    /// Initialising.
+    Init = 3,
+    /// Starting.
+    Start = 4,
+    /// Running.
+    Run = 5,
+    /// Stopped.
+    Stop = 6,
+    /// Saving configuration.
+    SaveConfig = 7,
+    /// Loading configuration.
+    LoadConfig = 8,
+    /// Power failure.
+    PowerFailure = 9,
+    /// Power good.
+    PowerGood = 10,
+    /// Error state.
+    Fault = 11,
+    /// Shutting down.
+    Shutdown = 12,
+    /// Suspended.
+    Suspend = 13,
+    /// Resuming.
+    Resume = 14,
+    /// Configuration mode.
+    Config = 15,
+    /// Reconfiguring.
+    Reconfig = 16,
+    /// Stopping.
+    Stopping = 17,
+    /// Incompatible.
+    Incompatible = 18,
+    /// Exception.
+    Exception = 19,
+    /// Unknown state.
+    #[wire(catch_all)]
+    Unknown(u16),
+}
+
+/// ADS error code, returned in the AMS header or as the result of an ADS response.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u32)]
+pub enum AdsErrorCode {
+    /// No error.
+    NoError = 0x0000,
+    /// Internal error.
+    InternalError = 0x0001,
+    /// Target port not found.
+    TargetPortNotFound = 0x0006,
+    /// Target machine not found.
+    TargetMachineNotFound = 0x0007,
+    /// Unknown command ID.
+    UnknownCommand = 0x0008,
+    /// General device error.
+    DeviceError = 0x0700,
+    /// Service is not supported by the server.
+    ServiceNotSupported = 0x0701,
+    /// Invalid index group.
+    InvalidIndexGroup = 0x0702,
+    /// Invalid index offset.
+    InvalidIndexOffset = 0x0703,
+    /// Reading or writing not permitted.
+    InvalidAccess = 0x0704,
+    /// Parameter size not correct.
+    InvalidSize = 0x0705,
+    /// Invalid data values.
+    InvalidData = 0x0706,
+    /// Device is not ready to operate.
+    NotReady = 0x0707,
+    /// Device is busy.
+    Busy = 0x0708,
+    /// Invalid operating system context.
+    InvalidContext = 0x0709,
+    /// Insufficient memory.
+    NoMemory = 0x070a,
+    /// Invalid parameter values.
+    InvalidParameter = 0x070b,
+    /// Not found.
+    NotFound = 0x070c,
+    /// Syntax error in file or command.
+    Syntax = 0x070d,
+    /// Objects do not match.
+    Incompatible = 0x070e,
+    /// Object already exists.
+    Exists = 0x070f,
+    /// Symbol not found.
+    SymbolNotFound = 0x0710,
+    /// Invalid state.
+    InvalidState = 0x0712,
+    /// Device timeout.
+    Timeout = 0x0719,
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u32),
+}
+
+impl core::fmt::Display for AdsErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoError => f.write_str("no error"),
+            Self::InternalError => f.write_str("internal error"),
+            Self::TargetPortNotFound => f.write_str("target port not found"),
+            Self::TargetMachineNotFound => f.write_str("target machine not found"),
+            Self::UnknownCommand => f.write_str("unknown command ID"),
+            Self::DeviceError => f.write_str("general device error"),
+            Self::ServiceNotSupported => f.write_str("service not supported"),
+            Self::InvalidIndexGroup => f.write_str("invalid index group"),
+            Self::InvalidIndexOffset => f.write_str("invalid index offset"),
+            Self::InvalidAccess => f.write_str("reading or writing not permitted"),
+            Self::InvalidSize => f.write_str("parameter size not correct"),
+            Self::InvalidData => f.write_str("invalid data values"),
+            Self::NotReady => f.write_str("device not ready"),
+            Self::Busy => f.write_str("device busy"),
+            Self::InvalidContext => f.write_str("invalid operating system context"),
+            Self::NoMemory => f.write_str("insufficient memory"),
+            Self::InvalidParameter => f.write_str("invalid parameter values"),
+            Self::NotFound => f.write_str("not found"),
+            Self::Syntax => f.write_str("syntax error"),
+            Self::Incompatible => f.write_str("objects do not match"),
+            Self::Exists => f.write_str("object already exists"),
+            Self::SymbolNotFound => f.write_str("symbol not found"),
+            Self::InvalidState => f.write_str("invalid state"),
+            Self::Timeout => f.write_str("device timeout"),
+            Self::Unknown(code) => write!(f, "unknown error {:#010x}", code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWriteSized};
+
+    #[test]
+    fn encode_read_state_request() {
+        let header = AmsHeader::request(
+            AmsAddress::new([5, 12, 34, 56, 1, 1], 0x1001),
+            AmsAddress::new([0; 6], 0),
+            AdsCommand::ReadState,
+            0,
+            0x1234,
+        );
+
+        assert_eq!(
+            header.pack(),
+            [
+                5, 12, 34, 56, 1, 1, // Target NetId
+                0x01, 0x10, // Target port
+                0, 0, 0, 0, 0, 0, // Source NetId
+                0, 0, // Source port
+                0x04, 0x00, // Command
+                0x04, 0x00, // State flags
+                0, 0, 0, 0, // Length
+                0, 0, 0, 0, // Error code
+                0x34, 0x12, 0, 0 // Invoke ID
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_response_header() {
+        let header = AmsHeader::unpack_from_slice(&[
+            0, 0, 0, 0, 0, 0, // Target NetId
+            0, 0, // Target port
+            5, 12, 34, 56, 1, 1, // Source NetId
+            0x01, 0x10, // Source port
+            0x02, 0x00, // Command
+            0x05, 0x00, // State flags
+            0x08, 0, 0, 0, // Length
+            0x02, 0x07, 0, 0, // Error code
+            0x01, 0, 0, 0, // Invoke ID
+        ])
+        .unwrap();
+
+        assert!(header.is_response());
+        assert_eq!(header.command, AdsCommand::Read);
+        assert_eq!(header.error_code, AdsErrorCode::InvalidIndexGroup);
+        assert_eq!(header.invoke_id, 1);
+    }
+
+    #[test]
+    fn decode_device_state() {
+        assert_eq!(
+            AdsDeviceState::unpack_from_slice(&[0x05, 0x00, 0x34, 0x12]),
+            Ok(AdsDeviceState {
+                ads_state: AdsState::Run,
+                device_state: 0x1234
+            })
+        );
+    }
+}
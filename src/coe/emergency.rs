@@ -0,0 +1,125 @@
+//! CoE Emergency messages, defined in ETG1000.6 Section 5.6.4.
+
+use super::CoeService;
+use crate::{
+    fmt,
+    mailbox::{MailboxHeader, MailboxType},
+};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
+
+/// The maximum number of unread emergency messages stored for each SubDevice.
+///
+/// Must be a power of two.
+const EMERGENCY_QUEUE_LEN: usize = 8;
+
+/// A CoE Emergency message sent by a SubDevice, e.g. when a drive faults.
+///
+/// Defined in ETG1000.6 Section 5.6.4.1 Table 50 – Emergency Request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 8)]
+pub struct CoeEmergency {
+    /// Error code, defined by the device profile (e.g. CiA 402) or the SubDevice vendor.
+    #[wire(bytes = 2)]
+    pub error_code: u16,
+    /// Value of the error register object `0x1001` when the error occurred.
+    #[wire(bytes = 1)]
+    pub error_register: u8,
+    /// Vendor specific data.
+    #[wire(bytes = 5)]
+    pub data: [u8; 5],
+}
+
+impl CoeEmergency {
+    /// Decode an emergency from a raw mailbox read, returning `None` if the mailbox does not
+    /// contain an emergency.
+    pub(crate) fn from_mailbox(buf: &[u8]) -> Option<Self> {
+        let header = MailboxHeader::unpack_from_slice(buf).ok()?;
+
+        if header.mailbox_type != MailboxType::Coe || header.service != CoeService::Emergency {
+            return None;
+        }
+
+        Self::unpack_from_slice(buf.get(MailboxHeader::PACKED_LEN..)?).ok()
+    }
+}
+
+/// A bounded queue of emergency messages received from a SubDevice.
+///
+/// When the queue is full, the oldest message is discarded to make space for a new one.
+#[derive(Default)]
+pub(crate) struct EmergencyQueue {
+    queue: heapless::mpmc::MpMcQueue<CoeEmergency, EMERGENCY_QUEUE_LEN>,
+}
+
+impl EmergencyQueue {
+    pub(crate) fn push(&self, emergency: CoeEmergency) {
+        let mut emergency = emergency;
+
+        while let Err(rejected) = self.queue.enqueue(emergency) {
+            emergency = rejected;
+
+            let _discarded = self.queue.dequeue();
+
+            fmt::warn!("Emergency queue is full, discarding oldest message");
+        }
+    }
+
+    pub(crate) fn pop(&self) -> Option<CoeEmergency> {
+        self.queue.dequeue()
+    }
+}
+
+impl core::fmt::Debug for EmergencyQueue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EmergencyQueue").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_emergency() {
+        // Mailbox header, CoE header with service 0x01, then error code 0x7500, error register 0x81
+        let raw = [
+            0x0a, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x10, 0x00, 0x75, 0x81, 0x01, 0x02, 0x03,
+            0x04, 0x05,
+        ];
+
+        assert_eq!(
+            CoeEmergency::from_mailbox(&raw),
+            Some(CoeEmergency {
+                error_code: 0x7500,
+                error_register: 0x81,
+                data: [0x01, 0x02, 0x03, 0x04, 0x05]
+            })
+        );
+    }
+
+    #[test]
+    fn ignore_sdo_response() {
+        let raw = [
+            0x0a, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x30, 0x60, 0x00, 0x10, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+
+        assert_eq!(CoeEmergency::from_mailbox(&raw), None);
+    }
+
+    #[test]
+    fn queue_discards_oldest() {
+        let queue = EmergencyQueue::default();
+
+        for error_code in 0..(EMERGENCY_QUEUE_LEN as u16 + 2) {
+            queue.push(CoeEmergency {
+                error_code,
+                error_register: 0,
+                data: [0u8; 5],
+            });
+        }
+
+        assert_eq!(queue.pop().map(|e| e.error_code), Some(2));
+    }
+}
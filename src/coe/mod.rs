@@ -1,6 +1,9 @@
 use ethercrab_wire::EtherCrabWireReadSized;
 
 pub mod abort_code;
+pub mod emergency;
+pub mod pdo_assignment;
+pub mod sdo_info;
 pub mod services;
 
 /// Defined in ETG1000.6 Table 29 – CoE elements
@@ -31,9 +34,12 @@ pub enum CoeService {
 ///
 /// See e.g. ETG1000.6 Section 5.6.2.6.2 Table 39 – Upload SDO Segment Response.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bits = 3)]
 #[repr(u8)]
 pub enum CoeCommand {
+    /// Download segment request. Also used as the upload segment response.
+    DownloadSegment = 0x00,
     Download = 0x01,
     Upload = 0x02,
     Abort = 0x04,
@@ -75,7 +81,7 @@ pub struct SegmentSdoHeader {
     pub toggle: bool,
 
     #[wire(bits = 3)]
-    command: CoeCommand,
+    pub command: CoeCommand,
 }
 
 /// Subindex access.
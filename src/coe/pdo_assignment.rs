@@ -0,0 +1,159 @@
+//! PDO assignment and mapping objects, defined in ETG1000.6 Section 5.6.7.4.
+
+use core::ops::RangeInclusive;
+
+/// RxPDO assignment object, listing the PDOs exchanged by the outputs sync manager.
+pub(crate) const RX_PDO_ASSIGNMENT: u16 = 0x1c12;
+
+/// TxPDO assignment object, listing the PDOs exchanged by the inputs sync manager.
+pub(crate) const TX_PDO_ASSIGNMENT: u16 = 0x1c13;
+
+/// Object indices of RxPDO (output) mapping objects.
+pub(crate) const RX_PDO_MAPPING: RangeInclusive<u16> = 0x1600..=0x17ff;
+
+/// Object indices of TxPDO (input) mapping objects.
+pub(crate) const TX_PDO_MAPPING: RangeInclusive<u16> = 0x1a00..=0x1bff;
+
+/// The maximum number of sub-indices in an assignment or mapping object. Sub-index 255 is
+/// reserved.
+pub(crate) const MAX_SUB_INDICES: usize = 254;
+
+/// An object mapped into a PDO.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PdoMappingEntry {
+    /// Object index.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Length of the object in bits.
+    pub bit_len: u8,
+}
+
+impl PdoMappingEntry {
+    /// Map an object with the given index, sub-index and length in bits.
+    pub const fn new(index: u16, sub_index: u8, bit_len: u8) -> Self {
+        Self {
+            index,
+            sub_index,
+            bit_len,
+        }
+    }
+
+    /// Leave a gap of `bit_len` bits in the PDO, e.g. to align the next entry to a byte boundary.
+    pub const fn gap(bit_len: u8) -> Self {
+        Self::new(0, 0, bit_len)
+    }
+
+    /// The value stored in a mapping object sub-index: index in the upper 16 bits, then sub-index,
+    /// then bit length.
+    pub(crate) const fn raw(&self) -> u32 {
+        (self.index as u32) << 16 | (self.sub_index as u32) << 8 | self.bit_len as u32
+    }
+}
+
+/// A PDO mapping object and the entries to map into it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PdoMapping<'a> {
+    pub(crate) index: u16,
+    pub(crate) entries: Option<&'a [PdoMappingEntry]>,
+}
+
+impl<'a> PdoMapping<'a> {
+    /// Map `entries` into the PDO at `index`.
+    ///
+    /// RxPDO indices must be in the range `0x1600..=0x17ff`, and TxPDO indices in
+    /// `0x1a00..=0x1bff`.
+    pub const fn new(index: u16, entries: &'a [PdoMappingEntry]) -> Self {
+        Self {
+            index,
+            entries: Some(entries),
+        }
+    }
+
+    /// Assign the PDO at `index` without writing its mapping.
+    ///
+    /// This is useful for PDOs with a fixed mapping that cannot be changed.
+    pub const fn fixed(index: u16) -> Self {
+        Self {
+            index,
+            entries: None,
+        }
+    }
+
+    /// PDO mapping object index.
+    pub const fn index(&self) -> u16 {
+        self.index
+    }
+}
+
+/// The RxPDOs and TxPDOs to assign to a SubDevice's process data sync managers, written by
+/// [`SubDeviceRef::configure_pdos`](crate::SubDeviceRef::configure_pdos).
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::{PdoAssignment, PdoMapping, PdoMappingEntry};
+///
+/// const OUTPUTS: [PdoMapping; 1] = [PdoMapping::new(
+///     0x1600,
+///     &[
+///         // Control word
+///         PdoMappingEntry::new(0x6040, 0, 16),
+///         // Target velocity
+///         PdoMappingEntry::new(0x60ff, 0, 32),
+///     ],
+/// )];
+///
+/// const INPUTS: [PdoMapping; 1] = [PdoMapping::new(
+///     0x1a00,
+///     &[
+///         // Status word
+///         PdoMappingEntry::new(0x6041, 0, 16),
+///         // Actual position
+///         PdoMappingEntry::new(0x6064, 0, 32),
+///     ],
+/// )];
+///
+/// let assignment = PdoAssignment::new().rx_pdos(&OUTPUTS).tx_pdos(&INPUTS);
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct PdoAssignment<'a> {
+    pub(crate) rx: Option<&'a [PdoMapping<'a>]>,
+    pub(crate) tx: Option<&'a [PdoMapping<'a>]>,
+}
+
+impl<'a> PdoAssignment<'a> {
+    /// Create an assignment that leaves both RxPDOs and TxPDOs unchanged.
+    pub const fn new() -> Self {
+        Self { rx: None, tx: None }
+    }
+
+    /// Replace the RxPDOs (outputs) assigned with object `0x1c12`.
+    pub const fn rx_pdos(self, pdos: &'a [PdoMapping<'a>]) -> Self {
+        Self {
+            rx: Some(pdos),
+            ..self
+        }
+    }
+
+    /// Replace the TxPDOs (inputs) assigned with object `0x1c13`.
+    pub const fn tx_pdos(self, pdos: &'a [PdoMapping<'a>]) -> Self {
+        Self {
+            tx: Some(pdos),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_entry() {
+        assert_eq!(PdoMappingEntry::new(0x6040, 0, 16).raw(), 0x6040_0010);
+        assert_eq!(PdoMappingEntry::new(0x6091, 2, 32).raw(), 0x6091_0220);
+        assert_eq!(PdoMappingEntry::gap(4).raw(), 0x0000_0004);
+    }
+}
@@ -0,0 +1,409 @@
+//! SDO Information service, defined in ETG1000.6 Section 5.6.3.
+
+use super::CoeService;
+use crate::mailbox::{MailboxHeader, MailboxType, Priority};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, WireError};
+
+/// SDO Information service opcode.
+///
+/// Defined in ETG1000.6 Section 5.6.3.2 Table 43 – SDO Info Header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bits = 7)]
+#[repr(u8)]
+pub enum SdoInfoOpCode {
+    /// Get OD List request.
+    GetOdListRequest = 0x01,
+    /// Get OD List response.
+    GetOdListResponse = 0x02,
+    /// Get Object Description request.
+    GetObjectDescriptionRequest = 0x03,
+    /// Get Object Description response.
+    GetObjectDescriptionResponse = 0x04,
+    /// Get Entry Description request.
+    GetEntryDescriptionRequest = 0x05,
+    /// Get Entry Description response.
+    GetEntryDescriptionResponse = 0x06,
+    /// SDO Info error request.
+    ErrorRequest = 0x07,
+}
+
+/// SDO Information header, sent after the CoE header.
+///
+/// Defined in ETG1000.6 Section 5.6.3.2 Table 43 – SDO Info Header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 4)]
+pub struct SdoInfoHeader {
+    #[wire(bits = 7)]
+    pub op_code: SdoInfoOpCode,
+    /// Set if more fragments of the response follow this one.
+    #[wire(bits = 1, post_skip_bytes = 1)]
+    pub incomplete: bool,
+    /// Number of fragments of the response still to come.
+    #[wire(bytes = 2)]
+    pub fragments_left: u16,
+}
+
+/// Mailbox, CoE and SDO Information headers common to every SDO Information request and
+/// response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 12)]
+pub struct SdoInfoHeaders {
+    #[wire(bytes = 8)]
+    pub header: MailboxHeader,
+    #[wire(bytes = 4)]
+    pub sdo_info_header: SdoInfoHeader,
+}
+
+impl SdoInfoHeaders {
+    fn request(counter: u8, op_code: SdoInfoOpCode, data_len: u16) -> Self {
+        Self {
+            header: MailboxHeader {
+                // CoE header, SDO Info header, then service data
+                length: 0x06 + data_len,
+                // address: 0x0000,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Coe,
+                counter,
+                service: CoeService::SdoInformation,
+            },
+            sdo_info_header: SdoInfoHeader {
+                op_code,
+                incomplete: false,
+                fragments_left: 0,
+            },
+        }
+    }
+
+    /// The length of the service data following these headers, as given by the mailbox header.
+    pub fn data_len(&self) -> usize {
+        usize::from(self.header.length.saturating_sub(0x06))
+    }
+}
+
+/// Get OD List request.
+///
+/// Defined in ETG1000.6 Section 5.6.3.3.1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 14)]
+pub struct GetOdListRequest {
+    #[wire(bytes = 12)]
+    pub headers: SdoInfoHeaders,
+    #[wire(bytes = 2)]
+    pub list_type: OdListType,
+}
+
+/// Get Object Description request.
+///
+/// Defined in ETG1000.6 Section 5.6.3.5.1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 14)]
+pub struct GetObjectDescriptionRequest {
+    #[wire(bytes = 12)]
+    pub headers: SdoInfoHeaders,
+    #[wire(bytes = 2)]
+    pub index: u16,
+}
+
+/// Get Entry Description request.
+///
+/// Defined in ETG1000.6 Section 5.6.3.6.1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 16)]
+pub struct GetEntryDescriptionRequest {
+    #[wire(bytes = 12)]
+    pub headers: SdoInfoHeaders,
+    #[wire(bytes = 2)]
+    pub index: u16,
+    #[wire(bytes = 1)]
+    pub sub_index: u8,
+    /// Which optional values (unit, default, min, max) to include in the response. EtherCrab
+    /// doesn't request any of them.
+    #[wire(bytes = 1)]
+    pub value_info: u8,
+}
+
+pub fn get_od_list(counter: u8, list_type: OdListType) -> GetOdListRequest {
+    GetOdListRequest {
+        headers: SdoInfoHeaders::request(counter, SdoInfoOpCode::GetOdListRequest, 2),
+        list_type,
+    }
+}
+
+pub fn get_object_description(counter: u8, index: u16) -> GetObjectDescriptionRequest {
+    GetObjectDescriptionRequest {
+        headers: SdoInfoHeaders::request(counter, SdoInfoOpCode::GetObjectDescriptionRequest, 2),
+        index,
+    }
+}
+
+pub fn get_entry_description(counter: u8, index: u16, sub_index: u8) -> GetEntryDescriptionRequest {
+    GetEntryDescriptionRequest {
+        headers: SdoInfoHeaders::request(counter, SdoInfoOpCode::GetEntryDescriptionRequest, 4),
+        index,
+        sub_index,
+        value_info: 0,
+    }
+}
+
+/// The list of objects to request with
+/// [`SubDeviceRef::sdo_info_object_list`](crate::SubDeviceRef::sdo_info_object_list).
+///
+/// Defined in ETG1000.6 Section 5.6.3.3.1 Table 44 – Get OD List Request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u16)]
+pub enum OdListType {
+    /// All objects in the object dictionary.
+    All = 0x01,
+    /// Objects which can be mapped into an RxPDO.
+    RxPdoMappable = 0x02,
+    /// Objects which can be mapped into a TxPDO.
+    TxPdoMappable = 0x03,
+    /// Objects which should be stored for a device replacement.
+    Backup = 0x04,
+    /// Objects which can be used as startup parameters.
+    Settings = 0x05,
+}
+
+/// Object code of an object dictionary entry.
+///
+/// Defined in ETG1000.6 Section 5.6.3.5.2 Table 47 – Get Object Description Response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ObjectCode {
+    /// A single value.
+    Variable = 0x07,
+    /// Multiple values of the same data type.
+    Array = 0x08,
+    /// Multiple values of possibly different data types.
+    Record = 0x09,
+    /// Unknown object code.
+    #[wire(catch_all)]
+    Unknown(u8),
+}
+
+/// The description of an object in a SubDevice's object dictionary.
+///
+/// Returned by
+/// [`SubDeviceRef::sdo_info_object_description`](crate::SubDeviceRef::sdo_info_object_description).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectDescription {
+    /// Object index.
+    pub index: u16,
+    /// Data type index, e.g. `0x0007` for `UNSIGNED32`. Defined in ETG1000.6 Table 64.
+    pub data_type: u16,
+    /// The highest sub-index of this object.
+    pub max_sub_index: u8,
+    /// Whether this object is a single variable, an array or a record.
+    pub object_code: ObjectCode,
+    /// Object name. Names longer than 64 bytes are truncated.
+    pub name: heapless::String<64>,
+}
+
+impl EtherCrabWireRead for ObjectDescription {
+    fn unpack_from_slice(buf: &[u8]) -> Result<Self, WireError> {
+        let fields = buf.get(0..6).ok_or(WireError::ReadBufferTooShort)?;
+        let name = &buf[6..];
+
+        Ok(Self {
+            index: u16::unpack_from_slice(&fields[0..2])?,
+            data_type: u16::unpack_from_slice(&fields[2..4])?,
+            max_sub_index: fields[4],
+            object_code: ObjectCode::unpack_from_slice(&fields[5..6])?,
+            name: truncated_name(name),
+        })
+    }
+}
+
+bitflags::bitflags! {
+    /// Access rights and mapping flags of an object dictionary entry.
+    ///
+    /// Defined in ETG1000.6 Section 5.6.3.6.2 Table 49 – Get Entry Description Response.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct ObjectAccess: u16 {
+        /// Readable in PRE-OP.
+        const READ_PRE_OP = 1 << 0;
+        /// Readable in SAFE-OP.
+        const READ_SAFE_OP = 1 << 1;
+        /// Readable in OP.
+        const READ_OP = 1 << 2;
+        /// Writable in PRE-OP.
+        const WRITE_PRE_OP = 1 << 3;
+        /// Writable in SAFE-OP.
+        const WRITE_SAFE_OP = 1 << 4;
+        /// Writable in OP.
+        const WRITE_OP = 1 << 5;
+        /// Can be mapped into an RxPDO.
+        const RX_PDO_MAPPABLE = 1 << 6;
+        /// Can be mapped into a TxPDO.
+        const TX_PDO_MAPPABLE = 1 << 7;
+        /// Should be stored for a device replacement.
+        const BACKUP = 1 << 8;
+        /// Can be used as a startup parameter.
+        const SETTINGS = 1 << 9;
+    }
+}
+
+impl EtherCrabWireSized for ObjectAccess {
+    const PACKED_LEN: usize = 2;
+
+    type Buffer = [u8; Self::PACKED_LEN];
+
+    fn buffer() -> Self::Buffer {
+        [0u8; Self::PACKED_LEN]
+    }
+}
+
+impl EtherCrabWireRead for ObjectAccess {
+    fn unpack_from_slice(buf: &[u8]) -> Result<Self, WireError> {
+        u16::unpack_from_slice(buf).map(Self::from_bits_retain)
+    }
+}
+
+/// The description of a single sub-index of an object in a SubDevice's object dictionary.
+///
+/// Returned by
+/// [`SubDeviceRef::sdo_info_entry_description`](crate::SubDeviceRef::sdo_info_entry_description).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryDescription {
+    /// Object index.
+    pub index: u16,
+    /// Sub-index of the object.
+    pub sub_index: u8,
+    /// Data type index, e.g. `0x0007` for `UNSIGNED32`. Defined in ETG1000.6 Table 64.
+    pub data_type: u16,
+    /// Length of the entry in bits.
+    pub bit_len: u16,
+    /// Access rights and PDO mapping flags.
+    pub access: ObjectAccess,
+    /// Entry name. Names longer than 64 bytes are truncated.
+    pub name: heapless::String<64>,
+}
+
+impl EntryDescription {
+    /// Whether this entry can be mapped into either an RxPDO or a TxPDO.
+    pub fn is_pdo_mappable(&self) -> bool {
+        self.access
+            .intersects(ObjectAccess::RX_PDO_MAPPABLE | ObjectAccess::TX_PDO_MAPPABLE)
+    }
+}
+
+impl EtherCrabWireRead for EntryDescription {
+    fn unpack_from_slice(buf: &[u8]) -> Result<Self, WireError> {
+        // NOTE: Byte 3 is the value info, which is always 0 in EtherCrab's requests so no unit,
+        // default, minimum or maximum value is present between the access flags and the name.
+        let fields = buf.get(0..10).ok_or(WireError::ReadBufferTooShort)?;
+        let name = &buf[10..];
+
+        Ok(Self {
+            index: u16::unpack_from_slice(&fields[0..2])?,
+            sub_index: fields[2],
+            data_type: u16::unpack_from_slice(&fields[4..6])?,
+            bit_len: u16::unpack_from_slice(&fields[6..8])?,
+            access: ObjectAccess::unpack_from_slice(&fields[8..10])?,
+            name: truncated_name(name),
+        })
+    }
+}
+
+/// Decode a name, truncating it to fit in `N` bytes and removing any trailing null bytes.
+fn truncated_name<const N: usize>(buf: &[u8]) -> heapless::String<N> {
+    let buf = &buf[0..buf.len().min(N)];
+
+    let s = match core::str::from_utf8(buf) {
+        Ok(s) => s,
+        // A multibyte character may have been cut off by the truncation above
+        Err(e) => core::str::from_utf8(&buf[0..e.valid_up_to()]).unwrap_or_default(),
+    };
+
+    let mut name = heapless::String::new();
+
+    // Infallible as the input is no longer than N bytes
+    let _ = name.push_str(s.trim_end_matches('\0'));
+
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireWriteSized;
+
+    #[test]
+    fn encode_get_od_list() {
+        assert_eq!(
+            get_od_list(2, OdListType::All).pack(),
+            [0x08, 0x00, 0x00, 0x00, 0x00, 0x23, 0x00, 0x80, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn encode_get_entry_description() {
+        assert_eq!(
+            get_entry_description(5, 0x1c12, 1).pack(),
+            [
+                0x0a, 0x00, 0x00, 0x00, 0x00, 0x53, 0x00, 0x80, 0x05, 0x00, 0x00, 0x00, 0x12, 0x1c,
+                0x01, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_incomplete_header() {
+        assert_eq!(
+            SdoInfoHeader::unpack_from_slice(&[0x82, 0x00, 0x03, 0x00]),
+            Ok(SdoInfoHeader {
+                op_code: SdoInfoOpCode::GetOdListResponse,
+                incomplete: true,
+                fragments_left: 3
+            })
+        );
+    }
+
+    #[test]
+    fn decode_object_description() {
+        let raw = [
+            0x12, 0x1c, 0x06, 0x00, 0x02, 0x08, b'R', b'x', b'P', b'D', b'O', b' ', b'a', b's',
+            b's', b'i', b'g', b'n', 0x00,
+        ];
+
+        assert_eq!(
+            ObjectDescription::unpack_from_slice(&raw),
+            Ok(ObjectDescription {
+                index: 0x1c12,
+                data_type: 0x0006,
+                max_sub_index: 2,
+                object_code: ObjectCode::Array,
+                name: "RxPDO assign".try_into().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn decode_entry_description() {
+        let raw = [
+            0x00, 0x70, 0x01, 0x00, 0x07, 0x00, 0x20, 0x00, 0x47, 0x00, b'T', b'a', b'r', b'g',
+            b'e', b't',
+        ];
+
+        let desc = EntryDescription::unpack_from_slice(&raw).unwrap();
+
+        assert_eq!(
+            desc,
+            EntryDescription {
+                index: 0x7000,
+                sub_index: 1,
+                data_type: 0x0007,
+                bit_len: 32,
+                access: ObjectAccess::READ_PRE_OP
+                    | ObjectAccess::READ_SAFE_OP
+                    | ObjectAccess::READ_OP
+                    | ObjectAccess::RX_PDO_MAPPABLE,
+                name: "Target".try_into().unwrap()
+            }
+        );
+        assert!(desc.is_pdo_mappable());
+    }
+}
@@ -138,6 +138,70 @@ pub fn download(
     }
 }
 
+/// A normal (non-expedited) SDO download request.
+///
+/// The request is followed in the mailbox by the `u32` complete size of the data being downloaded,
+/// then `chunk_len` bytes of data. If `chunk_len` is smaller than the complete size, the remaining
+/// data must be sent with [`download_segmented`] requests.
+///
+/// Complete access downloads start at sub-index 0, so the data must begin with the number of
+/// sub-indices, padded to 16 bits.
+pub fn download_normal(counter: u8, index: u16, access: SubIndex, chunk_len: u16) -> SdoNormal {
+    SdoNormal {
+        header: MailboxHeader {
+            // CoE header, SDO header and complete size, followed by the data itself
+            length: 0x0a + chunk_len,
+            // address: 0x0000,
+            priority: Priority::Lowest,
+            mailbox_type: MailboxType::Coe,
+            counter,
+            service: CoeService::SdoRequest,
+        },
+        sdo_header: InitSdoHeader {
+            size_indicator: true,
+            expedited_transfer: false,
+            size: 0,
+            complete_access: access.complete_access(),
+            command: super::CoeCommand::Download,
+            index,
+            sub_index: if access.complete_access() {
+                0
+            } else {
+                access.sub_index()
+            },
+        },
+    }
+}
+
+/// A download segment request, followed in the mailbox by `chunk_len` bytes of data.
+///
+/// Segments shorter than 7 bytes must be padded to 7 bytes, with the number of unused bytes given
+/// in the segment header. This is defined in ETG1000.6 Section 5.6.2.3.1.
+pub fn download_segmented(
+    counter: u8,
+    toggle: bool,
+    is_last_segment: bool,
+    chunk_len: u16,
+) -> SdoSegmented {
+    SdoSegmented {
+        header: MailboxHeader {
+            // CoE header and segment header, followed by at least 7 bytes of data
+            length: 0x03 + chunk_len.max(7),
+            // address: 0x0000,
+            priority: Priority::Lowest,
+            mailbox_type: MailboxType::Coe,
+            counter,
+            service: CoeService::SdoRequest,
+        },
+        sdo_header: SegmentSdoHeader {
+            is_last_segment,
+            segment_data_size: 7u16.saturating_sub(chunk_len) as u8,
+            toggle,
+            command: super::CoeCommand::DownloadSegment,
+        },
+    }
+}
+
 pub fn upload_segmented(counter: u8, toggle: bool) -> SdoSegmented {
     SdoSegmented {
         header: MailboxHeader {
@@ -184,7 +248,9 @@ pub fn upload(counter: u8, index: u16, access: SubIndex) -> SdoNormal {
 mod tests {
     use super::*;
     use crate::error::CoeAbortCode;
-    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+    use ethercrab_wire::{
+        EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite, EtherCrabWireWriteSized,
+    };
 
     #[test]
     fn decode_sdo_response_normal() {
@@ -280,6 +346,84 @@ mod tests {
         )
     }
 
+    #[test]
+    fn encode_sdo_request_normal() {
+        let request = download_normal(3, 0x1c32, 0.into(), 32);
+
+        pretty_assertions::assert_eq!(
+            request,
+            SdoNormal {
+                header: MailboxHeader {
+                    length: 42,
+                    // address: 0,
+                    priority: Priority::Lowest,
+                    mailbox_type: MailboxType::Coe,
+                    counter: 3,
+                    service: CoeService::SdoRequest,
+                },
+                sdo_header: InitSdoHeader {
+                    size_indicator: true,
+                    expedited_transfer: false,
+                    size: 0,
+                    complete_access: false,
+                    command: crate::coe::CoeCommand::Download,
+                    index: 0x1c32,
+                    sub_index: 0,
+                },
+            }
+        );
+
+        // Wireshark: "SDO Req : 'Initiate Download' Idx=0x1c32 Sub=0", 32 bytes
+        assert_eq!(
+            request.pack(),
+            [0x2a, 0x00, 0x00, 0x00, 0x00, 0x33, 0x00, 0x20, 0x21, 0x32, 0x1c, 0x00]
+        );
+    }
+
+    #[test]
+    fn encode_sdo_request_normal_complete() {
+        let request = download_normal(3, 0x1c12, SubIndex::Complete, 6);
+
+        assert_eq!(request.sdo_header.sub_index, 0);
+        assert_eq!(
+            request.pack(),
+            [0x10, 0x00, 0x00, 0x00, 0x00, 0x33, 0x00, 0x20, 0x31, 0x12, 0x1c, 0x00]
+        );
+    }
+
+    #[test]
+    fn encode_sdo_request_segment() {
+        let request = download_segmented(4, true, false, 100);
+
+        assert_eq!(request.header.length, 103);
+        assert_eq!(request.sdo_header.segment_data_size, 0);
+        assert_eq!(
+            request.pack(),
+            [0x67, 0x00, 0x00, 0x00, 0x00, 0x43, 0x00, 0x20, 0x10]
+        );
+    }
+
+    #[test]
+    fn encode_sdo_request_short_last_segment() {
+        // 3 bytes of data means 4 bytes of padding up to the minimum segment size
+        let request = download_segmented(5, false, true, 3);
+
+        assert_eq!(request.header.length, 10);
+        assert_eq!(
+            request.sdo_header,
+            SegmentSdoHeader {
+                is_last_segment: true,
+                segment_data_size: 4,
+                toggle: false,
+                command: crate::coe::CoeCommand::DownloadSegment,
+            }
+        );
+        assert_eq!(
+            request.pack(),
+            [0x0a, 0x00, 0x00, 0x00, 0x00, 0x53, 0x00, 0x20, 0x09]
+        );
+    }
+
     #[test]
     fn upload_request_normal() {
         let request = upload(210, 0x4567, 2.into());
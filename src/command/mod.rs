@@ -224,6 +224,11 @@ impl Command {
         WrappedWrite::new(Writes::Lrw { address })
     }
 
+    /// Logical Read (LRD).
+    pub fn lrd(address: u32) -> WrappedRead {
+        WrappedRead::new(Reads::Lrd { address })
+    }
+
     /// Logical Write (LWR).
     pub fn lwr(address: u32) -> WrappedWrite {
         WrappedWrite::new(Writes::Lwr { address })
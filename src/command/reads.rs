@@ -59,6 +59,8 @@ pub struct WrappedRead {
     pub command: Reads,
     /// Expected working counter.
     wkc: Option<u16>,
+    /// Whether this PDU is part of a mailbox transaction.
+    mailbox: bool,
 }
 
 impl WrappedRead {
@@ -66,6 +68,17 @@ impl WrappedRead {
         Self {
             command,
             wkc: Some(1),
+            mailbox: false,
+        }
+    }
+
+    /// Mark this PDU as part of a mailbox transaction so it can be sent with cyclic process data.
+    ///
+    /// See [`MailboxScheduling`](crate::MailboxScheduling).
+    pub(crate) fn mailbox(self) -> Self {
+        Self {
+            mailbox: true,
+            ..self
         }
     }
 
@@ -132,6 +145,6 @@ impl WrappedRead {
         maindevice: &'maindevice MainDevice<'maindevice>,
         len: u16,
     ) -> impl core::future::Future<Output = Result<ReceivedPdu<'maindevice>, Error>> {
-        maindevice.single_pdu(self.command.into(), (), Some(len))
+        maindevice.single_pdu(self.command.into(), (), Some(len), self.mailbox)
     }
 }
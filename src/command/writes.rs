@@ -52,6 +52,8 @@ pub struct WrappedWrite {
     /// Expected working counter.
     wkc: Option<u16>,
     len_override: Option<u16>,
+    /// Whether this PDU is part of a mailbox transaction.
+    mailbox: bool,
 }
 
 impl WrappedWrite {
@@ -60,6 +62,17 @@ impl WrappedWrite {
             command,
             wkc: Some(1),
             len_override: None,
+            mailbox: false,
+        }
+    }
+
+    /// Mark this PDU as part of a mailbox transaction so it can be sent with cyclic process data.
+    ///
+    /// See [`MailboxScheduling`](crate::MailboxScheduling).
+    pub(crate) fn mailbox(self) -> Self {
+        Self {
+            mailbox: true,
+            ..self
         }
     }
 
@@ -133,6 +146,6 @@ impl WrappedWrite {
         value: impl EtherCrabWireWrite,
         len_override: Option<u16>,
     ) -> impl core::future::Future<Output = Result<ReceivedPdu<'maindevice>, Error>> {
-        maindevice.single_pdu(self.command.into(), value, len_override)
+        maindevice.single_pdu(self.command.into(), value, len_override, self.mailbox)
     }
 }
@@ -773,6 +773,56 @@ impl core::fmt::Debug for DefaultMailbox {
     }
 }
 
+/// Bootstrap mailbox configuration, used in BOOT state.
+///
+/// Defined in ETG2010 Table 2 at word address `0x0014`.
+#[derive(Copy, Clone, Default, PartialEq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 8)]
+pub struct BootstrapMailbox {
+    /// Master to SubDevice receive mailbox address offset.
+    #[wire(bytes = 2)]
+    pub subdevice_receive_offset: u16,
+    /// Master to SubDevice receive mailbox size.
+    #[wire(bytes = 2)]
+    pub subdevice_receive_size: u16,
+    /// SubDevice to master send mailbox address offset.
+    #[wire(bytes = 2)]
+    pub subdevice_send_offset: u16,
+    /// SubDevice to master send mailbox size.
+    #[wire(bytes = 2)]
+    pub subdevice_send_size: u16,
+}
+
+impl BootstrapMailbox {
+    pub fn has_mailbox(&self) -> bool {
+        self.subdevice_receive_size > 0 && self.subdevice_send_size > 0
+    }
+}
+
+impl core::fmt::Debug for BootstrapMailbox {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BootstrapMailbox")
+            .field(
+                "subdevice_receive_offset",
+                &format_args!("{:#06x}", self.subdevice_receive_offset),
+            )
+            .field(
+                "subdevice_receive_size",
+                &format_args!("{:#06x}", self.subdevice_receive_size),
+            )
+            .field(
+                "subdevice_send_offset",
+                &format_args!("{:#06x}", self.subdevice_send_offset),
+            )
+            .field(
+                "subdevice_send_size",
+                &format_args!("{:#06x}", self.subdevice_send_size),
+            )
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
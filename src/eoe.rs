@@ -0,0 +1,298 @@
+//! Ethernet over EtherCAT (EoE), defined in ETG1000.6 Section 5.7.
+
+use core::net::Ipv4Addr;
+
+/// EoE fragments other than the last must have a length that is a multiple of this value.
+///
+/// The offset of each fragment in the complete frame is also given in units of this many bytes.
+pub const FRAGMENT_ALIGN: usize = 32;
+
+/// EoE frame type.
+///
+/// Defined in ETG1000.6 Section 5.7.2 Table 75 – EoE Fragment Request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum EoeFrameType {
+    /// Ethernet frame fragment.
+    FragmentData = 0x00,
+    /// Timestamp response.
+    TimestampResponse = 0x01,
+    /// Set IP parameter request.
+    SetIpParameterRequest = 0x02,
+    /// Set IP parameter response.
+    SetIpParameterResponse = 0x03,
+    /// Set MAC address filter request.
+    SetAddressFilterRequest = 0x04,
+    /// Set MAC address filter response.
+    SetAddressFilterResponse = 0x05,
+    /// Get IP parameter request.
+    GetIpParameterRequest = 0x06,
+    /// Get IP parameter response.
+    GetIpParameterResponse = 0x07,
+    /// Get MAC address filter request.
+    GetAddressFilterRequest = 0x08,
+    /// Get MAC address filter response.
+    GetAddressFilterResponse = 0x09,
+}
+
+/// EoE header, sent after the mailbox header.
+///
+/// The meaning of the last 16 bits depends on the frame type. For [`EoeFrameType::FragmentData`]
+/// they hold the fragment number, offset and frame number. For response frame types they hold an
+/// [`EoeResult`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 4)]
+pub struct EoeHeader {
+    #[wire(bits = 4)]
+    pub frame_type: EoeFrameType,
+    /// Always zero.
+    #[wire(bits = 4)]
+    pub port: u8,
+    #[wire(bits = 1)]
+    pub last_fragment: bool,
+    #[wire(bits = 1)]
+    pub time_appended: bool,
+    #[wire(bits = 1, post_skip = 5)]
+    pub time_request: bool,
+    #[wire(bytes = 2)]
+    pub fragment_info: u16,
+}
+
+impl EoeHeader {
+    /// Create a fragment header.
+    ///
+    /// For the first fragment, `offset` is the length of the complete frame in units of
+    /// [`FRAGMENT_ALIGN`] bytes, rounded up. For later fragments, it is the offset of the fragment
+    /// in the complete frame in the same units.
+    pub fn fragment(
+        frame_number: u8,
+        fragment_number: u8,
+        offset: u8,
+        last_fragment: bool,
+    ) -> Self {
+        Self {
+            frame_type: EoeFrameType::FragmentData,
+            port: 0,
+            last_fragment,
+            time_appended: false,
+            time_request: false,
+            fragment_info: u16::from(fragment_number & 0x3f)
+                | u16::from(offset & 0x3f) << 6
+                | u16::from(frame_number & 0x0f) << 12,
+        }
+    }
+
+    pub fn set_ip_parameter_request() -> Self {
+        Self {
+            frame_type: EoeFrameType::SetIpParameterRequest,
+            port: 0,
+            last_fragment: true,
+            time_appended: false,
+            time_request: false,
+            fragment_info: 0,
+        }
+    }
+
+    /// Fragment number, starting at zero for each new frame.
+    pub fn fragment_number(&self) -> u8 {
+        (self.fragment_info & 0x3f) as u8
+    }
+
+    /// Complete frame length (first fragment) or fragment offset (later fragments) in units of
+    /// [`FRAGMENT_ALIGN`] bytes.
+    pub fn offset(&self) -> u8 {
+        (self.fragment_info >> 6 & 0x3f) as u8
+    }
+
+    /// Frame number, used to associate fragments with a single frame.
+    pub fn frame_number(&self) -> u8 {
+        (self.fragment_info >> 12) as u8
+    }
+
+    /// The result code of a response frame.
+    pub fn result(&self) -> EoeResult {
+        EoeResult::from(self.fragment_info)
+    }
+}
+
+/// The result of an EoE request, sent by the SubDevice in a response frame.
+///
+/// Defined in ETG1000.6 Section 5.7.3 Table 78 – EoE Result Parameter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u16)]
+pub enum EoeResult {
+    /// The request succeeded.
+    Success = 0x0000,
+    /// Unspecified error.
+    UnspecifiedError = 0x0001,
+    /// The frame type is not supported.
+    UnsupportedFrameType = 0x0002,
+    /// IP configuration is not supported.
+    NoIpSupport = 0x0201,
+    /// DHCP is not supported.
+    NoDhcpSupport = 0x0202,
+    /// MAC address filtering is not supported.
+    NoFilterSupport = 0x0401,
+    /// Unknown result code.
+    #[wire(catch_all)]
+    Unknown(u16),
+}
+
+impl core::fmt::Display for EoeResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Success => f.write_str("success"),
+            Self::UnspecifiedError => f.write_str("unspecified error"),
+            Self::UnsupportedFrameType => f.write_str("unsupported frame type"),
+            Self::NoIpSupport => f.write_str("IP configuration not supported"),
+            Self::NoDhcpSupport => f.write_str("DHCP not supported"),
+            Self::NoFilterSupport => f.write_str("address filter not supported"),
+            Self::Unknown(code) => write!(f, "unknown result {:#06x}", code),
+        }
+    }
+}
+
+/// IP parameters to set in a SubDevice with
+/// [`SubDeviceRef::eoe_set_ip_parameters`](crate::SubDeviceRef::eoe_set_ip_parameters).
+///
+/// Only parameters set to `Some` are changed in the SubDevice.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::net::Ipv4Addr;
+/// use ethercrab::IpParameters;
+///
+/// let params = IpParameters {
+///     ip: Some(Ipv4Addr::new(192, 168, 100, 10)),
+///     subnet_mask: Some(Ipv4Addr::new(255, 255, 255, 0)),
+///     ..IpParameters::default()
+/// };
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IpParameters {
+    /// SubDevice MAC address.
+    pub mac_address: Option<[u8; 6]>,
+    /// SubDevice IP address.
+    pub ip: Option<Ipv4Addr>,
+    /// Subnet mask.
+    pub subnet_mask: Option<Ipv4Addr>,
+    /// Default gateway.
+    pub default_gateway: Option<Ipv4Addr>,
+    /// DNS server IP address.
+    pub dns_server: Option<Ipv4Addr>,
+    /// DNS name of the SubDevice, up to 32 bytes long.
+    pub dns_name: Option<heapless::String<32>>,
+}
+
+/// Set IP Parameter request body.
+///
+/// Defined in ETG1000.6 Section 5.7.3 Table 77 – EoE Init Request. Every parameter has a fixed
+/// position in the request, and is left as zero if not included. IP addresses are sent with their
+/// octets in reverse order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireWrite)]
+#[wire(bytes = 58)]
+pub struct SetIpParameterRequest {
+    /// Flags marking which parameters are included in the request.
+    #[wire(bytes = 4)]
+    pub flags: u32,
+    #[wire(bytes = 6)]
+    pub mac_address: [u8; 6],
+    #[wire(bytes = 4)]
+    pub ip: u32,
+    #[wire(bytes = 4)]
+    pub subnet_mask: u32,
+    #[wire(bytes = 4)]
+    pub default_gateway: u32,
+    #[wire(bytes = 4)]
+    pub dns_server: u32,
+    #[wire(bytes = 32)]
+    pub dns_name: [u8; 32],
+}
+
+impl From<&IpParameters> for SetIpParameterRequest {
+    fn from(params: &IpParameters) -> Self {
+        let flags = u32::from(params.mac_address.is_some())
+            | u32::from(params.ip.is_some()) << 1
+            | u32::from(params.subnet_mask.is_some()) << 2
+            | u32::from(params.default_gateway.is_some()) << 3
+            | u32::from(params.dns_server.is_some()) << 4
+            | u32::from(params.dns_name.is_some()) << 5;
+
+        let mut dns_name = [0u8; 32];
+
+        if let Some(name) = &params.dns_name {
+            dns_name[0..name.len()].copy_from_slice(name.as_bytes());
+        }
+
+        Self {
+            flags,
+            mac_address: params.mac_address.unwrap_or_default(),
+            ip: params.ip.map(u32::from).unwrap_or(0),
+            subnet_mask: params.subnet_mask.map(u32::from).unwrap_or(0),
+            default_gateway: params.default_gateway.map(u32::from).unwrap_or(0),
+            dns_server: params.dns_server.map(u32::from).unwrap_or(0),
+            dns_name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWriteSized};
+
+    #[test]
+    fn encode_first_fragment() {
+        // 100 byte frame in two fragments, frame number 5
+        assert_eq!(
+            EoeHeader::fragment(5, 0, 4, false).pack(),
+            [0x00, 0x00, 0x00, 0x51]
+        );
+    }
+
+    #[test]
+    fn encode_last_fragment() {
+        assert_eq!(
+            EoeHeader::fragment(5, 1, 2, true).pack(),
+            [0x00, 0x01, 0x81, 0x50]
+        );
+    }
+
+    #[test]
+    fn decode_fragment() {
+        assert_eq!(
+            EoeHeader::unpack_from_slice(&[0x00, 0x01, 0xc3, 0xa1]),
+            Ok(EoeHeader::fragment(0x0a, 0x03, 0x07, true))
+        );
+    }
+
+    #[test]
+    fn decode_set_ip_response() {
+        let header = EoeHeader::unpack_from_slice(&[0x03, 0x01, 0x01, 0x02]).unwrap();
+
+        assert_eq!(header.frame_type, EoeFrameType::SetIpParameterResponse);
+        assert_eq!(header.result(), EoeResult::NoIpSupport);
+    }
+
+    #[test]
+    fn encode_ip_parameters() {
+        let params = IpParameters {
+            ip: Some(Ipv4Addr::new(192, 168, 1, 10)),
+            subnet_mask: Some(Ipv4Addr::new(255, 255, 255, 0)),
+            dns_name: Some(heapless::String::try_from("drive").unwrap()),
+            ..IpParameters::default()
+        };
+
+        let mut expected = [0u8; 58];
+        expected[0] = 0b0010_0110;
+        expected[10..14].copy_from_slice(&[10, 1, 168, 192]);
+        expected[14..18].copy_from_slice(&[0, 255, 255, 255]);
+        expected[26..31].copy_from_slice(b"drive");
+
+        assert_eq!(SetIpParameterRequest::from(&params).pack(), expected);
+    }
+}
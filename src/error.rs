@@ -1,6 +1,11 @@
 //! EtherCrab error types.
 
+pub use crate::aoe::AdsErrorCode;
 pub use crate::coe::abort_code::CoeAbortCode;
+pub use crate::eoe::EoeResult;
+pub use crate::foe::FoeErrorCode;
+pub use crate::mailbox::MailboxErrorCode;
+pub use crate::soe::SoeErrorCode;
 use crate::{command::Command, fmt, AlStatusCode, SubDeviceState};
 use core::{cell::BorrowError, num::TryFromIntError};
 
@@ -97,6 +102,9 @@ pub enum Error {
 
     /// A distributed clock error occurred.
     DistributedClock(DistributedClockError),
+
+    /// A PDO assignment or mapping error occurred.
+    Pdo(PdoError),
 }
 
 #[cfg(feature = "std")]
@@ -157,6 +165,7 @@ impl core::fmt::Display for Error {
             Error::Wire(e) => write!(f, "wire encode/decode error: {}", e),
             Error::SubDevice(e) => write!(f, "subdevice error: {}", e),
             Error::DistributedClock(e) => write!(f, "distributed clock: {}", e),
+            Error::Pdo(e) => write!(f, "pdo: {}", e),
         }
     }
 }
@@ -252,10 +261,46 @@ impl core::fmt::Display for DistributedClockError {
     }
 }
 
-/// CoE mailbox error.
+/// PDO assignment or mapping error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PdoError {
+    /// A PDO mapping object index is outside the range for its direction.
+    InvalidIndex {
+        /// The PDO mapping object index.
+        index: u16,
+    },
+    /// A value read back from a PDO assignment or mapping object does not match the value written
+    /// to it.
+    Verify {
+        /// The index of the object.
+        index: u16,
+        /// The subindex of the object.
+        sub_index: u8,
+    },
+}
+
+impl core::fmt::Display for PdoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PdoError::InvalidIndex { index } => {
+                write!(f, "{:#06x} is not a valid PDO mapping index", index)
+            }
+            PdoError::Verify { index, sub_index } => write!(
+                f,
+                "{:#06x}:{} read back a different value to the one written",
+                index, sub_index
+            ),
+        }
+    }
+}
+
+/// Mailbox error.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
 pub enum MailboxError {
     /// The mailbox operation was aborted.
     Aborted {
@@ -282,6 +327,24 @@ pub enum MailboxError {
         /// The subindex used in the operation.
         sub_index: u8,
     },
+    /// The SubDevice replied with a mailbox error instead of a response.
+    ErrorReply(MailboxErrorCode),
+    /// The SubDevice replied with a mailbox of a different type to the request.
+    UnexpectedResponse,
+    /// An FoE (File access over EtherCAT) error.
+    Foe(FoeError),
+    /// An EoE (Ethernet over EtherCAT) error.
+    Eoe(EoeError),
+    /// An SoE (Servo drive profile over EtherCAT) error.
+    Soe(SoeError),
+    /// An AoE (ADS over EtherCAT) error.
+    Aoe(AoeError),
+    /// A whole object was written with [`SubIndex::Complete`](crate::SubIndex::Complete), but the
+    /// SubDevice does not support Complete Access. Nothing was sent to the SubDevice.
+    NoCompleteAccess {
+        /// The address used in the operation.
+        address: u16,
+    },
 }
 
 impl core::fmt::Display for MailboxError {
@@ -303,6 +366,123 @@ impl core::fmt::Display for MailboxError {
                 "{:#06x}:{} invalid response from device",
                 address, sub_index
             ),
+            MailboxError::ErrorReply(code) => write!(f, "mailbox error reply: {}", code),
+            MailboxError::UnexpectedResponse => f.write_str("unexpected response type from device"),
+            MailboxError::Foe(e) => write!(f, "foe: {}", e),
+            MailboxError::Eoe(e) => write!(f, "eoe: {}", e),
+            MailboxError::Soe(e) => write!(f, "soe: {}", e),
+            MailboxError::Aoe(e) => write!(f, "aoe: {}", e),
+            MailboxError::NoCompleteAccess { address } => write!(
+                f,
+                "{:#06x} complete access is not supported by device",
+                address
+            ),
+        }
+    }
+}
+
+/// FoE (File access over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FoeError {
+    /// The SubDevice aborted the transfer with an error.
+    Aborted(FoeErrorCode),
+    /// A data or acknowledge request was received with the wrong packet number.
+    PacketNumber {
+        /// The expected packet number.
+        expected: u32,
+        /// The packet number received from the SubDevice.
+        received: u32,
+    },
+    /// The SubDevice sent an FoE request that is not valid at this point in the transfer.
+    UnexpectedOpCode,
+    /// Reading the file data to send, or writing the received file data, failed.
+    Io,
+}
+
+impl core::fmt::Display for FoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FoeError::Aborted(code) => write!(f, "aborted: {}", code),
+            FoeError::PacketNumber { expected, received } => {
+                write!(f, "expected packet number {}, got {}", expected, received)
+            }
+            FoeError::UnexpectedOpCode => f.write_str("unexpected opcode"),
+            FoeError::Io => f.write_str("file data read/write failed"),
+        }
+    }
+}
+
+/// EoE (Ethernet over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum EoeError {
+    /// The SubDevice rejected a request.
+    Rejected(EoeResult),
+    /// A received Ethernet frame is too long to fit in the given buffer.
+    FrameTooLong,
+    /// A fragment was received out of order, or with the wrong frame number or offset.
+    Fragment,
+}
+
+impl core::fmt::Display for EoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EoeError::Rejected(result) => write!(f, "request rejected: {}", result),
+            EoeError::FrameTooLong => f.write_str("received frame too long for buffer"),
+            EoeError::Fragment => f.write_str("invalid fragment sequence"),
+        }
+    }
+}
+
+/// SoE (Servo drive profile over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SoeError {
+    /// The SubDevice responded with an error.
+    Aborted {
+        /// Error code.
+        code: SoeErrorCode,
+        /// The IDN used in the operation.
+        idn: u16,
+    },
+    /// The data read from the SubDevice is too long to fit in the given buffer or type.
+    TooLong {
+        /// The IDN used in the operation.
+        idn: u16,
+    },
+}
+
+impl core::fmt::Display for SoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SoeError::Aborted { code, idn } => write!(f, "IDN {:#06x} aborted: {}", idn, code),
+            SoeError::TooLong { idn } => {
+                write!(f, "IDN {:#06x} returned data is too long", idn)
+            }
+        }
+    }
+}
+
+/// AoE (ADS over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AoeError {
+    /// The ADS request failed with the given error code.
+    Ads(AdsErrorCode),
+    /// The data read from the SubDevice is too long to fit in the given buffer.
+    TooLong,
+}
+
+impl core::fmt::Display for AoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AoeError::Ads(code) => write!(f, "ADS error: {}", code),
+            AoeError::TooLong => f.write_str("returned data is too long"),
         }
     }
 }
@@ -420,6 +600,12 @@ impl From<DistributedClockError> for Error {
     }
 }
 
+impl From<PdoError> for Error {
+    fn from(e: PdoError) -> Self {
+        Self::Pdo(e)
+    }
+}
+
 impl From<PduValidationError> for PduError {
     fn from(e: PduValidationError) -> Self {
         Self::Validation(e)
@@ -0,0 +1,163 @@
+//! File access over EtherCAT (FoE), defined in ETG1000.6 Section 5.9.
+
+/// FoE opcode.
+///
+/// Defined in ETG1000.6 Section 5.9.2 Table 90 – Read Request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum FoeOpCode {
+    /// Read request.
+    ReadRequest = 0x01,
+    /// Write request.
+    WriteRequest = 0x02,
+    /// Data request.
+    Data = 0x03,
+    /// Acknowledge request.
+    Ack = 0x04,
+    /// Error request.
+    ErrorRequest = 0x05,
+    /// Busy request.
+    Busy = 0x06,
+}
+
+/// FoE header, sent after the mailbox header.
+///
+/// The meaning of `value` depends on the opcode:
+///
+/// - Read/write requests: password.
+/// - Data and acknowledge requests: packet number.
+/// - Error requests: [`FoeErrorCode`].
+/// - Busy requests: progress done in the lower 16 bits, progress total in the upper 16 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 6)]
+pub struct FoeHeader {
+    #[wire(bytes = 1, post_skip_bytes = 1)]
+    pub op_code: FoeOpCode,
+    #[wire(bytes = 4)]
+    pub value: u32,
+}
+
+impl FoeHeader {
+    pub fn read_request(password: u32) -> Self {
+        Self {
+            op_code: FoeOpCode::ReadRequest,
+            value: password,
+        }
+    }
+
+    pub fn write_request(password: u32) -> Self {
+        Self {
+            op_code: FoeOpCode::WriteRequest,
+            value: password,
+        }
+    }
+
+    pub fn data(packet_number: u32) -> Self {
+        Self {
+            op_code: FoeOpCode::Data,
+            value: packet_number,
+        }
+    }
+
+    pub fn ack(packet_number: u32) -> Self {
+        Self {
+            op_code: FoeOpCode::Ack,
+            value: packet_number,
+        }
+    }
+}
+
+/// FoE error code sent by a SubDevice.
+///
+/// Defined in ETG1000.6 Section 5.9.3 Table 92 – Error codes of FoE.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u32)]
+pub enum FoeErrorCode {
+    /// Not defined.
+    NotDefined = 0x8000,
+    /// File not found.
+    NotFound = 0x8001,
+    /// Access denied.
+    AccessDenied = 0x8002,
+    /// Disk full.
+    DiskFull = 0x8003,
+    /// Illegal operation.
+    Illegal = 0x8004,
+    /// Packet number wrong.
+    PacketNumberWrong = 0x8005,
+    /// File already exists.
+    AlreadyExists = 0x8006,
+    /// No user.
+    NoUser = 0x8007,
+    /// Only allowed in BOOT state.
+    BootstrapOnly = 0x8008,
+    /// Not allowed in BOOT state.
+    NotBootstrap = 0x8009,
+    /// No rights, e.g. wrong password.
+    NoRights = 0x800a,
+    /// Program error.
+    ProgramError = 0x800b,
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u32),
+}
+
+impl core::fmt::Display for FoeErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotDefined => f.write_str("not defined"),
+            Self::NotFound => f.write_str("file not found"),
+            Self::AccessDenied => f.write_str("access denied"),
+            Self::DiskFull => f.write_str("disk full"),
+            Self::Illegal => f.write_str("illegal operation"),
+            Self::PacketNumberWrong => f.write_str("packet number wrong"),
+            Self::AlreadyExists => f.write_str("file already exists"),
+            Self::NoUser => f.write_str("no user"),
+            Self::BootstrapOnly => f.write_str("only allowed in BOOT state"),
+            Self::NotBootstrap => f.write_str("not allowed in BOOT state"),
+            Self::NoRights => f.write_str("no rights"),
+            Self::ProgramError => f.write_str("program error"),
+            Self::Unknown(code) => write!(f, "unknown error {:#010x}", code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWriteSized};
+
+    #[test]
+    fn encode_write_request() {
+        assert_eq!(
+            FoeHeader::write_request(0x1234_5678).pack(),
+            [0x02, 0x00, 0x78, 0x56, 0x34, 0x12]
+        );
+    }
+
+    #[test]
+    fn decode_busy() {
+        assert_eq!(
+            FoeHeader::unpack_from_slice(&[0x06, 0x00, 0x32, 0x00, 0x64, 0x00]),
+            Ok(FoeHeader {
+                op_code: FoeOpCode::Busy,
+                value: 0x0064_0032
+            })
+        );
+    }
+
+    #[test]
+    fn decode_error_code() {
+        assert_eq!(
+            FoeErrorCode::unpack_from_slice(&[0x01, 0x80, 0x00, 0x00]),
+            Ok(FoeErrorCode::NotFound)
+        );
+        assert_eq!(
+            FoeErrorCode::unpack_from_slice(&[0x00, 0x90, 0x00, 0x00]),
+            Ok(FoeErrorCode::Unknown(0x9000))
+        );
+    }
+}
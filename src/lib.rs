@@ -16,6 +16,10 @@
 //! - `log` - enable logging with the [`log`](https://docs.rs/log) crate. This is enabled by default
 //!   when the `std` feature is enabled.
 //! - `serde` - enable `serde` impls for some public items.
+//! - `pdo-entries-16`, `pdo-entries-64`, `pdo-entries-256` - record the position of up to 16, 64 or
+//!   256 PDO entries for each SubDevice, used by
+//!   [`SubDevice::input_entries`](crate::SubDevice::input_entries) and named process variables. The
+//!   largest enabled size is used. If none of these features are enabled, no entries are recorded.
 //!
 //! For `no_std` targets, it is recommended to add this crate with
 //!
@@ -165,6 +169,7 @@ pub(crate) mod fmt;
 
 mod al_control;
 mod al_status_code;
+mod aoe;
 mod base_data_types;
 mod coe;
 mod command;
@@ -172,16 +177,20 @@ mod dc;
 mod dl_status;
 pub mod ds402;
 mod eeprom;
+mod eoe;
 pub mod error;
 mod ethernet;
 mod fmmu;
+mod foe;
 mod generate;
 mod mailbox;
+mod mailbox_scheduler;
 mod maindevice;
 mod maindevice_config;
 mod pdi;
 mod pdu_loop;
 mod register;
+mod soe;
 mod subdevice;
 pub mod subdevice_group;
 mod subdevice_state;
@@ -196,19 +205,34 @@ pub mod internals;
 pub mod std;
 
 pub use al_status_code::AlStatusCode;
-pub use coe::SubIndex;
+pub use aoe::{AdsDeviceState, AdsState, AmsAddress, AmsNetId};
+pub use base_data_types::PrimitiveDataType;
+pub use coe::{
+    emergency::CoeEmergency,
+    pdo_assignment::{PdoAssignment, PdoMapping, PdoMappingEntry},
+    sdo_info::{EntryDescription, ObjectAccess, ObjectCode, ObjectDescription, OdListType},
+    SubIndex,
+};
 pub use command::{Command, Reads, WrappedRead, WrappedWrite, Writes};
+pub use eoe::IpParameters;
 pub use ethercrab_wire::{
     EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireReadWrite, EtherCrabWireSized,
     EtherCrabWireWrite, EtherCrabWireWriteSized,
 };
 use ethernet::EthernetAddress;
+pub use mailbox::MailboxType;
 pub use maindevice::MainDevice;
-pub use maindevice_config::{MainDeviceConfig, RetryBehaviour};
+pub use maindevice_config::{MailboxScheduling, MainDeviceConfig, RetryBehaviour};
 pub use pdu_loop::{PduLoop, PduRx, PduStorage, PduTx, SendableFrame};
 pub use register::{DcSupport, RegisterAddress};
-pub use subdevice::{DcSync, SubDevice, SubDeviceIdentity, SubDevicePdi, SubDeviceRef};
-pub use subdevice_group::{GroupId, GroupSubDeviceIterator, SubDeviceGroup, SubDeviceGroupHandle};
+pub use soe::SoeElement;
+pub use subdevice::{
+    DcSync, ObjectList, PdoEntryLayout, SubDevice, SubDeviceIdentity, SubDevicePdi, SubDeviceRef,
+};
+pub use subdevice_group::{
+    GroupId, GroupSubDeviceIterator, PdiBuffering, ProcessVariable, SubDeviceGroup,
+    SubDeviceGroupHandle,
+};
 pub use subdevice_state::SubDeviceState;
 pub use timer_factory::Timeouts;
 
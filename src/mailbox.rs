@@ -12,6 +12,9 @@ pub enum Priority {
     Highest = 0x03,
 }
 
+/// Mailbox protocol type.
+///
+/// Defined in ETG1000.4 Section 5.6 Table 29 – Mailbox.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
 #[cfg_attr(test, derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -67,6 +70,71 @@ pub struct MailboxHeader {
     pub service: CoeService,
 }
 
+/// Mailbox header common to every mailbox protocol, without any protocol specific fields.
+///
+/// Defined in ETG1000.4 Section 5.6 Table 29 – Mailbox.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 6)]
+pub struct GenericMailboxHeader {
+    /// Mailbox data payload length, not including this header.
+    #[wire(bytes = 2, post_skip_bytes = 2)]
+    pub length: u16,
+    #[wire(pre_skip = 6, bits = 2)]
+    pub priority: Priority,
+    #[wire(bits = 4)]
+    pub mailbox_type: MailboxType,
+    /// Mailbox counter from 1 to 7 inclusive.
+    #[wire(bits = 3, post_skip = 1)]
+    pub counter: u8,
+}
+
+/// Error sent by a SubDevice in a mailbox of type [`MailboxType::Err`].
+///
+/// Defined in ETG1000.4 Section 5.6 Table 30 – Mailbox Error Reply.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u16)]
+pub enum MailboxErrorCode {
+    /// Syntax of 6 octet mailbox header is wrong.
+    Syntax = 0x0001,
+    /// The mailbox protocol is not supported.
+    UnsupportedProtocol = 0x0002,
+    /// Channel field contains wrong value.
+    InvalidChannel = 0x0003,
+    /// The service in the mailbox protocol is not supported.
+    ServiceNotSupported = 0x0004,
+    /// The mailbox protocol header of the mailbox protocol is wrong, not including the 6 octet
+    /// mailbox header.
+    InvalidHeader = 0x0005,
+    /// Length of received mailbox data is too short.
+    SizeTooShort = 0x0006,
+    /// Mailbox protocol can not be processed because of limited resources.
+    NoMoreMemory = 0x0007,
+    /// The length of data is inconsistent.
+    InvalidSize = 0x0008,
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u16),
+}
+
+impl core::fmt::Display for MailboxErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Syntax => f.write_str("invalid mailbox header syntax"),
+            Self::UnsupportedProtocol => f.write_str("mailbox protocol not supported"),
+            Self::InvalidChannel => f.write_str("invalid channel"),
+            Self::ServiceNotSupported => f.write_str("service not supported"),
+            Self::InvalidHeader => f.write_str("invalid mailbox protocol header"),
+            Self::SizeTooShort => f.write_str("mailbox data too short"),
+            Self::NoMoreMemory => f.write_str("out of memory"),
+            Self::InvalidSize => f.write_str("inconsistent data length"),
+            Self::Unknown(code) => write!(f, "unknown error {:#06x}", code),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,4 +255,32 @@ mod tests {
 
         assert_eq!(parsed, expected);
     }
+
+    #[test]
+    fn generic_header_matches_coe_header() {
+        let raw = [0x0a, 0x00, 0x00, 0x00, 0x00, 0x23, 0x00, 0x20];
+
+        assert_eq!(
+            GenericMailboxHeader::unpack_from_slice(&raw),
+            Ok(GenericMailboxHeader {
+                length: 10,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Coe,
+                counter: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn encode_generic_header() {
+        let packed = GenericMailboxHeader {
+            length: 0x12,
+            priority: Priority::Lowest,
+            mailbox_type: MailboxType::Foe,
+            counter: 5,
+        }
+        .pack();
+
+        assert_eq!(packed, [0x12, 0x00, 0x00, 0x00, 0x00, 0x54]);
+    }
 }
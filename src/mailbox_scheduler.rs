@@ -0,0 +1,470 @@
+//! Send mailbox PDUs in the same frames as cyclic process data.
+//!
+//! See [`MailboxScheduling::Cyclic`](crate::MailboxScheduling::Cyclic).
+
+use crate::{
+    error::Error,
+    fmt,
+    pdu_loop::{CreatedFrame, ParkedFrame, PduLoop, ReceivedFrame},
+    timer_factory::timer,
+};
+use atomic_waker::AtomicWaker;
+use core::{
+    cell::UnsafeCell,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Poll,
+    time::Duration,
+};
+
+/// The maximum number of mailbox PDUs that can wait for a cyclic frame at the same time. Any more
+/// are sent in their own frames.
+const SLOTS: usize = 4;
+
+#[atomic_enum::atomic_enum]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+enum SlotState {
+    /// The slot is empty.
+    Free = 0,
+    /// The slot's data is being read or written by whoever put it into this state.
+    Busy = 1,
+    /// A frame holding a mailbox PDU is waiting for a cycle to pick it up.
+    Queued = 2,
+    /// A cycle is sending the frame along with its process data.
+    Sent = 3,
+    /// The frame's response has been received.
+    Done = 4,
+    /// The mailbox PDU was cancelled after being picked up by a cycle, so the response will be
+    /// thrown away.
+    Abandoned = 5,
+}
+
+#[derive(Debug)]
+enum SlotData {
+    Empty,
+    Request(ParkedFrame),
+    Response(Result<ParkedFrame, Error>),
+}
+
+#[derive(Debug)]
+struct Slot {
+    state: AtomicSlotState,
+    data: UnsafeCell<SlotData>,
+    waker: AtomicWaker,
+}
+
+// SAFETY: Slot data is only accessed by whoever successfully put the slot into `Busy`.
+unsafe impl Sync for Slot {}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            state: AtomicSlotState::new(SlotState::Free),
+            data: UnsafeCell::new(SlotData::Empty),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    fn swap_state(&self, from: SlotState, to: SlotState) -> Result<(), SlotState> {
+        self.state
+            .compare_exchange(from, to, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| ())
+    }
+
+    /// Replace the slot's data.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have put the slot into [`SlotState::Busy`].
+    unsafe fn replace(&self, data: SlotData) -> SlotData {
+        core::mem::replace(&mut *self.data.get(), data)
+    }
+
+    /// Take back a frame that has not been picked up by a cycle yet.
+    fn take_request<'sto>(&self, pdu_loop: &PduLoop<'sto>) -> Option<CreatedFrame<'sto>> {
+        self.swap_state(SlotState::Queued, SlotState::Busy).ok()?;
+
+        // SAFETY: We just put the slot into `Busy`.
+        let SlotData::Request(parked) = (unsafe { self.replace(SlotData::Empty) }) else {
+            fmt::unreachable!()
+        };
+
+        self.state.store(SlotState::Free, Ordering::Release);
+
+        Some(pdu_loop.unpark_created(parked))
+    }
+
+    /// Take the response to a frame sent by a cycle.
+    fn take_response<'sto>(
+        &self,
+        pdu_loop: &PduLoop<'sto>,
+    ) -> Option<Result<ReceivedFrame<'sto>, Error>> {
+        self.swap_state(SlotState::Done, SlotState::Busy).ok()?;
+
+        // SAFETY: We just put the slot into `Busy`.
+        let SlotData::Response(response) = (unsafe { self.replace(SlotData::Empty) }) else {
+            fmt::unreachable!()
+        };
+
+        self.state.store(SlotState::Free, Ordering::Release);
+
+        Some(response.map(|parked| pdu_loop.unpark_received(parked)))
+    }
+
+    /// Store the response to a frame sent by a cycle and wake the waiting mailbox PDU.
+    fn respond(&self, response: Result<ReceivedFrame<'_>, Error>) {
+        match self.swap_state(SlotState::Sent, SlotState::Busy) {
+            Ok(()) => {
+                // SAFETY: We just put the slot into `Busy`.
+                unsafe { self.replace(SlotData::Response(response.map(ReceivedFrame::park))) };
+
+                self.state.store(SlotState::Done, Ordering::Release);
+
+                self.waker.wake();
+            }
+            // Nothing is waiting for the response, so drop it which releases the frame.
+            Err(SlotState::Abandoned) => {
+                self.state.store(SlotState::Free, Ordering::Release);
+            }
+            Err(state) => {
+                fmt::error!("Mailbox response for slot in unexpected state {:?}", state);
+            }
+        }
+    }
+
+    /// Clean up after a mailbox PDU that is no longer waiting for its response.
+    fn cancel(&self, pdu_loop: &PduLoop<'_>) {
+        loop {
+            match self.state.load(Ordering::Acquire) {
+                SlotState::Queued => {
+                    if let Some(frame) = self.take_request(pdu_loop) {
+                        frame.release();
+
+                        return;
+                    }
+                }
+                SlotState::Sent => {
+                    if self
+                        .swap_state(SlotState::Sent, SlotState::Abandoned)
+                        .is_ok()
+                    {
+                        return;
+                    }
+                }
+                SlotState::Done => {
+                    // Dropping the response releases its frame.
+                    if self.take_response(pdu_loop).is_some() {
+                        return;
+                    }
+                }
+                // Only held for the duration of a few non-blocking operations by the cyclic side.
+                SlotState::Busy => core::hint::spin_loop(),
+                SlotState::Free | SlotState::Abandoned => return,
+            }
+        }
+    }
+}
+
+/// The outcome of queueing a mailbox PDU with [`MailboxScheduler::send`].
+pub(crate) enum Scheduled<'sto> {
+    /// The PDU was sent in a cyclic frame and this is the response.
+    Received(Result<ReceivedFrame<'sto>, Error>),
+    /// The PDU was not picked up by a cycle and must be sent by the caller.
+    Unsent(CreatedFrame<'sto>),
+}
+
+/// Mailbox PDUs waiting to be sent with cyclic process data.
+#[derive(Debug)]
+pub(crate) struct MailboxScheduler {
+    slots: [Slot; SLOTS],
+    /// The slot the next cycle checks first, so every queued PDU gets a turn.
+    next: AtomicUsize,
+}
+
+impl MailboxScheduler {
+    pub(crate) const fn new() -> Self {
+        Self {
+            slots: [const { Slot::new() }; SLOTS],
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Queue a frame holding a single mailbox PDU to be sent in the next cyclic frame with room for
+    /// it.
+    ///
+    /// The frame is given back unsent if too many PDUs are queued already, or if no cycle picks it
+    /// up within `time_slice`.
+    pub(crate) async fn send<'sto>(
+        &self,
+        pdu_loop: &PduLoop<'sto>,
+        frame: CreatedFrame<'sto>,
+        time_slice: Duration,
+    ) -> Scheduled<'sto> {
+        let Some(slot) = self
+            .slots
+            .iter()
+            .find(|slot| slot.swap_state(SlotState::Free, SlotState::Busy).is_ok())
+        else {
+            fmt::trace!("No free mailbox slots, sending PDU on its own");
+
+            return Scheduled::Unsent(frame);
+        };
+
+        // SAFETY: We just put the slot into `Busy`.
+        unsafe { slot.replace(SlotData::Request(frame.park())) };
+
+        slot.state.store(SlotState::Queued, Ordering::Release);
+
+        let mut waiting = Waiting {
+            slot,
+            pdu_loop,
+            finished: false,
+        };
+
+        let mut timer = timer(time_slice);
+        let mut expired = false;
+
+        poll_fn(|cx| {
+            waiting.slot.waker.register(cx.waker());
+
+            if let Some(response) = waiting.slot.take_response(pdu_loop) {
+                waiting.finished = true;
+
+                return Poll::Ready(Scheduled::Received(response));
+            }
+
+            if !expired {
+                expired = Pin::new(&mut timer).poll(cx).is_ready();
+            }
+
+            if expired {
+                if let Some(frame) = waiting.slot.take_request(pdu_loop) {
+                    waiting.finished = true;
+
+                    fmt::trace!("Mailbox PDU not picked up within time slice, sending on its own");
+
+                    return Poll::Ready(Scheduled::Unsent(frame));
+                }
+
+                // A cycle is looking at the frame and may put it back, so check again soon.
+                if waiting.slot.state.load(Ordering::Acquire) == SlotState::Busy {
+                    cx.waker().wake_by_ref();
+                }
+            }
+
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Take the next queued mailbox PDU frame that has at least `room` bytes left for cyclic PDUs.
+    ///
+    /// The response to the frame must be passed to [`MailboxDelivery::deliver`].
+    pub(crate) fn next<'sto>(
+        &self,
+        pdu_loop: &PduLoop<'sto>,
+        room: usize,
+    ) -> Option<(CreatedFrame<'sto>, MailboxDelivery<'_>)> {
+        let start = self.next.load(Ordering::Relaxed);
+
+        for idx in (start..(start + SLOTS)).map(|idx| idx % SLOTS) {
+            let slot = &self.slots[idx];
+
+            let Some(frame) = slot
+                .swap_state(SlotState::Queued, SlotState::Busy)
+                .ok()
+                // SAFETY: We just put the slot into `Busy`.
+                .map(|_| unsafe { slot.replace(SlotData::Empty) })
+            else {
+                continue;
+            };
+
+            let SlotData::Request(parked) = frame else {
+                fmt::unreachable!()
+            };
+
+            let frame = pdu_loop.unpark_created(parked);
+
+            if frame.remaining() < room {
+                // SAFETY: The slot is still `Busy`.
+                unsafe { slot.replace(SlotData::Request(frame.park())) };
+
+                slot.state.store(SlotState::Queued, Ordering::Release);
+
+                continue;
+            }
+
+            slot.state.store(SlotState::Sent, Ordering::Release);
+
+            self.next.store((idx + 1) % SLOTS, Ordering::Relaxed);
+
+            return Some((
+                frame,
+                MailboxDelivery {
+                    slot,
+                    delivered: false,
+                },
+            ));
+        }
+
+        None
+    }
+}
+
+/// Releases a queued mailbox PDU if it is cancelled before it receives a response.
+struct Waiting<'a, 'sto> {
+    slot: &'a Slot,
+    pdu_loop: &'a PduLoop<'sto>,
+    finished: bool,
+}
+
+impl Drop for Waiting<'_, '_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.slot.cancel(self.pdu_loop);
+        }
+    }
+}
+
+/// Hands the response to a cyclic frame back to the mailbox PDU sent in it.
+///
+/// If this is dropped without calling [`deliver`](MailboxDelivery::deliver), e.g. because the
+/// cyclic exchange was cancelled, the mailbox PDU fails with [`Error::ReceiveFrame`].
+pub(crate) struct MailboxDelivery<'a> {
+    slot: &'a Slot,
+    delivered: bool,
+}
+
+impl MailboxDelivery<'_> {
+    /// Give the received frame, or the error raised while sending it, to the mailbox PDU.
+    pub(crate) fn deliver(mut self, response: Result<ReceivedFrame<'_>, Error>) {
+        self.delivered = true;
+
+        self.slot.respond(response);
+    }
+}
+
+impl Drop for MailboxDelivery<'_> {
+    fn drop(&mut self) {
+        if !self.delivered {
+            self.slot.respond(Err(Error::ReceiveFrame));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Command, PduStorage};
+    use core::pin::pin;
+    use futures_lite::future::{block_on, poll_once};
+
+    const SLICE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn pick_up_queued_pdu() {
+        let storage: PduStorage<4, { PduStorage::element_size(64) }> = PduStorage::new();
+        let (_tx, _rx, pdu_loop) = storage.try_split().unwrap();
+
+        let scheduler = MailboxScheduler::new();
+
+        let mut frame = pdu_loop.alloc_frame().unwrap();
+
+        frame
+            .push_pdu(Command::fprd(0x1000, 0x1800).into(), (), Some(8), false)
+            .unwrap();
+
+        let mut send = pin!(scheduler.send(&pdu_loop, frame, SLICE));
+
+        assert!(block_on(poll_once(&mut send)).is_none());
+
+        // Not enough room for cyclic data
+        assert!(scheduler.next(&pdu_loop, 64).is_none());
+
+        let (mut frame, delivery) = scheduler.next(&pdu_loop, 16).expect("Queued frame");
+
+        // Already picked up
+        assert!(scheduler.next(&pdu_loop, 0).is_none());
+
+        frame
+            .push_pdu(Command::lrw(0).into(), [0u8; 4], None, false)
+            .unwrap();
+
+        frame.release();
+
+        delivery.deliver(Err(Error::Timeout));
+
+        assert!(matches!(
+            block_on(poll_once(&mut send)),
+            Some(Scheduled::Received(Err(Error::Timeout)))
+        ));
+    }
+
+    #[test]
+    fn cancel_queued() {
+        let storage: PduStorage<1, { PduStorage::element_size(64) }> = PduStorage::new();
+        let (_tx, _rx, pdu_loop) = storage.try_split().unwrap();
+
+        let scheduler = MailboxScheduler::new();
+
+        {
+            let frame = pdu_loop.alloc_frame().unwrap();
+
+            let mut send = pin!(scheduler.send(&pdu_loop, frame, SLICE));
+
+            assert!(block_on(poll_once(&mut send)).is_none());
+        }
+
+        assert!(scheduler.next(&pdu_loop, 0).is_none());
+
+        // The only frame was released
+        assert!(pdu_loop.alloc_frame().is_ok());
+    }
+
+    #[test]
+    fn cancel_cycle() {
+        let storage: PduStorage<1, { PduStorage::element_size(64) }> = PduStorage::new();
+        let (_tx, _rx, pdu_loop) = storage.try_split().unwrap();
+
+        let scheduler = MailboxScheduler::new();
+
+        let frame = pdu_loop.alloc_frame().unwrap();
+
+        let mut send = pin!(scheduler.send(&pdu_loop, frame, SLICE));
+
+        assert!(block_on(poll_once(&mut send)).is_none());
+
+        let (frame, delivery) = scheduler.next(&pdu_loop, 0).unwrap();
+
+        frame.release();
+
+        drop(delivery);
+
+        assert!(matches!(
+            block_on(poll_once(&mut send)),
+            Some(Scheduled::Received(Err(Error::ReceiveFrame)))
+        ));
+    }
+
+    #[test]
+    fn time_slice_expired() {
+        let storage: PduStorage<1, { PduStorage::element_size(64) }> = PduStorage::new();
+        let (_tx, _rx, pdu_loop) = storage.try_split().unwrap();
+
+        let scheduler = MailboxScheduler::new();
+
+        let frame = pdu_loop.alloc_frame().unwrap();
+
+        let outcome = block_on(scheduler.send(&pdu_loop, frame, Duration::from_millis(1)));
+
+        let Scheduled::Unsent(frame) = outcome else {
+            panic!("Expected unsent frame");
+        };
+
+        frame.release();
+
+        assert!(scheduler.next(&pdu_loop, 0).is_none());
+    }
+}
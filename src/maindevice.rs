@@ -5,14 +5,15 @@ use crate::{
     dc,
     error::{Error, Item},
     fmt,
+    mailbox_scheduler::{MailboxDelivery, MailboxScheduler, Scheduled},
     pdi::PdiOffset,
-    pdu_loop::{PduLoop, ReceivedPdu},
+    pdu_loop::{CreatedFrame, PduLoop, ReceivedPdu},
     register::RegisterAddress,
     subdevice::SubDevice,
     subdevice_group::{self, SubDeviceGroupHandle},
     subdevice_state::SubDeviceState,
     timer_factory::IntoTimeout,
-    MainDeviceConfig, SubDeviceGroup, Timeouts, BASE_SUBDEVICE_ADDRESS,
+    MailboxScheduling, MainDeviceConfig, SubDeviceGroup, Timeouts, BASE_SUBDEVICE_ADDRESS,
 };
 use core::{
     ops::Range,
@@ -41,6 +42,8 @@ pub struct MainDevice<'sto> {
     dc_reference_configured_address: AtomicU16,
     pub(crate) timeouts: Timeouts,
     pub(crate) config: MainDeviceConfig,
+    /// Mailbox PDUs waiting to be sent with cyclic process data.
+    mailbox_scheduler: MailboxScheduler,
 }
 
 unsafe impl<'sto> Sync for MainDevice<'sto> {}
@@ -58,6 +61,7 @@ impl<'sto> MainDevice<'sto> {
             dc_reference_configured_address: AtomicU16::new(0),
             timeouts,
             config,
+            mailbox_scheduler: MailboxScheduler::new(),
         }
     }
 
@@ -496,26 +500,72 @@ impl<'sto> MainDevice<'sto> {
         .await
     }
 
-    pub(crate) fn max_frame_data(&self) -> usize {
-        self.pdu_loop.max_frame_data()
+    /// Allocate a frame to send cyclic process data in.
+    ///
+    /// If [`MailboxScheduling::Cyclic`] is configured, the frame may already contain a queued
+    /// mailbox PDU, leaving at least `cyclic_len` bytes for process data PDUs. The frame's response
+    /// must then be passed to [`MailboxDelivery::deliver`].
+    pub(crate) fn alloc_cyclic_frame(
+        &'sto self,
+        cyclic_len: usize,
+    ) -> Result<(CreatedFrame<'sto>, Option<MailboxDelivery<'sto>>), Error> {
+        if let Some((frame, delivery)) = self.mailbox_scheduler.next(&self.pdu_loop, cyclic_len) {
+            return Ok((frame, Some(delivery)));
+        }
+
+        self.pdu_loop.alloc_frame().map(|frame| (frame, None))
     }
 
     /// Send a single PDU in a frame.
+    ///
+    /// If `mailbox` is set and [`MailboxScheduling::Cyclic`] is configured, the PDU is queued to be
+    /// sent in the next cyclic process data frame instead. If that frame is lost or its exchange is
+    /// cancelled, the PDU is sent again in its own frame as allowed by
+    /// [`MainDeviceConfig::retry_behaviour`].
     pub(crate) async fn single_pdu(
         &'sto self,
         command: Command,
         data: impl EtherCrabWireWrite,
         len_override: Option<u16>,
+        mailbox: bool,
     ) -> Result<ReceivedPdu<'sto>, Error> {
+        let mut retries = self.config.retry_behaviour.retry_count();
+
         let mut frame = self.pdu_loop.alloc_frame()?;
 
-        let handle = frame.push_pdu(command, data, len_override, false)?;
+        let mut handle = frame.push_pdu(command, &data, len_override, false)?;
+
+        let frame = match self.config.mailbox_scheduling {
+            MailboxScheduling::Cyclic { time_slice } if mailbox => {
+                match self
+                    .mailbox_scheduler
+                    .send(&self.pdu_loop, frame, time_slice)
+                    .await
+                {
+                    Scheduled::Received(Ok(received)) => return received.first_pdu(handle),
+                    // The failed cyclic frame counts as the first attempt, so the PDU is sent again
+                    // in its own frame with the remaining retries.
+                    Scheduled::Received(Err(e @ (Error::Timeout | Error::ReceiveFrame)))
+                        if retries > 0 =>
+                    {
+                        fmt::debug!("Mailbox PDU in cyclic frame failed: {}, retrying", e);
 
-        let frame = frame.mark_sendable(
-            &self.pdu_loop,
-            self.timeouts.pdu,
-            self.config.retry_behaviour.retry_count(),
-        );
+                        retries -= 1;
+
+                        let mut frame = self.pdu_loop.alloc_frame()?;
+
+                        handle = frame.push_pdu(command, &data, len_override, false)?;
+
+                        frame
+                    }
+                    Scheduled::Received(Err(e)) => return Err(e),
+                    Scheduled::Unsent(frame) => frame,
+                }
+            }
+            _ => frame,
+        };
+
+        let frame = frame.mark_sendable(&self.pdu_loop, self.timeouts.pdu, retries);
 
         self.pdu_loop.wake_sender();
 
@@ -549,6 +599,82 @@ fn blank_mem_iter(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{PduStorage, RetryBehaviour};
+    use core::{pin::pin, time::Duration};
+    use futures_lite::future::{block_on, poll_once};
+
+    /// Queue a mailbox PDU for a cyclic frame, then cancel the cyclic exchange that picked it up.
+    fn cancelled_cyclic_mailbox_pdu(retry_behaviour: RetryBehaviour) -> Result<Vec<u8>, Error> {
+        let storage: PduStorage<4, { PduStorage::element_size(32) }> = PduStorage::new();
+        let (mut tx, mut rx, pdu_loop) = storage.try_split().unwrap();
+
+        let maindevice = MainDevice::new(
+            pdu_loop,
+            Timeouts::default(),
+            MainDeviceConfig {
+                mailbox_scheduling: MailboxScheduling::Cyclic {
+                    time_slice: Duration::from_secs(60),
+                },
+                retry_behaviour,
+                ..MainDeviceConfig::default()
+            },
+        );
+
+        let mut read =
+            pin!(maindevice.single_pdu(Command::fprd(0x1000, 0x1080).into(), (), Some(4), true));
+
+        assert!(block_on(poll_once(&mut read)).is_none());
+
+        let (frame, delivery) = maindevice.alloc_cyclic_frame(0).unwrap();
+
+        assert!(
+            delivery.is_some(),
+            "mailbox PDU should be picked up by the cycle"
+        );
+
+        frame.release();
+        drop(delivery);
+
+        if let Some(result) = block_on(poll_once(&mut read)) {
+            return result.map(|pdu| pdu.to_vec());
+        }
+
+        let frame = tx.next_sendable_frame().expect("PDU sent in its own frame");
+
+        let mut response = Vec::new();
+
+        frame
+            .send_blocking(|bytes| {
+                response = bytes.to_vec();
+
+                Ok(bytes.len())
+            })
+            .unwrap();
+
+        // Mark as a response, then fill in the data after the Ethernet, EtherCAT and PDU headers
+        response[6] = 0x12;
+        response[14 + 2 + 10..][..4].copy_from_slice(&[0xaa, 0xbb, 0xcc, 0xdd]);
+
+        rx.receive_frame(&response).unwrap();
+
+        block_on(read).map(|pdu| pdu.to_vec())
+    }
+
+    #[test]
+    fn cyclic_mailbox_pdu_retried() {
+        assert_eq!(
+            cancelled_cyclic_mailbox_pdu(RetryBehaviour::Count(1)),
+            Ok(vec![0xaa, 0xbb, 0xcc, 0xdd])
+        );
+    }
+
+    #[test]
+    fn cyclic_mailbox_pdu_no_retries() {
+        assert_eq!(
+            cancelled_cyclic_mailbox_pdu(RetryBehaviour::None),
+            Err(Error::ReceiveFrame)
+        );
+    }
 
     #[test]
     #[cfg_attr(miri, ignore)]
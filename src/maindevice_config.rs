@@ -1,5 +1,7 @@
 //! Configuration passed to [`MainDevice`](crate::MainDevice).
 
+use core::time::Duration;
+
 /// Configuration passed to [`MainDevice`](crate::MainDevice).
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct MainDeviceConfig {
@@ -13,6 +15,20 @@ pub struct MainDeviceConfig {
 
     /// EtherCAT packet (PDU) network retry behaviour.
     pub retry_behaviour: RetryBehaviour,
+
+    /// How mailbox PDUs are sent while process data is being exchanged.
+    ///
+    /// Defaults to [`MailboxScheduling::Immediate`].
+    pub mailbox_scheduling: MailboxScheduling,
+
+    /// How many times a lost or corrupted mailbox read is recovered by asking the SubDevice to
+    /// repeat its last mailbox message, per mailbox response.
+    ///
+    /// Defaults to 1. The SubDevice must acknowledge each repeat request within
+    /// [`Timeouts::mailbox_response`](crate::Timeouts::mailbox_response).
+    ///
+    /// If this is set to zero, mailbox read failures are returned without attempting recovery.
+    pub mailbox_repeats: u32,
 }
 
 impl Default for MainDeviceConfig {
@@ -20,6 +36,8 @@ impl Default for MainDeviceConfig {
         Self {
             dc_static_sync_iterations: 10_000,
             retry_behaviour: RetryBehaviour::default(),
+            mailbox_scheduling: MailboxScheduling::default(),
+            mailbox_repeats: 1,
         }
     }
 }
@@ -61,6 +79,30 @@ impl RetryBehaviour {
     }
 }
 
+/// Mailbox PDU scheduling policy.
+///
+/// Every step of a mailbox transaction, e.g. checking whether the write mailbox is empty, writing
+/// a request or reading a response, sends one `FPRD` or `FPWR` PDU.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum MailboxScheduling {
+    /// Send every mailbox PDU in its own frame as soon as it is ready (default).
+    #[default]
+    Immediate,
+
+    /// Queue mailbox PDUs to be sent in the same frame as the next cyclic process data exchange,
+    /// e.g. [`SubDeviceGroup::tx_rx`](crate::SubDeviceGroup::tx_rx), when there is room for them.
+    ///
+    /// Each cycle sends at most one queued mailbox PDU, so mailbox transactions advance one step
+    /// per cycle and add little jitter to process data. If no cycle picks a PDU up within
+    /// `time_slice`, for example because process data is not being exchanged yet, it is sent in
+    /// its own frame instead. A PDU whose cyclic frame is lost or cancelled is also sent again in
+    /// its own frame if [`MainDeviceConfig::retry_behaviour`] allows another attempt.
+    Cyclic {
+        /// How long a mailbox PDU waits for a cyclic frame before it is sent on its own.
+        time_slice: Duration,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,13 +3,13 @@ use core::ops::Range;
 /// An accumulator that stores the bit and byte offsets in the PDI so SubDevice IO data can be mapped
 /// to/from the PDI using FMMUs.
 ///
-/// PDI mappings are byte-aligned per each SubDevice.
+/// PDI mappings are byte-aligned per each SubDevice, unless bit-packed mapping is enabled for the
+/// group.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PdiOffset {
     pub start_address: u32,
-    // // Unused, but will become useful if we support bit-packed PDI mappings in the future.
-    // start_bit: u8,
+    pub start_bit: u8,
 }
 
 impl PdiOffset {
@@ -17,55 +17,72 @@ impl PdiOffset {
     pub fn increment_byte_aligned(self, bits: u16) -> Self {
         let inc_bytes = (bits + 7) / 8;
 
-        self.increment_inner(0, inc_bytes)
+        self.align().increment_inner(0, inc_bytes)
     }
 
+    /// Increment the address accumulator by a given number of bytes, starting from the next byte
+    /// boundary.
     pub fn increment(self, bytes: u16) -> Self {
-        self.increment_inner(0, bytes)
+        self.align().increment_inner(0, bytes)
+    }
+
+    /// Increment, calculating values for _next_ mapping when the struct is read after increment.
+    pub fn increment_bits(self, bits: u16) -> Self {
+        let inc_bytes = bits / 8;
+        let inc_bits = bits % 8;
+
+        self.increment_inner(inc_bits, inc_bytes)
+    }
+
+    /// Move to the start of the next byte if this offset is part way through a byte.
+    pub fn align(self) -> Self {
+        if self.start_bit == 0 {
+            self
+        } else {
+            Self {
+                start_address: self.start_address + 1,
+                start_bit: 0,
+            }
+        }
     }
 
     /// Common code shared between byte and bit aligned public methods.
-    fn increment_inner(self, _inc_bits: u16, inc_bytes: u16) -> Self {
-        // // Bit count overflows a byte, so move into the next byte's bits by incrementing the byte
-        // // index one more.
-        // let start_bit = if u16::from(self.start_bit) + inc_bits >= 8 {
-        //     inc_bytes += 1;
+    fn increment_inner(self, inc_bits: u16, mut inc_bytes: u16) -> Self {
+        // Bit count overflows a byte, so move into the next byte's bits by incrementing the byte
+        // index one more.
+        let start_bit = if u16::from(self.start_bit) + inc_bits >= 8 {
+            inc_bytes += 1;
 
-        //     ((u16::from(self.start_bit) + inc_bits) % 8) as u8
-        // } else {
-        //     self.start_bit + inc_bits as u8
-        // };
+            ((u16::from(self.start_bit) + inc_bits) % 8) as u8
+        } else {
+            self.start_bit + inc_bits as u8
+        };
 
         Self {
             start_address: self.start_address + u32::from(inc_bytes),
-            // start_bit,
+            start_bit,
         }
     }
 
-    // /// Compute end bit 0-7 in the final byte of the mapped PDI section.
-    // fn end_bit(self, bits: u16) -> u8 {
-    //     // SAFETY: The modulos here and in `increment` mean that all value can comfortably fit in a
-    //     // u8, so all the `as` and non-checked `+` here are fine.
+    /// Compute end bit 0-7 in the final byte of the mapped PDI section.
+    pub fn end_bit(self, bits: u16) -> u8 {
+        // SAFETY: The modulos here and in `increment` mean that all value can comfortably fit in a
+        // u8, so all the `as` and non-checked `+` here are fine.
 
-    //     let bits = (bits.saturating_sub(1) % 8) as u8;
-
-    //     self.start_bit + bits % 8
-    // }
+        let bits = (bits.saturating_sub(1) % 8) as u8;
 
-    /// Compute an index range between this offset (inclusive) and another (exclusive).
-    pub fn up_to(self, other: Self) -> Range<usize> {
-        self.start_address as usize..other.start_address as usize
+        self.start_bit + bits % 8
     }
 
-    // Maybe one day we support packed PDIs. In that instance, uncomment this and the tests below.
-    // DO NOT DELETE as part of cleanup
-    // /// Increment, calculating values for _next_ mapping when the struct is read after increment.
-    // fn increment(self, bits: u16) -> Self {
-    //     let inc_bytes = bits / 8;
-    //     let inc_bits = bits % 8;
+    /// Compute an index range between this offset (inclusive) and another (exclusive), including
+    /// any partially used byte at the end.
+    pub fn up_to(self, other: Self) -> Range<usize> {
+        if other == self {
+            return self.start_address as usize..self.start_address as usize;
+        }
 
-    //     self.increment_inner(inc_bits, inc_bytes)
-    // }
+        self.start_address as usize..other.align().start_address as usize
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -73,6 +90,10 @@ impl PdiOffset {
 pub struct PdiSegment {
     pub bytes: Range<usize>,
     pub bit_len: usize,
+    /// Position of the segment's first bit in its first byte. Always `0` unless bit-packed.
+    pub start_bit: u8,
+    /// True if the segment is bit-packed, so its byte may be shared with other SubDevices.
+    pub bit_packed: bool,
 }
 
 impl PdiSegment {
@@ -87,7 +108,13 @@ impl PdiSegment {
 
 impl core::fmt::Display for PdiSegment {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        if self.bit_len > 0 {
+        if self.bit_packed {
+            write!(
+                f,
+                "{:#010x}.{} ({} bits)",
+                self.bytes.start, self.start_bit, self.bit_len
+            )
+        } else if self.bit_len > 0 {
             write!(
                 f,
                 "{:#010x}..{:#010x} ({} bits)",
@@ -99,6 +126,36 @@ impl core::fmt::Display for PdiSegment {
     }
 }
 
+/// Copy `bit_len` bits starting at `bit_offset` in `data` into the start of `buf`, least
+/// significant bit first.
+///
+/// Bits in `buf` past `bit_len` are left unchanged. `data` and `buf` must be long enough to hold
+/// the given bits.
+pub fn read_bits(data: &[u8], bit_offset: usize, bit_len: usize, buf: &mut [u8]) {
+    for bit in 0..bit_len {
+        let src = bit_offset + bit;
+
+        let value = data[src / 8] >> (src % 8) & 1;
+
+        buf[bit / 8] = buf[bit / 8] & !(1 << (bit % 8)) | value << (bit % 8);
+    }
+}
+
+/// Copy the first `bit_len` bits of `value` into `data` starting at `bit_offset`, least significant
+/// bit first.
+///
+/// Bits in `data` outside the given range are left unchanged. `data` and `value` must be long
+/// enough to hold the given bits.
+pub fn write_bits(data: &mut [u8], bit_offset: usize, bit_len: usize, value: &[u8]) {
+    for bit in 0..bit_len {
+        let dest = bit_offset + bit;
+
+        let value = value[bit / 8] >> (bit % 8) & 1;
+
+        data[dest / 8] = data[dest / 8] & !(1 << (dest % 8)) | value << (dest % 8);
+    }
+}
+
 // impl PdiSegment {
 //     /// If this segment contains less than 8 bits, this method will calculate the bit mask for the
 //     /// used bits.
@@ -117,18 +174,50 @@ mod tests {
 
         let input = input.increment_byte_aligned(4);
 
-        assert_eq!(input, PdiOffset { start_address: 1 }, "first increment");
+        assert_eq!(
+            input,
+            PdiOffset {
+                start_address: 1,
+                start_bit: 0
+            },
+            "first increment"
+        );
 
         let input = input.increment_byte_aligned(4);
 
-        assert_eq!(input, PdiOffset { start_address: 2 }, "second increment");
+        assert_eq!(
+            input,
+            PdiOffset {
+                start_address: 2,
+                start_bit: 0
+            },
+            "second increment"
+        );
+    }
+
+    #[test]
+    fn unaligned_bits() {
+        let mut data = [0b1010_0000u8, 0b0000_0110];
+        let mut buf = [0xffu8];
+
+        // 4 bits spanning the byte boundary
+        read_bits(&data, 6, 4, &mut buf);
+
+        assert_eq!(buf, [0b1111_1010]);
+
+        write_bits(&mut data, 6, 4, &[0b0101]);
+
+        assert_eq!(data, [0b0110_0000, 0b0000_0101]);
     }
 
     #[test]
     #[cfg_attr(miri, ignore)]
     fn fuzz_pdi_segment() {
         heckcheck::check(|(start_address, incr_bits): (u32, u16)| {
-            let offset = PdiOffset { start_address };
+            let offset = PdiOffset {
+                start_address,
+                start_bit: 0,
+            };
 
             let new = offset.increment_byte_aligned(incr_bits);
 
@@ -140,63 +229,75 @@ mod tests {
                 offset.start_address + incr_bytes,
                 "incorrect increment"
             );
-            // assert_eq!(new.start_bit, 0, "not byte aligned");
+            assert_eq!(new.start_bit, 0, "not byte aligned");
 
             Ok(())
         });
     }
 
-    // Maybe one day we support packed PDIs. DO NOT DELETE as part of cleanup.
-    // #[test]
-    // fn size_bytes() {
-    //     // E.g. 2x EL2004, 1x EL1004
-    //     let input = MappingOffset::default()
-    //         .increment(4)
-    //         .increment(4)
-    //         .increment(4);
-
-    //     assert_eq!(input.size_bytes(), 2);
-    // }
-
-    // #[test]
-    // fn simulate_2_el2004() {
-    //     let input = MappingOffset::default();
-
-    //     let input = input.increment(4);
+    #[test]
+    fn size_bytes() {
+        // E.g. 2x EL2004, 1x EL1004
+        let input = PdiOffset::default()
+            .increment_bits(4)
+            .increment_bits(4)
+            .increment_bits(4);
+
+        assert_eq!(PdiOffset::default().up_to(input), 0..2);
+    }
 
-    //     assert_eq!(
-    //         input,
-    //         MappingOffset {
-    //             start_address: 0,
-    //             start_bit: 4
-    //         }
-    //     );
+    #[test]
+    fn simulate_2_el2004() {
+        let input = PdiOffset::default();
 
-    //     let input = input.increment(4);
+        let input = input.increment_bits(4);
+
+        assert_eq!(
+            input,
+            PdiOffset {
+                start_address: 0,
+                start_bit: 4
+            }
+        );
+
+        let input = input.increment_bits(4);
+
+        assert_eq!(
+            input,
+            PdiOffset {
+                start_address: 1,
+                start_bit: 0
+            }
+        );
+    }
 
-    //     assert_eq!(
-    //         input,
-    //         MappingOffset {
-    //             start_address: 1,
-    //             start_bit: 0
-    //         }
-    //     );
-    // }
+    #[test]
+    fn end_bit() {
+        let input = PdiOffset::default();
 
-    // #[test]
-    // fn end_bit() {
-    //     let input = MappingOffset::default();
+        assert_eq!(input.end_bit(4), 3);
 
-    //     assert_eq!(input.end_bit(4), 3);
+        let input = input.increment_bits(4);
 
-    //     let input = input.increment(4);
+        assert_eq!(input.end_bit(4), 7);
 
-    //     assert_eq!(input.end_bit(4), 7);
+        let input = input.increment_bits(4);
 
-    //     let input = input.increment(4);
+        assert_eq!(input.end_bit(4), 3);
+    }
 
-    //     assert_eq!(input.end_bit(4), 3);
-    // }
+    #[test]
+    fn byte_aligned_after_bits() {
+        let input = PdiOffset::default().increment_bits(4);
+
+        assert_eq!(
+            input.increment_byte_aligned(8),
+            PdiOffset {
+                start_address: 2,
+                start_bit: 0
+            }
+        );
+    }
 
     // #[test]
     // fn zero_length_end_bit() {
@@ -21,6 +21,8 @@ use ethercrab_wire::{EtherCrabWireSized, EtherCrabWireWrite, EtherCrabWireWriteS
 pub struct CreatedFrame<'sto> {
     inner: FrameBox<'sto>,
     pdu_count: u8,
+    /// Offset of the most recently pushed PDU in the frame payload.
+    last_pdu_start: usize,
 }
 
 impl<'sto> CreatedFrame<'sto> {
@@ -39,9 +41,54 @@ impl<'sto> CreatedFrame<'sto> {
         Ok(Self {
             inner,
             pdu_count: 0,
+            last_pdu_start: 0,
         })
     }
 
+    /// Take back a frame that was parked with [`park`](CreatedFrame::park).
+    pub(in crate::pdu_loop) fn unpark(
+        frame: NonNull<FrameElement<0>>,
+        pdu_idx: &'sto AtomicU8,
+        frame_data_len: usize,
+        parked: ParkedFrame,
+    ) -> Self {
+        Self {
+            inner: FrameBox::new(frame, pdu_idx, frame_data_len),
+            pdu_count: parked.pdu_count,
+            last_pdu_start: parked.last_pdu_start,
+        }
+    }
+
+    /// Give up ownership of this frame without changing its state, so it can be stored without a
+    /// lifetime and taken back later with [`PduLoop::unpark_created`].
+    pub fn park(self) -> ParkedFrame {
+        ParkedFrame {
+            frame_index: self.inner.frame_index(),
+            pdu_count: self.pdu_count,
+            last_pdu_start: self.last_pdu_start,
+        }
+    }
+
+    /// Return the frame to the pool of available frames without sending it.
+    pub fn release(self) {
+        // Invariant: a `CreatedFrame` can only exist for a frame in the `Created` state.
+        fmt::unwrap!(self.inner.swap_state(FrameState::Created, FrameState::None));
+    }
+
+    /// The number of bytes left in the frame for more PDUs, including their headers and working
+    /// counters.
+    pub fn remaining(&self) -> usize {
+        self.inner
+            .pdu_buf()
+            .len()
+            .saturating_sub(self.inner.pdu_payload_len())
+    }
+
+    /// The number of bytes in a frame taken by a PDU with a payload of `data_len` bytes.
+    pub const fn pdu_len(data_len: usize) -> usize {
+        PduHeader::PACKED_LEN + data_len + 2
+    }
+
     /// The frame has been initialised, filled with a data payload (if required), and is now ready
     /// to be sent.
     ///
@@ -122,9 +169,21 @@ impl<'sto> CreatedFrame<'sto> {
         // Next two bytes are working counter, but they are always zero on send (and the buffer is
         // zero-initialised) so there's nothing to do.
 
+        // Tell the SubDevices another PDU follows the previous one, which may have been pushed
+        // without knowing this one would be added.
+        if self.pdu_count > 0 {
+            // The high byte of the flags field, which holds the `NEXT` bit, is the 8th byte of the
+            // PDU header.
+            if let Some(flags) = self.inner.pdu_buf_mut().get_mut(self.last_pdu_start + 7) {
+                *flags |= 0x80;
+            }
+        }
+
         // Don't need to check length here as we do that with `pdu_buf_mut().get_mut()` above.
         self.inner.add_pdu(alloc_size, pdu_idx);
 
+        self.last_pdu_start = consumed;
+
         let index_in_frame = self.pdu_count;
 
         self.pdu_count += 1;
@@ -146,6 +205,27 @@ impl<'sto> CreatedFrame<'sto> {
 // a 'static bound.
 unsafe impl<'sto> Send for CreatedFrame<'sto> {}
 
+/// A frame that is not held by any typestate, identified by its index in the PDU storage.
+///
+/// The frame keeps its current state until it is taken back with [`PduLoop::unpark_created`] or
+/// [`PduLoop::unpark_received`]. Like [`CreatedFrame`], dropping a `ParkedFrame` leaks the frame.
+#[derive(Debug)]
+pub struct ParkedFrame {
+    pub(in crate::pdu_loop) frame_index: u8,
+    pdu_count: u8,
+    last_pdu_start: usize,
+}
+
+impl ParkedFrame {
+    pub(in crate::pdu_loop) fn received(frame_index: u8) -> Self {
+        Self {
+            frame_index,
+            pdu_count: 0,
+            last_pdu_start: 0,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct PduResponseHandle {
     pub index_in_frame: u8,
@@ -165,6 +245,7 @@ mod tests {
         ptr::NonNull,
         sync::atomic::{AtomicU16, AtomicU8},
     };
+    use ethercrab_wire::EtherCrabWireRead;
 
     #[test]
     fn too_long() {
@@ -200,4 +281,56 @@ mod tests {
 
         assert_eq!(handle.unwrap_err(), PduError::TooLong);
     }
+
+    #[test]
+    fn set_more_follows() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        const BUF_LEN: usize = 64;
+
+        let pdu_idx = AtomicU8::new(0);
+
+        let frames = UnsafeCell::new([FrameElement {
+            frame_index: 0xab,
+            status: AtomicFrameState::new(FrameState::None),
+            waker: AtomicWaker::default(),
+            ethernet_frame: [0u8; BUF_LEN],
+            pdu_payload_len: 0,
+            first_pdu: AtomicU16::new(FIRST_PDU_EMPTY),
+        }]);
+
+        let mut created = CreatedFrame::claim_created(
+            unsafe { NonNull::new_unchecked(frames.get().cast()) },
+            0xab,
+            &pdu_idx,
+            BUF_LEN,
+        )
+        .expect("Claim created");
+
+        created
+            .push_pdu(Command::fprd(0x1000, 0x1800).into(), (), Some(2), false)
+            .unwrap();
+
+        let flags = |created: &CreatedFrame, start: usize| {
+            PduHeader::unpack_from_slice(&created.inner.pdu_buf()[start..])
+                .unwrap()
+                .flags
+        };
+
+        assert!(!flags(&created, 0).more_follows);
+
+        created
+            .push_pdu(Command::lrw(0).into(), [0u8; 4], None, false)
+            .unwrap();
+
+        assert_eq!(flags(&created, 0), PduFlags::new(2, true));
+        assert_eq!(
+            flags(&created, CreatedFrame::pdu_len(2)),
+            PduFlags::new(4, false)
+        );
+        assert_eq!(
+            created.remaining(),
+            created.inner.pdu_buf().len() - CreatedFrame::pdu_len(2) - CreatedFrame::pdu_len(4)
+        );
+    }
 }
@@ -2,11 +2,14 @@ use crate::{
     error::{Error, PduError},
     fmt,
     pdu_loop::{
-        frame_element::{created_frame::PduResponseHandle, FrameBox, FrameState},
+        frame_element::{
+            created_frame::{ParkedFrame, PduResponseHandle},
+            FrameBox, FrameElement, FrameState,
+        },
         pdu_header::PduHeader,
     },
 };
-use core::{marker::PhantomData, ops::Deref, ptr::NonNull};
+use core::{marker::PhantomData, ops::Deref, ptr::NonNull, sync::atomic::AtomicU8};
 use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
 
 /// A frame element where response data has been received from the EtherCAT network.
@@ -23,6 +26,26 @@ impl<'sto> ReceivedFrame<'sto> {
         Self { inner }
     }
 
+    /// Take back a frame that was parked with [`park`](ReceivedFrame::park).
+    pub(in crate::pdu_loop) fn unpark(
+        frame: NonNull<FrameElement<0>>,
+        pdu_idx: &'sto AtomicU8,
+        frame_data_len: usize,
+    ) -> Self {
+        Self::new(FrameBox::new(frame, pdu_idx, frame_data_len))
+    }
+
+    /// Give up ownership of this frame without releasing it, so it can be stored without a
+    /// lifetime and taken back later with [`PduLoop::unpark_received`](crate::PduLoop::unpark_received).
+    pub fn park(self) -> ParkedFrame {
+        let parked = ParkedFrame::received(self.inner.frame_index());
+
+        // Keep the frame in `RxProcessing` until it is unparked.
+        core::mem::forget(self);
+
+        parked
+    }
+
     pub fn first_pdu(self, handle: PduResponseHandle) -> Result<ReceivedPdu<'sto>, Error> {
         let buf = self.inner.pdu_buf();
 
@@ -9,7 +9,8 @@ pub(crate) enum ProtocolType {
     DlPdu = 0x01u8,
     // Not currently supported.
     // NetworkVariables = 0x04,
-    // Mailbox = 0x05,
+    /// Mailbox gateway (ETG.8200).
+    Mailbox = 0x05,
     // #[wire(catch_all)]
     // Unknown(u8),
 }
@@ -74,6 +75,15 @@ impl EthercatFrameHeader {
         }
     }
 
+    /// Create a new mailbox gateway frame header.
+    #[cfg(feature = "std")]
+    pub(crate) fn mailbox(len: u16) -> Self {
+        Self {
+            payload_len: len & LEN_MASK,
+            protocol: ProtocolType::Mailbox,
+        }
+    }
+
     /// Convenience method for naming consistency.
     pub(crate) const fn header_len() -> usize {
         Self::PACKED_LEN
@@ -107,6 +117,19 @@ mod tests {
         assert_eq!(header.protocol, ProtocolType::DlPdu);
     }
 
+    #[test]
+    fn mailbox_header() {
+        let raw = [0x10u8, 0x50];
+
+        let header = EthercatFrameHeader::unpack_from_slice(&raw).unwrap();
+
+        assert_eq!(header, EthercatFrameHeader::mailbox(0x10));
+
+        let mut buf = [0u8; 2];
+
+        assert_eq!(header.pack_to_slice_unchecked(&mut buf), raw);
+    }
+
     #[test]
     fn parse() {
         // Header from packet #39, soem-sdinfo-ek1100-only.pcapng
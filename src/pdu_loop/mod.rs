@@ -1,5 +1,5 @@
 mod frame_element;
-mod frame_header;
+pub(crate) mod frame_header;
 mod pdu_flags;
 mod pdu_header;
 mod pdu_rx;
@@ -13,8 +13,9 @@ pub use pdu_rx::PduRx;
 pub use pdu_tx::PduTx;
 pub use storage::PduStorage;
 
-pub(crate) use self::frame_element::created_frame::CreatedFrame;
-pub(crate) use frame_element::received_frame::ReceivedPdu;
+pub(crate) use self::frame_element::created_frame::{CreatedFrame, ParkedFrame, PduResponseHandle};
+pub(crate) use frame_element::received_frame::{ReceivedFrame, ReceivedPdu};
+pub(crate) use frame_element::receiving_frame::ReceiveFrameFut;
 
 pub use frame_element::sendable_frame::SendableFrame;
 
@@ -102,6 +103,16 @@ impl<'sto> PduLoop<'sto> {
     pub(crate) fn alloc_frame(&self) -> Result<CreatedFrame<'sto>, Error> {
         self.storage.alloc_frame()
     }
+
+    /// Take back a frame parked by [`CreatedFrame::park`].
+    pub(crate) fn unpark_created(&self, parked: ParkedFrame) -> CreatedFrame<'sto> {
+        self.storage.unpark_created(parked)
+    }
+
+    /// Take back a frame parked by [`ReceivedFrame::park`].
+    pub(crate) fn unpark_received(&self, parked: ParkedFrame) -> ReceivedFrame<'sto> {
+        self.storage.unpark_received(parked)
+    }
 }
 
 #[cfg(test)]
@@ -214,7 +225,7 @@ mod tests {
             let mut frame = pdu_loop.storage.alloc_frame().expect("Frame alloc");
 
             let handle = frame
-                .push_pdu(Command::fpwr(0x5678, 0x1234).into(), &data, None, false)
+                .push_pdu(Command::fpwr(0x5678, 0x1234).into(), data, None, false)
                 .expect("Push PDU");
 
             let mut frame_fut = pin!(frame.mark_sendable(&pdu_loop, Duration::MAX, usize::MAX));
@@ -364,7 +375,7 @@ mod tests {
             let handle = frame
                 .push_pdu(
                     Command::fpwr(0x6789, 0x1234).into(),
-                    &data_bytes,
+                    data_bytes,
                     None,
                     false,
                 )
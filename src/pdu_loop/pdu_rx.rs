@@ -3,7 +3,7 @@ use crate::ethernet::{EthernetAddress, EthernetFrame};
 use crate::{
     error::{Error, PduError},
     fmt,
-    pdu_loop::frame_header::EthercatFrameHeader,
+    pdu_loop::frame_header::{EthercatFrameHeader, ProtocolType},
     ETHERCAT_ETHERTYPE, MASTER_ADDR,
 };
 use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
@@ -57,6 +57,12 @@ impl<'sto> PduRx<'sto> {
             e
         })?;
 
+        if frame_header.protocol != ProtocolType::DlPdu {
+            fmt::trace!("Ignore non-PDU frame");
+
+            return Ok(());
+        }
+
         // Skip EtherCAT header and get PDU(s) payload
         let i = i
             .get(
@@ -5,7 +5,10 @@ use crate::{
     fmt,
     pdu_loop::{
         frame_element::{
-            created_frame::CreatedFrame, receiving_frame::ReceivingFrame, FrameElement,
+            created_frame::{CreatedFrame, ParkedFrame},
+            received_frame::ReceivedFrame,
+            receiving_frame::ReceivingFrame,
+            FrameElement,
         },
         pdu_flags::PduFlags,
     },
@@ -86,6 +89,7 @@ impl<const N: usize, const DATA: usize> PduStorage<N, DATA> {
     /// - `N` is larger than `u8::MAX, or not a power of two, or
     /// - `DATA` is less than 28 as this is the minimum size required to hold an EtherCAT frame with
     ///   zero PDU length.
+    #[allow(clippy::new_without_default)]
     pub const fn new() -> Self {
         // MSRV: Make `N` a `u8` when `generic_const_exprs` is stablised
         // If possible, try using `NonZeroU8`.
@@ -212,6 +216,20 @@ impl<'sto> PduStorageRef<'sto> {
         Err(PduError::SwapState.into())
     }
 
+    /// Take back a frame parked by [`CreatedFrame::park`].
+    pub(in crate::pdu_loop) fn unpark_created(&self, parked: ParkedFrame) -> CreatedFrame<'sto> {
+        let frame = self.frame_at_index(usize::from(parked.frame_index));
+
+        CreatedFrame::unpark(frame, self.pdu_idx, self.frame_data_len, parked)
+    }
+
+    /// Take back a frame parked by [`ReceivedFrame::park`].
+    pub(in crate::pdu_loop) fn unpark_received(&self, parked: ParkedFrame) -> ReceivedFrame<'sto> {
+        let frame = self.frame_at_index(usize::from(parked.frame_index));
+
+        ReceivedFrame::unpark(frame, self.pdu_idx, self.frame_data_len)
+    }
+
     /// Updates state from SENDING -> RX_BUSY
     pub(in crate::pdu_loop) fn claim_receiving(
         &self,
@@ -268,6 +268,14 @@ impl RegisterAddress {
     pub fn sync_manager_status(index: u8) -> u16 {
         u16::from(Self::sync_manager(index)) + 5
     }
+
+    /// Sync manager activate and PDI control registers by SM index.
+    ///
+    /// The activate register is the 6th byte after the start of the SM, followed by the PDI control
+    /// register.
+    pub fn sync_manager_enable(index: u8) -> u16 {
+        u16::from(Self::sync_manager(index)) + 6
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
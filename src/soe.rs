@@ -0,0 +1,330 @@
+//! Servo drive profile over EtherCAT (SoE), defined in ETG1000.6 Section 5.8.
+
+/// SoE opcode.
+///
+/// Defined in ETG1000.6 Section 5.8.2 Table 83 – SoE Read Request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum SoeOpCode {
+    /// Read request.
+    ReadRequest = 0x01,
+    /// Read response.
+    ReadResponse = 0x02,
+    /// Write request.
+    WriteRequest = 0x03,
+    /// Write response.
+    WriteResponse = 0x04,
+    /// Notification request.
+    Notification = 0x05,
+    /// SubDevice info (emergency) request.
+    SubDeviceInfo = 0x06,
+}
+
+/// SoE header, sent after the mailbox header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 4)]
+pub struct SoeHeader {
+    #[wire(bits = 3)]
+    pub op_code: SoeOpCode,
+    /// More fragments follow this one.
+    #[wire(bits = 1)]
+    pub incomplete: bool,
+    /// The data holds an [`SoeErrorCode`] instead of the requested element.
+    #[wire(bits = 1)]
+    pub error: bool,
+    #[wire(bits = 3)]
+    pub drive_number: u8,
+    /// A bit field of [`SoeElement`]s.
+    #[wire(bytes = 1)]
+    pub elements: u8,
+    /// The IDN, or the number of fragments left if `incomplete` is set.
+    #[wire(bytes = 2)]
+    pub idn_or_fragments_left: u16,
+}
+
+impl SoeHeader {
+    pub fn read(drive_number: u8, idn: u16, element: SoeElement) -> Self {
+        Self {
+            op_code: SoeOpCode::ReadRequest,
+            incomplete: false,
+            error: false,
+            drive_number,
+            elements: u8::from(element),
+            idn_or_fragments_left: idn,
+        }
+    }
+
+    /// Create a write request header.
+    ///
+    /// If more fragments follow this one, `fragments_left` should be the number of them. For the
+    /// last (or only) fragment, it should be `0`.
+    pub fn write(drive_number: u8, idn: u16, element: SoeElement, fragments_left: u16) -> Self {
+        let incomplete = fragments_left > 0;
+
+        Self {
+            op_code: SoeOpCode::WriteRequest,
+            incomplete,
+            error: false,
+            drive_number,
+            elements: u8::from(element),
+            idn_or_fragments_left: if incomplete { fragments_left } else { idn },
+        }
+    }
+}
+
+/// An element of an IDN to read or write with SoE.
+///
+/// Defined in ETG1000.6 Section 5.8.2 Table 83 – SoE Read Request.
+///
+/// The name and unit elements, as well as the value of list IDNs, are sent as a list: two `u16`s
+/// holding the current and maximum length in bytes, followed by the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum SoeElement {
+    /// Data state.
+    DataState = 0x01,
+    /// Name.
+    Name = 0x02,
+    /// Attribute, describing the data type, length and display format of the value.
+    Attribute = 0x04,
+    /// Unit.
+    Unit = 0x08,
+    /// Minimum value.
+    Minimum = 0x10,
+    /// Maximum value.
+    Maximum = 0x20,
+    /// Value.
+    Value = 0x40,
+    /// Default value.
+    Default = 0x80,
+}
+
+/// SoE error code sent by a SubDevice.
+///
+/// Defined in IEC 61800-7-204 (SERCOS) and referenced by ETG1000.6 Section 5.8.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u16)]
+pub enum SoeErrorCode {
+    /// No error.
+    NoError = 0x0000,
+    /// The IDN does not exist.
+    NoIdn = 0x1001,
+    /// Invalid access to element 1.
+    InvalidAccess = 0x1009,
+    /// The name does not exist.
+    NoName = 0x2001,
+    /// Name transmission too short.
+    NameTooShort = 0x2002,
+    /// Name transmission too long.
+    NameTooLong = 0x2003,
+    /// The name cannot be changed.
+    NameReadOnly = 0x2004,
+    /// The name is write protected at this time.
+    NameWriteProtected = 0x2005,
+    /// Attribute transmission too short.
+    AttributeTooShort = 0x3002,
+    /// Attribute transmission too long.
+    AttributeTooLong = 0x3003,
+    /// The attribute cannot be changed.
+    AttributeReadOnly = 0x3004,
+    /// The attribute is write protected at this time.
+    AttributeWriteProtected = 0x3005,
+    /// The unit does not exist.
+    NoUnit = 0x4001,
+    /// Unit transmission too short.
+    UnitTooShort = 0x4002,
+    /// Unit transmission too long.
+    UnitTooLong = 0x4003,
+    /// The unit cannot be changed.
+    UnitReadOnly = 0x4004,
+    /// The unit is write protected at this time.
+    UnitWriteProtected = 0x4005,
+    /// The minimum value does not exist.
+    NoMinimum = 0x5001,
+    /// Minimum value transmission too short.
+    MinimumTooShort = 0x5002,
+    /// Minimum value transmission too long.
+    MinimumTooLong = 0x5003,
+    /// The minimum value cannot be changed.
+    MinimumReadOnly = 0x5004,
+    /// The minimum value is write protected at this time.
+    MinimumWriteProtected = 0x5005,
+    /// The maximum value does not exist.
+    NoMaximum = 0x6001,
+    /// Maximum value transmission too short.
+    MaximumTooShort = 0x6002,
+    /// Maximum value transmission too long.
+    MaximumTooLong = 0x6003,
+    /// The maximum value cannot be changed.
+    MaximumReadOnly = 0x6004,
+    /// The maximum value is write protected at this time.
+    MaximumWriteProtected = 0x6005,
+    /// Value transmission too short.
+    ValueTooShort = 0x7002,
+    /// Value transmission too long.
+    ValueTooLong = 0x7003,
+    /// The value cannot be changed.
+    ValueReadOnly = 0x7004,
+    /// The value is write protected at this time, e.g. because of the current state.
+    ValueWriteProtected = 0x7005,
+    /// The value is smaller than the minimum value.
+    ValueBelowMinimum = 0x7006,
+    /// The value is greater than the maximum value.
+    ValueAboveMaximum = 0x7007,
+    /// The value is invalid.
+    InvalidValue = 0x7008,
+    /// The value is write protected by a password.
+    ValuePasswordProtected = 0x7009,
+    /// The value is write protected as it is configured cyclically.
+    ValueCyclic = 0x700a,
+    /// Invalid indirect addressing.
+    InvalidIndirectAddressing = 0x700b,
+    /// The value is write protected due to other settings.
+    ValueProtectedBySettings = 0x700c,
+    /// The procedure command is already active.
+    CommandActive = 0x7010,
+    /// The procedure command cannot be interrupted.
+    CommandNotInterruptible = 0x7011,
+    /// The procedure command cannot be executed at this time.
+    CommandNotExecutable = 0x7012,
+    /// The procedure command cannot be executed due to invalid parameters.
+    CommandInvalidParameters = 0x7013,
+    /// The data state does not exist.
+    NoDataState = 0x7014,
+    /// The default value does not exist.
+    NoDefault = 0x8001,
+    /// Default value transmission too long.
+    DefaultTooLong = 0x8002,
+    /// The default value cannot be changed.
+    DefaultReadOnly = 0x8004,
+    /// Invalid drive number.
+    InvalidDriveNumber = 0x800a,
+    /// General error.
+    GeneralError = 0x800b,
+    /// No element was addressed.
+    NoElement = 0x800c,
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u16),
+}
+
+impl core::fmt::Display for SoeErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoError => f.write_str("no error"),
+            Self::NoIdn => f.write_str("IDN does not exist"),
+            Self::InvalidAccess => f.write_str("invalid access to element 1"),
+            Self::NoName => f.write_str("name does not exist"),
+            Self::NameTooShort => f.write_str("name transmission too short"),
+            Self::NameTooLong => f.write_str("name transmission too long"),
+            Self::NameReadOnly => f.write_str("name cannot be changed"),
+            Self::NameWriteProtected => f.write_str("name is write protected at this time"),
+            Self::AttributeTooShort => f.write_str("attribute transmission too short"),
+            Self::AttributeTooLong => f.write_str("attribute transmission too long"),
+            Self::AttributeReadOnly => f.write_str("attribute cannot be changed"),
+            Self::AttributeWriteProtected => {
+                f.write_str("attribute is write protected at this time")
+            }
+            Self::NoUnit => f.write_str("unit does not exist"),
+            Self::UnitTooShort => f.write_str("unit transmission too short"),
+            Self::UnitTooLong => f.write_str("unit transmission too long"),
+            Self::UnitReadOnly => f.write_str("unit cannot be changed"),
+            Self::UnitWriteProtected => f.write_str("unit is write protected at this time"),
+            Self::NoMinimum => f.write_str("minimum value does not exist"),
+            Self::MinimumTooShort => f.write_str("minimum value transmission too short"),
+            Self::MinimumTooLong => f.write_str("minimum value transmission too long"),
+            Self::MinimumReadOnly => f.write_str("minimum value cannot be changed"),
+            Self::MinimumWriteProtected => {
+                f.write_str("minimum value is write protected at this time")
+            }
+            Self::NoMaximum => f.write_str("maximum value does not exist"),
+            Self::MaximumTooShort => f.write_str("maximum value transmission too short"),
+            Self::MaximumTooLong => f.write_str("maximum value transmission too long"),
+            Self::MaximumReadOnly => f.write_str("maximum value cannot be changed"),
+            Self::MaximumWriteProtected => {
+                f.write_str("maximum value is write protected at this time")
+            }
+            Self::ValueTooShort => f.write_str("value transmission too short"),
+            Self::ValueTooLong => f.write_str("value transmission too long"),
+            Self::ValueReadOnly => f.write_str("value cannot be changed"),
+            Self::ValueWriteProtected => f.write_str("value is write protected at this time"),
+            Self::ValueBelowMinimum => f.write_str("value is smaller than the minimum"),
+            Self::ValueAboveMaximum => f.write_str("value is greater than the maximum"),
+            Self::InvalidValue => f.write_str("invalid value"),
+            Self::ValuePasswordProtected => f.write_str("value is password protected"),
+            Self::ValueCyclic => f.write_str("value is configured cyclically"),
+            Self::InvalidIndirectAddressing => f.write_str("invalid indirect addressing"),
+            Self::ValueProtectedBySettings => {
+                f.write_str("value is write protected due to other settings")
+            }
+            Self::CommandActive => f.write_str("procedure command already active"),
+            Self::CommandNotInterruptible => f.write_str("procedure command not interruptible"),
+            Self::CommandNotExecutable => {
+                f.write_str("procedure command not executable at this time")
+            }
+            Self::CommandInvalidParameters => {
+                f.write_str("procedure command not executable with invalid parameters")
+            }
+            Self::NoDataState => f.write_str("data state does not exist"),
+            Self::NoDefault => f.write_str("default value does not exist"),
+            Self::DefaultTooLong => f.write_str("default value transmission too long"),
+            Self::DefaultReadOnly => f.write_str("default value cannot be changed"),
+            Self::InvalidDriveNumber => f.write_str("invalid drive number"),
+            Self::GeneralError => f.write_str("general error"),
+            Self::NoElement => f.write_str("no element addressed"),
+            Self::Unknown(code) => write!(f, "unknown error {:#06x}", code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWriteSized};
+
+    #[test]
+    fn encode_read_request() {
+        // S-0-0011, drive 1
+        assert_eq!(
+            SoeHeader::read(1, 0x000b, SoeElement::Value).pack(),
+            [0b0010_0001, 0x40, 0x0b, 0x00]
+        );
+    }
+
+    #[test]
+    fn encode_write_fragment() {
+        assert_eq!(
+            SoeHeader::write(0, 0x8001, SoeElement::Value, 2).pack(),
+            [0b0000_1011, 0x40, 0x02, 0x00]
+        );
+        assert_eq!(
+            SoeHeader::write(0, 0x8001, SoeElement::Value, 0).pack(),
+            [0b0000_0011, 0x40, 0x01, 0x80]
+        );
+    }
+
+    #[test]
+    fn decode_error_response() {
+        let raw = [0b0001_0010, 0x40, 0x0b, 0x00, 0x01, 0x10];
+
+        assert_eq!(
+            SoeHeader::unpack_from_slice(&raw),
+            Ok(SoeHeader {
+                op_code: SoeOpCode::ReadResponse,
+                incomplete: false,
+                error: true,
+                drive_number: 0,
+                elements: 0x40,
+                idn_or_fragments_left: 0x000b
+            })
+        );
+        assert_eq!(
+            SoeErrorCode::unpack_from_slice(&raw[4..]),
+            Ok(SoeErrorCode::NoIdn)
+        );
+    }
+}
@@ -0,0 +1,488 @@
+//! A blocking runner for process data cycles with timing statistics.
+
+use crate::{
+    error::Error,
+    subdevice_group::{CycleInfo, HasDc, HasPdi, NoDc},
+    MainDevice, SubDeviceGroup,
+};
+use core::{future::Future, ops::ControlFlow, time::Duration};
+use futures_lite::future::{block_on, zip};
+use std::{io, time::Instant};
+
+/// The result of one process data cycle of one or more groups, returned by
+/// [`CyclicGroup::cycle`].
+#[derive(Debug, Copy, Clone)]
+pub struct GroupCycle {
+    /// `true` if every group returned the working counter expected when all of its SubDevices are
+    /// in OP.
+    pub wkc_ok: bool,
+
+    /// Timing information from the first group with Distributed Clocks configured, if any.
+    pub cycle_info: Option<CycleInfo>,
+}
+
+impl GroupCycle {
+    fn and(self, other: Self) -> Self {
+        Self {
+            wkc_ok: self.wkc_ok && other.wkc_ok,
+            cycle_info: self.cycle_info.or(other.cycle_info),
+        }
+    }
+}
+
+/// One or more groups whose process data is exchanged by a [`CyclicRunner`].
+///
+/// This is implemented for [`SubDeviceGroup`]s with a PDI, references to them, and tuples of up to
+/// four of them. Groups in a tuple are sent at the same time.
+pub trait CyclicGroup {
+    /// Exchange process data with every group once.
+    ///
+    /// Groups with Distributed Clocks configured are sent with
+    /// [`tx_rx_dc`](SubDeviceGroup::tx_rx_dc), and all others with
+    /// [`tx_rx`](SubDeviceGroup::tx_rx).
+    fn cycle<'sto>(
+        &self,
+        maindevice: &'sto MainDevice<'sto>,
+    ) -> impl Future<Output = Result<GroupCycle, Error>>;
+}
+
+impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, S> CyclicGroup
+    for SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, S, NoDc>
+where
+    S: HasPdi,
+{
+    async fn cycle<'sto>(&self, maindevice: &'sto MainDevice<'sto>) -> Result<GroupCycle, Error> {
+        let response = self.tx_rx_response(maindevice).await?;
+
+        Ok(GroupCycle {
+            wkc_ok: response.wkc == response.expected_wkc,
+            cycle_info: None,
+        })
+    }
+}
+
+impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, S> CyclicGroup
+    for SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, S, HasDc>
+where
+    S: HasPdi,
+{
+    async fn cycle<'sto>(&self, maindevice: &'sto MainDevice<'sto>) -> Result<GroupCycle, Error> {
+        let (response, cycle_info) = self.tx_rx_dc_response(maindevice).await?;
+
+        Ok(GroupCycle {
+            wkc_ok: response.wkc == response.expected_wkc,
+            cycle_info: Some(cycle_info),
+        })
+    }
+}
+
+impl<G> CyclicGroup for &G
+where
+    G: CyclicGroup,
+{
+    fn cycle<'sto>(
+        &self,
+        maindevice: &'sto MainDevice<'sto>,
+    ) -> impl Future<Output = Result<GroupCycle, Error>> {
+        (**self).cycle(maindevice)
+    }
+}
+
+impl<A, B> CyclicGroup for (A, B)
+where
+    A: CyclicGroup,
+    B: CyclicGroup,
+{
+    async fn cycle<'sto>(&self, maindevice: &'sto MainDevice<'sto>) -> Result<GroupCycle, Error> {
+        let (a, b) = zip(self.0.cycle(maindevice), self.1.cycle(maindevice)).await;
+
+        Ok(a?.and(b?))
+    }
+}
+
+impl<A, B, C> CyclicGroup for (A, B, C)
+where
+    A: CyclicGroup,
+    B: CyclicGroup,
+    C: CyclicGroup,
+{
+    async fn cycle<'sto>(&self, maindevice: &'sto MainDevice<'sto>) -> Result<GroupCycle, Error> {
+        let (a, (b, c)) = zip(
+            self.0.cycle(maindevice),
+            zip(self.1.cycle(maindevice), self.2.cycle(maindevice)),
+        )
+        .await;
+
+        Ok(a?.and(b?).and(c?))
+    }
+}
+
+impl<A, B, C, D> CyclicGroup for (A, B, C, D)
+where
+    A: CyclicGroup,
+    B: CyclicGroup,
+    C: CyclicGroup,
+    D: CyclicGroup,
+{
+    async fn cycle<'sto>(&self, maindevice: &'sto MainDevice<'sto>) -> Result<GroupCycle, Error> {
+        let ((a, b), (c, d)) = zip(
+            zip(self.0.cycle(maindevice), self.1.cycle(maindevice)),
+            zip(self.2.cycle(maindevice), self.3.cycle(maindevice)),
+        )
+        .await;
+
+        Ok(a?.and(b?).and(c?).and(d?))
+    }
+}
+
+/// The minimum, maximum and mean of a set of durations.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DurationStats {
+    min: Duration,
+    max: Duration,
+    total: Duration,
+    count: u64,
+}
+
+impl DurationStats {
+    /// The shortest duration, or zero if none have been recorded.
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    /// The longest duration, or zero if none have been recorded.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// The mean of every duration, or zero if none have been recorded.
+    pub fn mean(&self) -> Duration {
+        self.total
+            .as_nanos()
+            .checked_div(u128::from(self.count))
+            .map_or(Duration::ZERO, |nanos| Duration::from_nanos(nanos as u64))
+    }
+
+    /// The number of durations recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    fn record(&mut self, duration: Duration) {
+        if self.count == 0 {
+            self.min = duration;
+            self.max = duration;
+        } else {
+            self.min = self.min.min(duration);
+            self.max = self.max.max(duration);
+        }
+
+        self.total = self.total.saturating_add(duration);
+        self.count += 1;
+    }
+}
+
+/// Statistics collected by a [`CyclicRunner`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CycleStats {
+    /// The number of cycles that have been run.
+    pub cycles: u64,
+
+    /// The time between the starts of consecutive cycles.
+    pub cycle_time: DurationStats,
+
+    /// How late each cycle started compared to when it was scheduled to start.
+    pub latency: DurationStats,
+
+    /// The number of cycles that took so long that the next cycle started late.
+    pub overruns: u64,
+
+    /// The number of cycles where a group did not return its expected working counter.
+    pub missed_wkc: u64,
+}
+
+/// Run the process data cycle of one or more groups on the current thread, keeping timing
+/// statistics.
+///
+/// Each cycle starts a fixed period after the previous one. If a group has Distributed Clocks
+/// configured, the next cycle is instead started after the
+/// [`next_cycle_wait`](CycleInfo::next_cycle_wait) returned by
+/// [`tx_rx_dc`](SubDeviceGroup::tx_rx_dc) so the cycle stays aligned with the SYNC0 pulse.
+///
+/// The runner blocks the current thread while waiting for the next cycle, using `timerfd` on
+/// Linux. It should be run in its own thread, ideally with a realtime priority, and the TX/RX task
+/// must be run in another thread.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{
+/// #     error::Error, std::CyclicRunner, subdevice_group::Op, MainDevice, SubDeviceGroup,
+/// # };
+/// # use core::ops::ControlFlow;
+/// # use std::time::Duration;
+/// # fn run<'a>(
+/// #     maindevice: &'a MainDevice<'a>,
+/// #     slow: &'a SubDeviceGroup<2, 4, Op>,
+/// #     fast: &'a SubDeviceGroup<1, 1, Op>,
+/// # ) -> Result<(), Error> {
+/// let mut runner =
+///     CyclicRunner::new((slow, fast), Duration::from_millis(1)).expect("Failed to create timer");
+///
+/// runner.run(maindevice, |(slow, fast), stats| {
+///     // Process data computations happen here
+///
+///     if stats.cycles < 10_000 {
+///         ControlFlow::Continue(())
+///     } else {
+///         ControlFlow::Break(())
+///     }
+/// })?;
+///
+/// let stats = runner.stats();
+///
+/// println!(
+///     "Cycle time min {:?} max {:?} mean {:?}, {} overruns",
+///     stats.cycle_time.min(),
+///     stats.cycle_time.max(),
+///     stats.cycle_time.mean(),
+///     stats.overruns
+/// );
+/// # Ok(()) }
+/// ```
+pub struct CyclicRunner<G> {
+    groups: G,
+    period: Duration,
+    timer: Timer,
+    stats: CycleStats,
+}
+
+impl<G> CyclicRunner<G>
+where
+    G: CyclicGroup,
+{
+    /// Create a new runner which exchanges the process data of `groups` every `period`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timer could not be created.
+    pub fn new(groups: G, period: Duration) -> Result<Self, io::Error> {
+        Ok(Self {
+            groups,
+            period,
+            timer: Timer::new()?,
+            stats: CycleStats::default(),
+        })
+    }
+
+    /// Run cycles until `f` returns [`ControlFlow::Break`] or an error occurs.
+    ///
+    /// `f` is called after the process data of every group has been exchanged with the current
+    /// statistics.
+    ///
+    /// Statistics are kept between calls to this method. Use
+    /// [`reset_stats`](CyclicRunner::reset_stats) to clear them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by a group's `tx_rx` method. A working counter mismatch is
+    /// not an error, and is counted in [`CycleStats::missed_wkc`] instead.
+    pub fn run<'sto, F>(
+        &mut self,
+        maindevice: &'sto MainDevice<'sto>,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&G, &CycleStats) -> ControlFlow<()>,
+    {
+        let mut deadline = Instant::now();
+        let mut last_start = None;
+
+        loop {
+            self.timer.wait_until(deadline);
+
+            let start = Instant::now();
+
+            self.stats
+                .latency
+                .record(start.saturating_duration_since(deadline));
+
+            if let Some(last_start) = last_start {
+                self.stats.cycle_time.record(start - last_start);
+            }
+
+            last_start = Some(start);
+
+            let GroupCycle { wkc_ok, cycle_info } = block_on(self.groups.cycle(maindevice))?;
+
+            self.stats.cycles += 1;
+
+            if !wkc_ok {
+                self.stats.missed_wkc += 1;
+            }
+
+            let flow = f(&self.groups, &self.stats);
+
+            deadline = match cycle_info {
+                Some(cycle_info) => start + cycle_info.next_cycle_wait,
+                None => deadline + self.period,
+            };
+
+            let now = Instant::now();
+
+            if now > deadline {
+                self.stats.overruns += 1;
+
+                // Skip any cycles that were missed completely instead of running them back to back
+                // to catch up. DC cycle waits are always calculated from the current time.
+                if cycle_info.is_none() && !self.period.is_zero() {
+                    while deadline < now {
+                        deadline += self.period;
+                    }
+                }
+            }
+
+            if flow.is_break() {
+                break Ok(());
+            }
+        }
+    }
+
+    /// Statistics collected since the runner was created or [`reset_stats`](CyclicRunner::reset_stats)
+    /// was called.
+    pub fn stats(&self) -> &CycleStats {
+        &self.stats
+    }
+
+    /// Clear all statistics.
+    pub fn reset_stats(&mut self) {
+        self.stats = CycleStats::default();
+    }
+
+    /// Get a reference to the groups driven by this runner.
+    pub fn groups(&self) -> &G {
+        &self.groups
+    }
+
+    /// Consume the runner, returning the groups it drove.
+    pub fn into_groups(self) -> G {
+        self.groups
+    }
+}
+
+#[cfg(target_os = "linux")]
+struct Timer(timerfd::TimerFd);
+
+#[cfg(target_os = "linux")]
+impl Timer {
+    fn new() -> Result<Self, io::Error> {
+        timerfd::TimerFd::new().map(Self)
+    }
+
+    fn wait_until(&mut self, deadline: Instant) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        // A zero duration disarms the timer instead of expiring immediately
+        if remaining.is_zero() {
+            return;
+        }
+
+        self.0.set_state(
+            timerfd::TimerState::Oneshot(remaining),
+            timerfd::SetTimeFlags::Default,
+        );
+
+        self.0.read();
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct Timer;
+
+#[cfg(not(target_os = "linux"))]
+impl Timer {
+    fn new() -> Result<Self, io::Error> {
+        Ok(Self)
+    }
+
+    fn wait_until(&mut self, deadline: Instant) {
+        std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MainDeviceConfig, PduStorage, Timeouts};
+    use core::cell::Cell;
+
+    struct FakeGroup {
+        cycles: Cell<u32>,
+    }
+
+    impl CyclicGroup for FakeGroup {
+        async fn cycle<'sto>(
+            &self,
+            _maindevice: &'sto MainDevice<'sto>,
+        ) -> Result<GroupCycle, Error> {
+            self.cycles.set(self.cycles.get() + 1);
+
+            Ok(GroupCycle {
+                // Every third call is missing a SubDevice
+                wkc_ok: self.cycles.get() % 3 != 0,
+                cycle_info: None,
+            })
+        }
+    }
+
+    #[test]
+    fn duration_stats() {
+        let mut stats = DurationStats::default();
+
+        assert_eq!(stats.mean(), Duration::ZERO);
+
+        stats.record(Duration::from_micros(20));
+        stats.record(Duration::from_micros(10));
+        stats.record(Duration::from_micros(60));
+
+        assert_eq!(stats.min(), Duration::from_micros(10));
+        assert_eq!(stats.max(), Duration::from_micros(60));
+        assert_eq!(stats.mean(), Duration::from_micros(30));
+        assert_eq!(stats.count(), 3);
+    }
+
+    #[test]
+    fn run_until_break() {
+        let storage: PduStorage<1, { PduStorage::element_size(8) }> = PduStorage::new();
+        let (_tx, _rx, pdu_loop) = storage.try_split().unwrap();
+
+        let maindevice =
+            MainDevice::new(pdu_loop, Timeouts::default(), MainDeviceConfig::default());
+
+        let group = FakeGroup {
+            cycles: Cell::new(0),
+        };
+
+        let mut runner = CyclicRunner::new((&group, &group), Duration::from_millis(1)).unwrap();
+
+        runner
+            .run(&maindevice, |_groups, stats| {
+                if stats.cycles < 5 {
+                    ControlFlow::Continue(())
+                } else {
+                    ControlFlow::Break(())
+                }
+            })
+            .unwrap();
+
+        let stats = runner.stats();
+
+        assert_eq!(group.cycles.get(), 10);
+        assert_eq!(stats.cycles, 5);
+        // Both groups share a counter, so the 3rd, 6th and 9th calls are spread over 3 cycles
+        assert_eq!(stats.missed_wkc, 3);
+        assert_eq!(stats.latency.count(), 5);
+        assert_eq!(stats.cycle_time.count(), 4);
+        assert!(stats.cycle_time.max() >= stats.cycle_time.min());
+    }
+}
@@ -0,0 +1,236 @@
+//! Bridge a SubDevice's EoE channel to a Linux TAP device, with inspiration from
+//! [https://github.com/embassy-rs/embassy](https://github.com/embassy-rs/embassy/blob/master/examples/std/src/tuntap.rs).
+
+use crate::{
+    error::Error,
+    fmt,
+    std::unix::{ifreq, ifreq_for},
+    SubDevice, SubDeviceRef,
+};
+use async_io::{Async, IoSafe, Timer};
+use core::{future::Future, ops::Deref, time::Duration};
+use futures_lite::{AsyncReadExt, AsyncWriteExt};
+use std::{
+    io,
+    os::{
+        fd::{AsFd, BorrowedFd},
+        unix::io::{AsRawFd, RawFd},
+    },
+};
+
+/// `_IOW('T', 202, int)`. These architectures use a different ioctl direction encoding.
+#[cfg(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+))]
+const TUNSETIFF: libc::c_ulong = 0x800454CA;
+/// `_IOW('T', 202, int)`.
+#[cfg(not(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+)))]
+const TUNSETIFF: libc::c_ulong = 0x400454CA;
+const IFF_TAP: libc::c_short = 0x0002;
+const IFF_NO_PI: libc::c_short = 0x1000;
+
+/// Longest Ethernet frame without FCS, plus a VLAN tag.
+const MAX_FRAME_LEN: usize = 1518;
+
+struct TapDesc {
+    fd: RawFd,
+}
+
+impl TapDesc {
+    fn new(name: &str) -> io::Result<Self> {
+        let fd = unsafe {
+            let fd = libc::open(c"/dev/net/tun".as_ptr(), libc::O_RDWR | libc::O_NONBLOCK);
+            if fd == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            fd
+        };
+
+        // Take ownership of the file descriptor so it is closed if creating the TAP device fails
+        let self_ = Self { fd };
+
+        let mut ifreq: ifreq = ifreq_for(name);
+        ifreq.set_flags(IFF_TAP | IFF_NO_PI);
+
+        unsafe {
+            #[allow(trivial_casts)]
+            #[cfg(target_env = "musl")]
+            let res = libc::ioctl(self_.fd, TUNSETIFF as libc::c_int, &mut ifreq as *mut ifreq);
+            #[allow(trivial_casts)]
+            #[cfg(not(target_env = "musl"))]
+            let res = libc::ioctl(self_.fd, TUNSETIFF, &mut ifreq as *mut ifreq);
+
+            if res == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(self_)
+    }
+}
+
+impl AsRawFd for TapDesc {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl AsFd for TapDesc {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.fd) }
+    }
+}
+
+// SAFETY: Implementing this trait pledges that the underlying file descriptor will not be dropped
+// by `Read` or `Write` impls. More information can be read
+// [here](https://docs.rs/async-io/latest/async_io/trait.IoSafe.html).
+unsafe impl IoSafe for TapDesc {}
+
+impl Drop for TapDesc {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+impl io::Read for TapDesc {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = unsafe { libc::read(self.fd, buf.as_mut_ptr().cast(), buf.len()) };
+        if len == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(len as usize)
+        }
+    }
+}
+
+impl io::Write for TapDesc {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = unsafe { libc::write(self.fd, buf.as_ptr().cast(), buf.len()) };
+        if len == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(len as usize)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Bridge a SubDevice's Ethernet over EtherCAT (EoE) channel to a TAP network interface.
+///
+/// This returns a future which forwards Ethernet frames between the TAP interface named
+/// `tap_name` and the SubDevice until an error occurs. The SubDevice is polled for frames it has
+/// sent every `poll_interval`. Mailbox errors and timeouts are logged and otherwise ignored, as a
+/// lost frame will be retried by higher level protocols.
+///
+/// The TAP interface is created if it does not already exist, which requires the `CAP_NET_ADMIN`
+/// capability. It must then be brought up and given an address in the same subnet as the
+/// SubDevice, e.g. with `ip link set dev <tap_name> up` and `ip addr add`. The SubDevice's own
+/// address can be set with
+/// [`SubDeviceRef::eoe_set_ip_parameters`](crate::SubDeviceRef::eoe_set_ip_parameters).
+///
+/// This function is only available on `linux` targets.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{SubDevice, SubDeviceRef, error::Error, std::eoe_tap_bridge};
+/// # use std::time::Duration;
+/// # async fn bridge(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+/// let bridge = eoe_tap_bridge("tap-ethercat", &subdevice, Duration::from_millis(5))
+///     .expect("Failed to create TAP device");
+///
+/// bridge.await?;
+/// # Ok(()) }
+/// ```
+pub fn eoe_tap_bridge<'r, S>(
+    tap_name: &str,
+    subdevice: &'r SubDeviceRef<'_, S>,
+    poll_interval: Duration,
+) -> Result<impl Future<Output = Result<(), Error>> + 'r, io::Error>
+where
+    S: Deref<Target = SubDevice>,
+{
+    let mut tap = Async::new(TapDesc::new(tap_name)?)?;
+
+    fmt::debug!(
+        "Bridging SubDevice {:#06x} EoE to TAP device {}",
+        subdevice.configured_address(),
+        tap_name
+    );
+
+    Ok(async move {
+        let mut rx_buf = [0u8; MAX_FRAME_LEN];
+        let mut tx_buf = [0u8; MAX_FRAME_LEN];
+
+        loop {
+            // Forward every frame the SubDevice has waiting
+            loop {
+                match subdevice.eoe_receive_frame(&mut rx_buf).await {
+                    Ok(Some(len)) => {
+                        tap.write_all(&rx_buf[0..len]).await.map_err(|e| {
+                            fmt::error!("Failed to write frame to TAP device: {}", e);
+
+                            Error::SendFrame
+                        })?;
+                    }
+                    Ok(None) => break,
+                    Err(e @ (Error::Mailbox(_) | Error::Timeout)) => {
+                        fmt::warn!(
+                            "SubDevice {:#06x} EoE receive failed: {}",
+                            subdevice.configured_address(),
+                            e
+                        );
+
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let received =
+                futures_lite::future::or(async { Some(tap.read(&mut tx_buf).await) }, async {
+                    Timer::after(poll_interval).await;
+
+                    None
+                })
+                .await;
+
+            match received {
+                Some(Ok(len)) => match subdevice.eoe_send_frame(&tx_buf[0..len]).await {
+                    Ok(()) => (),
+                    Err(e @ (Error::Mailbox(_) | Error::Timeout)) => {
+                        fmt::warn!(
+                            "SubDevice {:#06x} EoE send failed: {}",
+                            subdevice.configured_address(),
+                            e
+                        );
+                    }
+                    Err(e) => return Err(e),
+                },
+                Some(Err(e)) => {
+                    fmt::error!("Failed to read frame from TAP device: {}", e);
+
+                    return Err(Error::ReceiveFrame);
+                }
+                None => (),
+            }
+        }
+    })
+}
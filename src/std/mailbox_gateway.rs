@@ -0,0 +1,316 @@
+//! ETG.8200 mailbox gateway, forwarding mailbox requests from external tools received over UDP to
+//! SubDevices.
+
+use crate::{
+    eoe::{EoeFrameType, EoeHeader},
+    error::{Error, Item, PduError},
+    fmt,
+    foe::{FoeHeader, FoeOpCode},
+    mailbox::{MailboxType, Priority},
+    pdu_loop::frame_header::{EthercatFrameHeader, ProtocolType},
+    soe::SoeHeader,
+    SubDevice, SubDeviceRef,
+};
+use async_io::Async;
+use core::{future::Future, ops::Deref};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+};
+
+/// The UDP port mailbox gateways listen on, defined in ETG.8200.
+pub const MAILBOX_GATEWAY_PORT: u16 = 0x88a4;
+
+/// Longest EtherCAT frame that can be received or sent by the gateway.
+const MAX_FRAME_LEN: usize = 1500;
+
+/// Mailbox header including the station address of the addressed SubDevice.
+///
+/// Defined in ETG1000.4 Section 5.6 Table 29 – Mailbox.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 6)]
+struct GatewayMailboxHeader {
+    #[wire(bytes = 2)]
+    length: u16,
+    /// Configured station address of the SubDevice the mailbox is sent to or received from.
+    #[wire(bytes = 2)]
+    address: u16,
+    #[wire(pre_skip = 6, bits = 2)]
+    priority: Priority,
+    #[wire(bits = 4)]
+    mailbox_type: MailboxType,
+    #[wire(bits = 3, post_skip = 1)]
+    counter: u8,
+}
+
+/// Run an ETG.8200 mailbox gateway, forwarding mailbox requests from external tools such as
+/// vendor configuration software to SubDevices.
+///
+/// This returns a future which receives requests on a UDP socket bound to `addr`, usually on
+/// [`MAILBOX_GATEWAY_PORT`], until an error occurs. Each request is an EtherCAT frame holding a
+/// single mailbox, which is forwarded to the SubDevice whose configured station address is given
+/// in the mailbox header. The SubDevice's response is returned to the sender in the same format.
+///
+/// `lookup` is called with the configured address of every request to get the SubDevice to
+/// forward it to. Requests for which it returns `None` are dropped. Configured addresses start at
+/// `0x1000` for the first SubDevice on the network.
+///
+/// Requests are handled one at a time. Requests the SubDevice doesn't respond to, such as EoE
+/// frame fragments, are forwarded without waiting for or returning a response. The application
+/// should not make its own mailbox requests to a SubDevice while it is being used through the
+/// gateway.
+///
+/// The SubDevice returned by `lookup` is held until its request has been handled. Use
+/// [`SubDeviceGroup::mailbox_subdevice`](crate::SubDeviceGroup::mailbox_subdevice), which doesn't
+/// hold the SubDevice's process data, so the group's state can still be checked in the meantime.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{
+/// #     error::Error, std::{mailbox_gateway, MAILBOX_GATEWAY_PORT}, MainDevice, SubDeviceGroup,
+/// # };
+/// # use std::net::SocketAddr;
+/// # async fn gateway<'a>(
+/// #     maindevice: &'a MainDevice<'a>,
+/// #     group: &'a SubDeviceGroup<16, 64>,
+/// # ) -> Result<(), Error> {
+/// let gateway = mailbox_gateway(
+///     SocketAddr::from(([0, 0, 0, 0], MAILBOX_GATEWAY_PORT)),
+///     |address| {
+///         (0..group.len()).find_map(|index| {
+///             group
+///                 .mailbox_subdevice(maindevice, index)
+///                 .ok()
+///                 .filter(|subdevice| subdevice.configured_address() == address)
+///         })
+///     },
+/// )
+/// .expect("Failed to bind mailbox gateway socket");
+///
+/// gateway.await?;
+/// # Ok(()) }
+/// ```
+pub fn mailbox_gateway<'a, F, S>(
+    addr: SocketAddr,
+    mut lookup: F,
+) -> Result<impl Future<Output = Result<(), Error>> + 'a, io::Error>
+where
+    F: FnMut(u16) -> Option<SubDeviceRef<'a, S>> + 'a,
+    S: Deref<Target = SubDevice> + 'a,
+{
+    let socket = Async::<UdpSocket>::bind(addr)?;
+
+    fmt::debug!("Mailbox gateway listening on {}", addr);
+
+    Ok(async move {
+        let mut request = [0u8; MAX_FRAME_LEN];
+        let mut response = [0u8; MAX_FRAME_LEN];
+
+        loop {
+            let (len, client) = socket.recv_from(&mut request).await.map_err(|e| {
+                fmt::error!("Failed to receive mailbox gateway request: {}", e);
+
+                Error::ReceiveFrame
+            })?;
+
+            match forward_request(&mut lookup, &request[0..len], &mut response).await {
+                Ok(Some(len)) => {
+                    socket
+                        .send_to(&response[0..len], client)
+                        .await
+                        .map_err(|e| {
+                            fmt::error!("Failed to send mailbox gateway response: {}", e);
+
+                            Error::SendFrame
+                        })?;
+                }
+                Ok(None) => (),
+                // Errors are specific to a single request, so the gateway keeps running
+                Err(e) => {
+                    fmt::warn!("Mailbox gateway request from {} failed: {}", client, e);
+                }
+            }
+        }
+    })
+}
+
+/// Forward a single gateway request to its SubDevice, writing the response frame into `response`
+/// and returning its length.
+///
+/// Returns `Ok(None)` if the request should be ignored.
+async fn forward_request<'a, F, S>(
+    lookup: &mut F,
+    request: &[u8],
+    response: &mut [u8],
+) -> Result<Option<usize>, Error>
+where
+    F: FnMut(u16) -> Option<SubDeviceRef<'a, S>>,
+    S: Deref<Target = SubDevice>,
+{
+    let frame_header = EthercatFrameHeader::unpack_from_slice(request)?;
+
+    if frame_header.protocol != ProtocolType::Mailbox {
+        fmt::debug!(
+            "Ignoring mailbox gateway frame with protocol {:?}",
+            frame_header.protocol
+        );
+
+        return Ok(None);
+    }
+
+    let mailbox = request
+        .get(
+            EthercatFrameHeader::PACKED_LEN
+                ..(EthercatFrameHeader::PACKED_LEN + usize::from(frame_header.payload_len)),
+        )
+        .ok_or(Error::Pdu(PduError::Decode))?;
+
+    let header = GatewayMailboxHeader::unpack_from_slice(mailbox)?;
+
+    let data = mailbox
+        .get(
+            GatewayMailboxHeader::PACKED_LEN
+                ..(GatewayMailboxHeader::PACKED_LEN + usize::from(header.length)),
+        )
+        .ok_or(Error::Pdu(PduError::Decode))?;
+
+    let Some(subdevice) = lookup(header.address) else {
+        fmt::warn!(
+            "Mailbox gateway request for unknown SubDevice {:#06x}",
+            header.address
+        );
+
+        return Err(Error::NotFound {
+            item: Item::SubDevice,
+            index: None,
+        });
+    };
+
+    fmt::trace!(
+        "Mailbox gateway {:?} request of {} bytes to SubDevice {:#06x}",
+        header.mailbox_type,
+        data.len(),
+        header.address
+    );
+
+    let (frame_header_buf, rest) = response.split_at_mut(EthercatFrameHeader::PACKED_LEN);
+    let (mailbox_header_buf, data_buf) = rest.split_at_mut(GatewayMailboxHeader::PACKED_LEN);
+
+    let response = expects_response(header.mailbox_type, data).then_some(data_buf);
+
+    let Some((response_header, len)) = subdevice
+        .mailbox_forward(header.mailbox_type, data, response)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let mailbox_len = GatewayMailboxHeader::PACKED_LEN + len;
+
+    EthercatFrameHeader::mailbox(u16::try_from(mailbox_len)?).pack_to_slice(frame_header_buf)?;
+
+    GatewayMailboxHeader {
+        length: u16::try_from(len)?,
+        address: header.address,
+        priority: response_header.priority,
+        mailbox_type: response_header.mailbox_type,
+        counter: response_header.counter,
+    }
+    .pack_to_slice(mailbox_header_buf)?;
+
+    Ok(Some(EthercatFrameHeader::PACKED_LEN + mailbox_len))
+}
+
+/// Returns `false` if the SubDevice doesn't respond to the given mailbox request data.
+///
+/// Requests that can't be decoded are assumed to have a response, which may be a mailbox error
+/// reply.
+fn expects_response(mailbox_type: MailboxType, data: &[u8]) -> bool {
+    match mailbox_type {
+        MailboxType::Eoe => EoeHeader::unpack_from_slice(data).map_or(true, |header| {
+            header.frame_type != EoeFrameType::FragmentData
+        }),
+        MailboxType::Foe => FoeHeader::unpack_from_slice(data)
+            .map_or(true, |header| header.op_code != FoeOpCode::ErrorRequest),
+        // Only the last fragment of a request is responded to
+        MailboxType::Soe => {
+            SoeHeader::unpack_from_slice(data).map_or(true, |header| !header.incomplete)
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireWriteSized;
+
+    #[test]
+    fn decode_mailbox_header() {
+        // CoE SDO upload request to SubDevice 0x1001, counter 2
+        let raw = [0x0a, 0x00, 0x01, 0x10, 0x00, 0x23];
+
+        assert_eq!(
+            GatewayMailboxHeader::unpack_from_slice(&raw),
+            Ok(GatewayMailboxHeader {
+                length: 10,
+                address: 0x1001,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Coe,
+                counter: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn unanswered_requests() {
+        // EoE fragment
+        assert!(!expects_response(
+            MailboxType::Eoe,
+            &[0x00, 0x01, 0x00, 0x10, 0xff, 0xff]
+        ));
+        // EoE set IP parameter request
+        assert!(expects_response(
+            MailboxType::Eoe,
+            &[0x02, 0x01, 0x00, 0x00]
+        ));
+        // FoE error request
+        assert!(!expects_response(
+            MailboxType::Foe,
+            &[0x05, 0x00, 0x00, 0x80, 0x00, 0x00]
+        ));
+        // FoE read request
+        assert!(expects_response(
+            MailboxType::Foe,
+            &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00]
+        ));
+        // SoE write request with more fragments following
+        assert!(!expects_response(
+            MailboxType::Soe,
+            &[0x0b, 0x40, 0x02, 0x00]
+        ));
+        // Last SoE write request fragment
+        assert!(expects_response(
+            MailboxType::Soe,
+            &[0x03, 0x40, 0x20, 0x00]
+        ));
+        // Too short to decode
+        assert!(expects_response(MailboxType::Eoe, &[]));
+        assert!(expects_response(MailboxType::Coe, &[]));
+    }
+
+    #[test]
+    fn encode_mailbox_header() {
+        let header = GatewayMailboxHeader {
+            length: 10,
+            address: 0x1001,
+            priority: Priority::Lowest,
+            mailbox_type: MailboxType::Coe,
+            counter: 2,
+        };
+
+        assert_eq!(header.pack(), [0x0a, 0x00, 0x01, 0x10, 0x00, 0x23]);
+    }
+}
@@ -1,7 +1,11 @@
 //! Items required for running in `std` environments.
 
+mod cyclic;
+#[cfg(target_os = "linux")]
+mod eoe_tap;
 #[cfg(target_os = "linux")]
 mod io_uring;
+mod mailbox_gateway;
 #[cfg(unix)]
 mod unix;
 #[cfg(target_os = "windows")]
@@ -9,8 +13,13 @@ mod windows;
 
 #[cfg(target_os = "windows")]
 pub use self::windows::{ethercat_now, tx_rx_task};
+pub use cyclic::{CycleStats, CyclicGroup, CyclicRunner, DurationStats, GroupCycle};
 #[cfg(unix)]
 pub use unix::{ethercat_now, tx_rx_task};
+// TAP devices are only supported on Linux
+#[cfg(target_os = "linux")]
+pub use eoe_tap::eoe_tap_bridge;
+pub use mailbox_gateway::{mailbox_gateway, MAILBOX_GATEWAY_PORT};
 // io_uring is Linux-only
 #[cfg(target_os = "linux")]
 pub use io_uring::tx_rx_task_io_uring;
@@ -160,10 +160,11 @@ pub fn ethercat_now() -> u64 {
 
 // Unix only
 #[allow(trivial_numeric_casts)]
-fn ifreq_for(name: &str) -> ifreq {
+pub(in crate::std) fn ifreq_for(name: &str) -> ifreq {
     let mut ifreq = ifreq {
         ifr_name: [0; libc::IF_NAMESIZE],
         ifr_data: 0,
+        _padding: [0; IFR_IFRU_LEN - core::mem::size_of::<libc::c_int>()],
     };
     for (i, byte) in name.as_bytes().iter().enumerate() {
         ifreq.ifr_name[i] = *byte as libc::c_char;
@@ -171,10 +172,57 @@ fn ifreq_for(name: &str) -> ifreq {
     ifreq
 }
 
+/// Size of the `ifr_ifru` union in `struct ifreq`. Its largest member is `struct ifmap`, which
+/// holds two `unsigned long`s, an `unsigned short` and three `unsigned char`s.
+const IFR_IFRU_LEN: usize = (2 * core::mem::size_of::<libc::c_ulong>() + 5)
+    .next_multiple_of(core::mem::size_of::<libc::c_ulong>());
+
 #[repr(C)]
 #[derive(Debug)]
 #[allow(non_camel_case_types)]
-struct ifreq {
+pub(in crate::std) struct ifreq {
     ifr_name: [libc::c_char; libc::IF_NAMESIZE],
-    ifr_data: libc::c_int, /* ifr_ifindex or ifr_mtu */
+    pub(in crate::std) ifr_data: libc::c_int, /* ifr_ifindex or ifr_mtu */
+    /// The rest of the `ifr_ifru` union. Ioctls copy the whole struct back, so it must be as long
+    /// as the kernel's.
+    _padding: [u8; IFR_IFRU_LEN - core::mem::size_of::<libc::c_int>()],
+}
+
+impl ifreq {
+    /// Set `ifr_flags`, which is a `short` at the start of the `ifr_ifru` union.
+    #[cfg(target_os = "linux")]
+    pub(in crate::std) fn set_flags(&mut self, flags: libc::c_short) {
+        let [a, b] = flags.to_ne_bytes();
+
+        self.ifr_data = libc::c_int::from_ne_bytes([a, b, 0, 0]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn ifreq_layout() {
+        assert_eq!(
+            core::mem::size_of::<ifreq>(),
+            core::mem::size_of::<libc::ifreq>()
+        );
+
+        let mut ifreq = ifreq_for("tap0");
+
+        ifreq.set_flags(0x1002);
+
+        // Read back through the kernel's layout
+        let flags = unsafe {
+            core::ptr::addr_of!(ifreq)
+                .cast::<libc::ifreq>()
+                .read_unaligned()
+                .ifr_ifru
+                .ifru_flags
+        };
+
+        assert_eq!(flags, 0x1002);
+    }
 }
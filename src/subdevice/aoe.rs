@@ -0,0 +1,307 @@
+use super::{SubDevice, SubDeviceRef};
+use crate::{
+    aoe::{
+        AdsCommand, AdsDeviceState, AdsErrorCode, AdsReadRequest, AdsReadWriteRequest,
+        AdsWriteRequest, AmsAddress, AmsHeader,
+    },
+    error::{AoeError, Error, MailboxError},
+    fmt,
+    mailbox::MailboxType,
+    pdu_loop::ReceivedPdu,
+};
+use core::{ops::Deref, sync::atomic::Ordering};
+use ethercrab_wire::{
+    EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite, EtherCrabWireWriteSized,
+};
+
+/// The source address sent in every AoE request.
+///
+/// The SubDevice always responds through its mailbox, so no source address is required.
+const SOURCE_ADDRESS: AmsAddress = AmsAddress::new([0; 6], 0);
+
+/// ADS over EtherCAT (AoE) methods.
+///
+/// ADS requests are addressed to an AMS NetId and port, which are given by the SubDevice's
+/// documentation or configured in the SubDevice. The SubDevice must be in PRE-OP or higher.
+impl<'a, S> SubDeviceRef<'a, S>
+where
+    S: Deref<Target = SubDevice>,
+{
+    /// Read data from an ADS index group and offset into `buf`, returning the part of `buf` that
+    /// was written to.
+    ///
+    /// Up to `buf.len()` bytes are requested from the SubDevice.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, error::Error, AmsAddress};
+    /// # async fn read(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+    /// let target = AmsAddress::new([5, 12, 34, 56, 1, 1], 0x1001);
+    ///
+    /// let mut buf = [0u8; 4];
+    ///
+    /// let data = subdevice.aoe_read(target, 0xf302, 0, &mut buf).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn aoe_read<'buf>(
+        &self,
+        target: AmsAddress,
+        index_group: u32,
+        index_offset: u32,
+        buf: &'buf mut [u8],
+    ) -> Result<&'buf [u8], Error> {
+        let request = AdsReadRequest {
+            index_group,
+            index_offset,
+            length: u32::try_from(buf.len())?,
+        };
+
+        let (response, len) = self.aoe_request(target, AdsCommand::Read, request).await?;
+
+        Self::aoe_response_data(
+            response
+                .get(0..len)
+                .ok_or(Error::Mailbox(MailboxError::UnexpectedResponse))?,
+            buf,
+        )
+    }
+
+    /// Write data to an ADS index group and offset.
+    pub async fn aoe_write(
+        &self,
+        target: AmsAddress,
+        index_group: u32,
+        index_offset: u32,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let request = AdsWriteRequest {
+            index_group,
+            index_offset,
+            length: u32::try_from(data.len())?,
+        };
+
+        self.aoe_request(target, AdsCommand::Write, (request.pack().as_ref(), data))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Write `data` to an ADS index group and offset, then read the response into `buf` in a
+    /// single request, returning the part of `buf` that was written to.
+    ///
+    /// Up to `buf.len()` bytes are requested from the SubDevice.
+    pub async fn aoe_read_write<'buf>(
+        &self,
+        target: AmsAddress,
+        index_group: u32,
+        index_offset: u32,
+        data: &[u8],
+        buf: &'buf mut [u8],
+    ) -> Result<&'buf [u8], Error> {
+        let request = AdsReadWriteRequest {
+            index_group,
+            index_offset,
+            read_length: u32::try_from(buf.len())?,
+            write_length: u32::try_from(data.len())?,
+        };
+
+        let (response, len) = self
+            .aoe_request(
+                target,
+                AdsCommand::ReadWrite,
+                (request.pack().as_ref(), data),
+            )
+            .await?;
+
+        Self::aoe_response_data(
+            response
+                .get(0..len)
+                .ok_or(Error::Mailbox(MailboxError::UnexpectedResponse))?,
+            buf,
+        )
+    }
+
+    /// Read the ADS and device state of an ADS device.
+    pub async fn aoe_read_state(&self, target: AmsAddress) -> Result<AdsDeviceState, Error> {
+        let (response, len) = self.aoe_request(target, AdsCommand::ReadState, ()).await?;
+
+        Ok(AdsDeviceState::unpack_from_slice(
+            response
+                .get(0..len)
+                .ok_or(Error::Mailbox(MailboxError::UnexpectedResponse))?,
+        )?)
+    }
+
+    /// Send an ADS request and wait for its response.
+    ///
+    /// The returned data starts after the ADS result, which is checked for errors. The length of
+    /// the data is also returned, as the response may be shorter than the returned PDU.
+    async fn aoe_request(
+        &self,
+        target: AmsAddress,
+        command: AdsCommand,
+        data: impl EtherCrabWireWrite,
+    ) -> Result<(ReceivedPdu, usize), Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        let invoke_id = u32::from(self.state.aoe_invoke_id.fetch_add(1, Ordering::Relaxed));
+
+        fmt::trace!(
+            "SubDevice {:#06x} AoE {:?} to {}, invoke ID {}",
+            self.configured_address(),
+            command,
+            target,
+            invoke_id
+        );
+
+        let header = AmsHeader::request(
+            target,
+            SOURCE_ADDRESS,
+            command,
+            data.packed_len(),
+            invoke_id,
+        );
+
+        let read_mailbox = self
+            .mailbox_write(MailboxType::Aoe, (header.pack().as_ref(), data))
+            .await?;
+
+        loop {
+            let (mailbox_header, mut response) =
+                self.mailbox_read(&read_mailbox, MailboxType::Aoe).await?;
+
+            let header = AmsHeader::unpack_from_slice(&response)?;
+
+            if !header.is_response() || header.invoke_id != invoke_id {
+                fmt::debug!(
+                    "SubDevice {:#06x} ignoring AoE {:?} with invoke ID {}",
+                    self.configured_address(),
+                    header.command,
+                    header.invoke_id
+                );
+
+                self.set_response_pending(true);
+
+                continue;
+            }
+
+            if header.command != command {
+                fmt::error!(
+                    "SubDevice {:#06x} invalid AoE response {:?}, expected {:?}",
+                    self.configured_address(),
+                    header.command,
+                    command
+                );
+
+                return Err(Error::Mailbox(MailboxError::UnexpectedResponse));
+            }
+
+            response.trim_front(AmsHeader::PACKED_LEN);
+
+            // The AMS error code is set if the request could not be delivered, otherwise the ADS
+            // data always starts with a result code.
+            let code = if header.error_code != AdsErrorCode::NoError {
+                header.error_code
+            } else {
+                AdsErrorCode::unpack_from_slice(&response)?
+            };
+
+            if code != AdsErrorCode::NoError {
+                fmt::error!(
+                    "SubDevice {:#06x} AoE {:?} to {} failed: {}",
+                    self.configured_address(),
+                    command,
+                    target,
+                    code
+                );
+
+                return Err(Error::Mailbox(MailboxError::Aoe(AoeError::Ads(code))));
+            }
+
+            response.trim_front(AdsErrorCode::PACKED_LEN);
+
+            let data_len = usize::from(mailbox_header.length)
+                .saturating_sub(AmsHeader::PACKED_LEN)
+                .min(header.length as usize)
+                .saturating_sub(AdsErrorCode::PACKED_LEN);
+
+            if data_len > response.len() {
+                fmt::error!(
+                    "SubDevice {:#06x} AoE {:?} response of {} bytes is shorter than its length {}",
+                    self.configured_address(),
+                    command,
+                    response.len(),
+                    data_len
+                );
+
+                return Err(Error::Mailbox(MailboxError::UnexpectedResponse));
+            }
+
+            break Ok((response, data_len));
+        }
+    }
+
+    /// Copy the data from a Read or ReadWrite response into `buf`.
+    fn aoe_response_data<'buf>(response: &[u8], buf: &'buf mut [u8]) -> Result<&'buf [u8], Error> {
+        let len = u32::unpack_from_slice(response)? as usize;
+
+        let data = response
+            .get(u32::PACKED_LEN..(u32::PACKED_LEN + len))
+            .ok_or_else(|| {
+                fmt::error!(
+                    "AoE response data length {} is longer than the response of {} bytes",
+                    len,
+                    response.len()
+                );
+
+                Error::Mailbox(MailboxError::UnexpectedResponse)
+            })?;
+
+        let buf = buf
+            .get_mut(0..len)
+            .ok_or(Error::Mailbox(MailboxError::Aoe(AoeError::TooLong)))?;
+
+        buf.copy_from_slice(data);
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Ref<'a> = SubDeviceRef<'a, &'a SubDevice>;
+
+    #[test]
+    fn response_data() {
+        let mut buf = [0u8; 4];
+
+        assert_eq!(
+            Ref::aoe_response_data(&[0x02, 0x00, 0x00, 0x00, 0xaa, 0xbb, 0xcc], &mut buf),
+            Ok([0xaa, 0xbb].as_slice())
+        );
+    }
+
+    #[test]
+    fn response_data_truncated() {
+        let mut buf = [0u8; 4];
+
+        // Length says 3 bytes but only 2 were received
+        assert_eq!(
+            Ref::aoe_response_data(&[0x03, 0x00, 0x00, 0x00, 0xaa, 0xbb], &mut buf),
+            Err(Error::Mailbox(MailboxError::UnexpectedResponse))
+        );
+    }
+
+    #[test]
+    fn response_data_too_long() {
+        let mut buf = [0u8; 1];
+
+        assert_eq!(
+            Ref::aoe_response_data(&[0x02, 0x00, 0x00, 0x00, 0xaa, 0xbb], &mut buf),
+            Err(Error::Mailbox(MailboxError::Aoe(AoeError::TooLong)))
+        );
+    }
+}
@@ -1,21 +1,194 @@
 use super::{SubDevice, SubDeviceRef};
 use crate::{
+    base_data_types::PrimitiveDataType,
     coe::{SdoExpedited, SubIndex},
     eeprom::types::{
-        CoeDetails, FmmuUsage, MailboxProtocols, Pdo, SiiOwner, SyncManager, SyncManagerEnable,
-        SyncManagerType,
+        CoeDetails, FmmuUsage, MailboxProtocols, Pdo, PdoEntry, SiiOwner, SyncManager,
+        SyncManagerEnable, SyncManagerType,
     },
-    error::{Error, Item},
+    error::{Error, Item, MailboxError},
     fmmu::Fmmu,
     fmt,
     pdi::{PdiOffset, PdiSegment},
     register::RegisterAddress,
-    subdevice::types::{Mailbox, MailboxConfig},
+    subdevice::types::{
+        name_hash, EntryName, Mailbox, MailboxConfig, PdoEntries, PdoEntryLayout, MAX_PDO_ENTRIES,
+    },
     subdevice_state::SubDeviceState,
     sync_manager_channel::{Enable, Status, SyncManagerChannel, SM_BASE_ADDRESS, SM_TYPE_ADDRESS},
 };
 use core::ops::DerefMut;
 
+/// Bootstrap (BOOT state) methods, used to update SubDevice firmware.
+impl<'a, S> SubDeviceRef<'a, S>
+where
+    S: DerefMut<Target = SubDevice>,
+{
+    /// Transition this SubDevice into BOOT state, e.g. to update its firmware using
+    /// [`foe_write`](SubDeviceRef::foe_write).
+    ///
+    /// The SubDevice is first put into INIT, then its mailbox sync managers are reconfigured with
+    /// the bootstrap mailbox settings read from its EEPROM before BOOT is requested. All mailbox
+    /// methods use the bootstrap mailbox until [`exit_bootstrap`](SubDeviceRef::exit_bootstrap) is
+    /// called.
+    ///
+    /// Only this SubDevice changes state. The group it belongs to should not be used to exchange
+    /// process data while the SubDevice is in BOOT.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MailboxError::NoMailbox`](crate::error::MailboxError::NoMailbox) if the SubDevice
+    /// does not define a bootstrap mailbox in its EEPROM.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, error::Error};
+    /// # async fn update(
+    /// #     mut subdevice: SubDeviceRef<'_, &mut SubDevice>,
+    /// #     firmware: &[u8],
+    /// # ) -> Result<(), Error> {
+    /// subdevice.enter_bootstrap().await?;
+    ///
+    /// subdevice.foe_write("firmware.efw", 0, firmware).await?;
+    ///
+    /// subdevice.exit_bootstrap().await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn enter_bootstrap(&mut self) -> Result<(), Error> {
+        let bootstrap = self.eeprom().bootstrap_mailbox_config().await?;
+
+        fmt::trace!(
+            "SubDevice {:#06x} bootstrap mailbox configuration: {:#?}",
+            self.configured_address,
+            bootstrap
+        );
+
+        if !bootstrap.has_mailbox() {
+            fmt::error!(
+                "SubDevice {:#06x} has no bootstrap mailbox",
+                self.configured_address
+            );
+
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        }
+
+        // BOOT can only be requested from INIT, which is also where mailboxes must be configured
+        self.request_subdevice_state(SubDeviceState::Init).await?;
+
+        self.set_eeprom_mode(SiiOwner::Master).await?;
+
+        let sync_managers = self.eeprom().sync_managers().await?;
+
+        let mut read_mailbox = None;
+        let mut write_mailbox = None;
+
+        for (sync_manager_index, sync_manager) in sync_managers.iter().enumerate() {
+            let sync_manager_index = sync_manager_index as u8;
+
+            match sync_manager.usage_type {
+                SyncManagerType::MailboxWrite => {
+                    let mailbox = Mailbox {
+                        address: bootstrap.subdevice_receive_offset,
+                        len: bootstrap.subdevice_receive_size,
+                        sync_manager: sync_manager_index,
+                    };
+
+                    self.write_bootstrap_sm_config(sync_manager, mailbox)
+                        .await?;
+
+                    write_mailbox = Some(mailbox);
+                }
+                SyncManagerType::MailboxRead => {
+                    let mailbox = Mailbox {
+                        address: bootstrap.subdevice_send_offset,
+                        len: bootstrap.subdevice_send_size,
+                        sync_manager: sync_manager_index,
+                    };
+
+                    self.write_bootstrap_sm_config(sync_manager, mailbox)
+                        .await?;
+
+                    read_mailbox = Some(mailbox);
+                }
+                _ => continue,
+            }
+        }
+
+        if read_mailbox.is_none() || write_mailbox.is_none() {
+            fmt::error!(
+                "SubDevice {:#06x} has no mailbox sync managers",
+                self.configured_address
+            );
+
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        }
+
+        self.state.config.mailbox.read = read_mailbox;
+        self.state.config.mailbox.write = write_mailbox;
+
+        self.set_eeprom_mode(SiiOwner::Pdi).await?;
+
+        fmt::debug!(
+            "SubDevice {:#06x} bootstrap mailbox SMs configured. Transitioning to BOOT",
+            self.configured_address
+        );
+
+        self.request_subdevice_state(SubDeviceState::Bootstrap)
+            .await?;
+
+        self.set_eeprom_mode(SiiOwner::Master).await?;
+
+        Ok(())
+    }
+
+    /// Transition this SubDevice from BOOT back to INIT, restoring its standard mailbox
+    /// configuration.
+    ///
+    /// The SubDevice is left in INIT. Many SubDevices must be power cycled to start new firmware,
+    /// after which the network should be initialised again with
+    /// [`MainDevice::init`](crate::MainDevice::init).
+    pub async fn exit_bootstrap(&mut self) -> Result<(), Error> {
+        self.request_subdevice_state(SubDeviceState::Init).await?;
+
+        self.set_eeprom_mode(SiiOwner::Master).await?;
+
+        let sync_managers = self.eeprom().sync_managers().await?;
+
+        // Sync manager types are only read from CoE during initialisation, so must be preserved
+        let coe_sync_manager_types =
+            core::mem::take(&mut self.state.config.mailbox.coe_sync_manager_types);
+
+        self.configure_mailbox_sms(&sync_managers).await?;
+
+        self.state.config.mailbox.coe_sync_manager_types = coe_sync_manager_types;
+
+        fmt::debug!(
+            "SubDevice {:#06x} left BOOT, standard mailbox SMs restored",
+            self.configured_address
+        );
+
+        Ok(())
+    }
+
+    /// Configure a mailbox sync manager with a bootstrap mailbox address and length.
+    async fn write_bootstrap_sm_config(
+        &self,
+        sync_manager: &SyncManager,
+        mailbox: Mailbox,
+    ) -> Result<SyncManagerChannel, Error> {
+        self.write_sm_config(
+            mailbox.sync_manager,
+            &SyncManager {
+                start_addr: mailbox.address,
+                ..*sync_manager
+            },
+            mailbox.len,
+        )
+        .await
+    }
+}
+
 /// Configuation from EEPROM methods.
 impl<'a, S> SubDeviceRef<'a, S>
 where
@@ -107,6 +280,7 @@ where
         mut global_offset: PdiOffset,
         group_start_address: u32,
         direction: PdoDirection,
+        bit_packed: bool,
     ) -> Result<PdiOffset, Error> {
         let sync_managers = self.eeprom().sync_managers().await?;
         let fmmu_usage = self.eeprom().fmmus().await?;
@@ -136,12 +310,32 @@ where
             has_coe
         );
 
+        let input = matches!(direction, PdoDirection::MasterRead);
+
+        self.state
+            .config
+            .io
+            .entries
+            .retain(|entry| entry.input != input);
+
         let range = if has_coe {
-            self.configure_pdos_coe(&sync_managers, &fmmu_usage, direction, &mut global_offset)
-                .await?
+            self.configure_pdos_coe(
+                &sync_managers,
+                &fmmu_usage,
+                direction,
+                bit_packed,
+                &mut global_offset,
+            )
+            .await?
         } else {
-            self.configure_pdos_eeprom(&sync_managers, &fmmu_usage, direction, &mut global_offset)
-                .await?
+            self.configure_pdos_eeprom(
+                &sync_managers,
+                &fmmu_usage,
+                direction,
+                bit_packed,
+                &mut global_offset,
+            )
+            .await?
         };
 
         match direction {
@@ -173,6 +367,107 @@ where
         Ok(global_offset)
     }
 
+    /// Read the names of this SubDevice's PDO entries from EEPROM so they can be found by name.
+    ///
+    /// Entries mapped using CoE are matched with the PDOs listed in EEPROM by PDO, index and
+    /// sub-index to find their names and data types.
+    pub(crate) async fn load_entry_names(&mut self) -> Result<(), Error> {
+        if self.state.config.mailbox.has_coe {
+            self.sii_entry_names(PdoDirection::MasterRead).await?;
+            self.sii_entry_names(PdoDirection::MasterWrite).await?;
+        }
+
+        self.hash_entry_names().await
+    }
+
+    /// Map the read mailbox sync manager status into the PDI using a spare FMMU, so mailbox
+    /// responses can be detected from cyclic process data instead of polling the SubDevice.
+    ///
+    /// An FMMU reserved for sync manager status in the SubDevice EEPROM is preferred, otherwise the
+    /// first FMMU not already used for process data is chosen. If the SubDevice has no mailbox or
+    /// no spare FMMU, nothing is mapped and the mailbox status is polled as normal.
+    pub(crate) async fn configure_mailbox_status_fmmu(
+        &mut self,
+        global_offset: PdiOffset,
+        group_start_address: u32,
+    ) -> Result<PdiOffset, Error> {
+        let Some(read_mailbox) = self.state.config.mailbox.read else {
+            return Ok(global_offset);
+        };
+
+        let fmmu_usage = self.eeprom().fmmus().await?;
+
+        // Up to 16 FMMUs as per ETG1000.4 Table 57
+        let fmmu_count = self
+            .read(RegisterAddress::FmmuCount)
+            .receive::<u8>(self.maindevice)
+            .await?
+            .min(16);
+
+        let candidates = fmmu_usage
+            .iter()
+            .enumerate()
+            .filter(|(_index, usage)| **usage == FmmuUsage::SyncManagerStatus)
+            .map(|(index, _usage)| index as u8)
+            .chain(0..fmmu_count)
+            .filter(|index| *index < fmmu_count);
+
+        let mut fmmu_index = None;
+
+        for index in candidates {
+            let fmmu = self
+                .read(RegisterAddress::fmmu(index))
+                .receive::<Fmmu>(self.maindevice)
+                .await?;
+
+            // FMMUs are zeroed on startup, so any enabled FMMU is already used for process data
+            if !fmmu.enable {
+                fmmu_index = Some(index);
+
+                break;
+            }
+        }
+
+        let Some(fmmu_index) = fmmu_index else {
+            fmt::warn!(
+                "SubDevice {:#06x} has no spare FMMU to map mailbox status. Mailbox will be polled.",
+                self.configured_address
+            );
+
+            return Ok(global_offset);
+        };
+
+        let fmmu_config = Fmmu {
+            logical_start_address: global_offset.start_address,
+            length_bytes: 1,
+            logical_start_bit: 0,
+            logical_end_bit: 7,
+            physical_start_address: RegisterAddress::sync_manager_status(read_mailbox.sync_manager),
+            physical_start_bit: 0,
+            read_enable: true,
+            write_enable: false,
+            enable: true,
+        };
+
+        self.write(RegisterAddress::fmmu(fmmu_index))
+            .send(self.maindevice, fmmu_config)
+            .await?;
+
+        fmt::debug!(
+            "SubDevice {:#06x} FMMU{} (mailbox status): {}",
+            self.configured_address,
+            fmmu_index,
+            fmmu_config
+        );
+
+        self.state.config.io.mailbox_status =
+            Some((global_offset.start_address - group_start_address) as usize);
+
+        self.state.mailbox_status.set_mapped(true);
+
+        Ok(global_offset.increment(1))
+    }
+
     async fn write_sm_config(
         &self,
         sync_manager_index: u8,
@@ -287,10 +582,11 @@ where
 
     /// Configure PDOs from CoE registers.
     async fn configure_pdos_coe(
-        &self,
+        &mut self,
         sync_managers: &[SyncManager],
         fmmu_usage: &[FmmuUsage],
         direction: PdoDirection,
+        bit_packed: bool,
         global_offset: &mut PdiOffset,
     ) -> Result<PdiSegment, Error> {
         if !self.state.config.mailbox.has_coe {
@@ -308,30 +604,23 @@ where
         //     .sdo_read::<u8>(SM_TYPE_ADDRESS, SubIndex::Index(0))
         //     .await?;
 
-        let start_offset = *global_offset;
-        let mut total_bit_len = 0;
+        // Bit length of each sync manager of the desired type, read before anything is written so
+        // the position of the SubDevice's data in the PDI can be chosen up front. Entries are
+        // recorded with offsets from the start of their sync manager's data, which are moved once
+        // that position is known, so the end of each sync manager's entries is kept too.
+        let mut sm_bit_lens = heapless::Vec::<(u8, u16, usize), 16>::new();
+
+        let mut entries_start = self.state.config.io.entries.len();
+        let mut dropped_entries = 0u16;
+
+        for sync_manager_index in 0..self.state.config.mailbox.coe_sync_manager_types.len() {
+            let sm_type = self.state.config.mailbox.coe_sync_manager_types[sync_manager_index];
 
-        for (sync_manager_index, sm_type) in self
-            .state
-            .config
-            .mailbox
-            .coe_sync_manager_types
-            .iter()
-            .enumerate()
-        {
             let sync_manager_index = sync_manager_index as u8;
 
             let sm_address = SM_BASE_ADDRESS + u16::from(sync_manager_index);
 
-            let sync_manager =
-                sync_managers
-                    .get(usize::from(sync_manager_index))
-                    .ok_or(Error::NotFound {
-                        item: Item::SyncManager,
-                        index: Some(usize::from(sync_manager_index)),
-                    })?;
-
-            if *sm_type != desired_sm_type {
+            if sm_type != desired_sm_type {
                 continue;
             }
 
@@ -398,6 +687,21 @@ where
                         mapping_bit_len,
                     );
 
+                    let entry = PdoEntryLayout {
+                        pdo,
+                        index,
+                        sub_index,
+                        bit_len: mapping_bit_len,
+                        bit_offset: sm_bit_len,
+                        data_type: PrimitiveDataType::Unknown,
+                        input: matches!(direction, PdoDirection::MasterRead),
+                        name: EntryName::default(),
+                    };
+
+                    if !push_entry(&mut self.state.config.io.entries, entry) {
+                        dropped_entries += 1;
+                    }
+
                     sm_bit_len += u16::from(mapping_bit_len);
                 }
             }
@@ -408,6 +712,47 @@ where
                 (sm_bit_len + 7) / 8
             );
 
+            sm_bit_lens
+                .push((
+                    sync_manager_index,
+                    sm_bit_len,
+                    self.state.config.io.entries.len(),
+                ))
+                .map_err(|_| Error::Capacity(Item::SyncManager))?;
+        }
+
+        warn_dropped_entries(self.configured_address, dropped_entries);
+
+        let (start, packed) = segment_start(
+            *global_offset,
+            sm_bit_lens
+                .iter()
+                .map(|(_sm, bit_len, _entries_end)| *bit_len),
+            bit_packed,
+        );
+
+        *global_offset = start;
+
+        let start_offset = *global_offset;
+        let mut total_bit_len = 0;
+
+        for (sync_manager_index, sm_bit_len, entries_end) in sm_bit_lens {
+            let sync_manager =
+                sync_managers
+                    .get(usize::from(sync_manager_index))
+                    .ok_or(Error::NotFound {
+                        item: Item::SyncManager,
+                        index: Some(usize::from(sync_manager_index)),
+                    })?;
+
+            let sm_bit_offset = sm_bit_offset(start_offset, *global_offset);
+
+            for entry in self.state.config.io.entries[entries_start..entries_end].iter_mut() {
+                entry.bit_offset += sm_bit_offset;
+            }
+
+            entries_start = entries_end;
+
             let sm_config = self
                 .write_sm_config(sync_manager_index, sync_manager, (sm_bit_len + 7) / 8)
                 .await?;
@@ -427,6 +772,7 @@ where
                     global_offset,
                     desired_sm_type,
                     &sm_config,
+                    packed,
                 )
                 .await?;
             }
@@ -437,6 +783,8 @@ where
         Ok(PdiSegment {
             bit_len: total_bit_len.into(),
             bytes: start_offset.up_to(*global_offset),
+            start_bit: start_offset.start_bit,
+            bit_packed: packed,
         })
     }
 
@@ -447,6 +795,7 @@ where
         global_offset: &mut PdiOffset,
         desired_sm_type: SyncManagerType,
         sm_config: &SyncManagerChannel,
+        bit_packed: bool,
     ) -> Result<(), Error> {
         // Multiple SMs may use the same FMMU, so we'll read the existing config from the SubDevice
         let mut fmmu_config = self
@@ -460,11 +809,24 @@ where
             fmmu_config.length_bytes += sm_config.length_bytes;
 
             fmmu_config
+        } else if bit_packed {
+            // Bit-packed data always fits in a single byte of the PDI
+            Fmmu {
+                logical_start_address: global_offset.start_address,
+                length_bytes: 1,
+                logical_start_bit: global_offset.start_bit,
+                logical_end_bit: global_offset.end_bit(sm_bit_len),
+                physical_start_address: sm_config.physical_start_address,
+                physical_start_bit: 0x0,
+                read_enable: desired_sm_type == SyncManagerType::ProcessDataRead,
+                write_enable: desired_sm_type == SyncManagerType::ProcessDataWrite,
+                enable: true,
+            }
         } else {
             Fmmu {
                 logical_start_address: global_offset.start_address,
                 length_bytes: sm_config.length_bytes,
-                // Mapping into PDI is byte-aligned until/if we support bit-oriented SubDevices
+                // Byte-aligned unless the group uses bit-packed mapping
                 logical_start_bit: 0,
                 // Always byte-aligned
                 logical_end_bit: 7,
@@ -487,29 +849,135 @@ where
             fmmu_config
         );
 
-        *global_offset = global_offset.increment_byte_aligned(sm_bit_len);
+        *global_offset = if bit_packed {
+            global_offset.increment_bits(sm_bit_len)
+        } else if sm_bit_len > 0 {
+            global_offset.increment_byte_aligned(sm_bit_len)
+        } else {
+            *global_offset
+        };
+
+        Ok(())
+    }
+
+    /// Fill in the names and data types of PDO entries mapped with CoE from the PDOs listed in the
+    /// SubDevice EEPROM.
+    async fn sii_entry_names(&mut self, direction: PdoDirection) -> Result<(), Error> {
+        let input = matches!(direction, PdoDirection::MasterRead);
+
+        let eeprom = self.eeprom();
+        let entries = &mut self.state.config.io.entries;
+
+        let on_entry = |pdo: &Pdo, sii_entry: &PdoEntry| {
+            for entry in entries.iter_mut().filter(|entry| {
+                entry.input == input
+                    && entry.pdo == pdo.index
+                    && entry.index == sii_entry.index
+                    && entry.sub_index == sii_entry.sub_index
+            }) {
+                entry.data_type = sii_entry.data_type;
+                entry.name = EntryName {
+                    pdo_string_idx: pdo.name_string_idx,
+                    string_idx: sii_entry.name_string_idx,
+                    ..EntryName::default()
+                };
+            }
+        };
+
+        match direction {
+            PdoDirection::MasterRead => eeprom.master_read_pdos(on_entry).await?,
+            PdoDirection::MasterWrite => eeprom.master_write_pdos(on_entry).await?,
+        };
 
         Ok(())
     }
 
+    /// Hash the EEPROM names of PDO entries so they can be found by name later.
+    async fn hash_entry_names(&mut self) -> Result<(), Error> {
+        let eeprom = self.eeprom();
+        let entries = &mut self.state.config.io.entries;
+
+        if entries.iter().all(|entry| entry.name.string_idx == 0) {
+            return Ok(());
+        }
+
+        eeprom
+            .strings(|string_idx, string| {
+                let hash = name_hash(string);
+
+                for entry in entries.iter_mut() {
+                    if entry.name.pdo_string_idx == string_idx {
+                        entry.name.pdo_hash = Some(hash);
+                    }
+
+                    if entry.name.string_idx == string_idx {
+                        entry.name.hash = Some(hash);
+                    }
+                }
+            })
+            .await
+    }
+
     /// Configure PDOs from EEPROM
     async fn configure_pdos_eeprom(
-        &self,
+        &mut self,
         sync_managers: &[SyncManager],
         fmmu_usage: &[FmmuUsage],
         direction: PdoDirection,
+        bit_packed: bool,
         offset: &mut PdiOffset,
     ) -> Result<PdiSegment, Error> {
+        let input = matches!(direction, PdoDirection::MasterRead);
+
+        let (sm_type, fmmu_type) = direction.filter_terms();
+
+        let eeprom = self.eeprom();
+        let entries = &mut self.state.config.io.entries;
+
+        // Entries are read for all PDOs before it's known where their sync manager's data is, so
+        // their offsets are filled in below.
+        let entries_start = entries.len();
+        let mut dropped_entries = 0u16;
+
+        let on_entry = |pdo: &Pdo, entry: &PdoEntry| {
+            let in_direction = sync_managers
+                .get(usize::from(pdo.sync_manager))
+                .is_some_and(|sm| sm.usage_type == sm_type);
+
+            if !in_direction {
+                return;
+            }
+
+            let entry = PdoEntryLayout {
+                pdo: pdo.index,
+                index: entry.index,
+                sub_index: entry.sub_index,
+                bit_len: entry.data_length_bits,
+                bit_offset: 0,
+                data_type: entry.data_type,
+                input,
+                name: EntryName {
+                    pdo_string_idx: pdo.name_string_idx,
+                    string_idx: entry.name_string_idx,
+                    ..EntryName::default()
+                },
+            };
+
+            if !push_entry(entries, entry) {
+                dropped_entries += 1;
+            }
+        };
+
         let pdos: heapless::Vec<Pdo, 16> = match direction {
             PdoDirection::MasterRead => {
-                let read_pdos = self.eeprom().master_read_pdos().await?;
+                let read_pdos = eeprom.master_read_pdos(on_entry).await?;
 
                 fmt::trace!("SubDevice inputs PDOs {:#?}", read_pdos);
 
                 read_pdos
             }
             PdoDirection::MasterWrite => {
-                let write_pdos = self.eeprom().master_write_pdos().await?;
+                let write_pdos = eeprom.master_write_pdos(on_entry).await?;
 
                 fmt::trace!("SubDevice outputs PDOs {:#?}", write_pdos);
 
@@ -517,12 +985,34 @@ where
             }
         };
 
+        warn_dropped_entries(self.configured_address, dropped_entries);
+
         let fmmu_sm_mappings = self.eeprom().fmmu_mappings().await?;
 
+        let sm_bit_len = |sync_manager_index: u8| -> u16 {
+            pdos.iter()
+                .filter(|pdo| pdo.sync_manager == sync_manager_index)
+                .map(|pdo| pdo.bit_len)
+                .sum()
+        };
+
+        let (start, packed) = segment_start(
+            *offset,
+            sync_managers
+                .iter()
+                .enumerate()
+                .filter(|(_idx, sm)| sm.usage_type == sm_type)
+                .map(|(sync_manager_index, _sm)| sm_bit_len(sync_manager_index as u8)),
+            bit_packed,
+        );
+
+        *offset = start;
+
         let start_offset = *offset;
         let mut total_bit_len = 0;
 
-        let (sm_type, fmmu_type) = direction.filter_terms();
+        // Offset of the next entry in each sync manager
+        let mut entry_bit_offsets = heapless::Vec::<(u8, u16), 16>::new();
 
         for (sync_manager_index, sync_manager) in sync_managers
             .iter()
@@ -531,11 +1021,11 @@ where
         {
             let sync_manager_index = sync_manager_index as u8;
 
-            let bit_len = pdos
-                .iter()
-                .filter(|pdo| pdo.sync_manager == sync_manager_index)
-                .map(|pdo| pdo.bit_len)
-                .sum();
+            entry_bit_offsets
+                .push((sync_manager_index, sm_bit_offset(start_offset, *offset)))
+                .map_err(|_| Error::Capacity(Item::SyncManager))?;
+
+            let bit_len = sm_bit_len(sync_manager_index);
 
             total_bit_len += bit_len;
 
@@ -573,17 +1063,89 @@ where
                 offset,
                 sm_type,
                 &sm_config,
+                packed,
             )
             .await?;
         }
 
+        for entry in self.state.config.io.entries[entries_start..].iter_mut() {
+            let bit_offset = pdos
+                .iter()
+                .find(|pdo| pdo.index == entry.pdo)
+                .and_then(|pdo| {
+                    entry_bit_offsets
+                        .iter_mut()
+                        .find(|(sm, _offset)| *sm == pdo.sync_manager)
+                })
+                .map(|(_sm, offset)| offset);
+
+            if let Some(bit_offset) = bit_offset {
+                entry.bit_offset = *bit_offset;
+
+                *bit_offset += u16::from(entry.bit_len);
+            }
+        }
+
         Ok(PdiSegment {
             bit_len: total_bit_len.into(),
             bytes: start_offset.up_to(*offset),
+            start_bit: start_offset.start_bit,
+            bit_packed: packed,
         })
     }
 }
 
+/// Bit offset of the next sync manager from the first byte of the SubDevice's inputs or outputs.
+fn sm_bit_offset(start: PdiOffset, current: PdiOffset) -> u16 {
+    ((current.start_address - start.start_address) * 8) as u16 + u16::from(current.start_bit)
+}
+
+/// Find where a SubDevice's inputs or outputs start in the PDI, and whether they are bit-packed.
+///
+/// With `bit_packed` set, less than 8 bits of data in a single sync manager is packed into the
+/// free bits of the current byte, or the next byte if it doesn't fit. Packed data never spans two
+/// bytes. All other data starts on the next byte boundary.
+fn segment_start(
+    offset: PdiOffset,
+    sm_bit_lens: impl Iterator<Item = u16>,
+    bit_packed: bool,
+) -> (PdiOffset, bool) {
+    let mut sm_bit_lens = sm_bit_lens.filter(|bit_len| *bit_len > 0);
+
+    let packed_bit_len = match (sm_bit_lens.next(), sm_bit_lens.next()) {
+        // No data, so there's no need to move to the next byte
+        (None, _) => return (offset, false),
+        (Some(bit_len), None) if bit_packed && bit_len < 8 => bit_len,
+        _ => return (offset.align(), false),
+    };
+
+    if u16::from(offset.start_bit) + packed_bit_len > 8 {
+        (offset.align(), true)
+    } else {
+        (offset, true)
+    }
+}
+
+/// Record a PDO entry, returning false if it was dropped because the list of entries is full.
+///
+/// Nothing is recorded if no `pdo-entries-*` feature is enabled.
+fn push_entry(entries: &mut PdoEntries, entry: PdoEntryLayout) -> bool {
+    MAX_PDO_ENTRIES == 0 || entries.push(entry).is_ok()
+}
+
+/// The entry layout is only informational, so a full list of entries doesn't stop the SubDevice
+/// from being configured.
+fn warn_dropped_entries(configured_address: u16, dropped: u16) {
+    if dropped > 0 {
+        fmt::warn!(
+            "SubDevice {:#06x} has more than {} PDO entries, {} were not recorded. Enable a larger pdo-entries-* feature.",
+            configured_address,
+            MAX_PDO_ENTRIES,
+            dropped
+        );
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum PdoDirection {
     MasterRead,
@@ -598,3 +1160,49 @@ impl PdoDirection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_small_segments() {
+        let offset = PdiOffset {
+            start_address: 2,
+            start_bit: 4,
+        };
+
+        // EL2004 after another EL2004
+        assert_eq!(segment_start(offset, [4].into_iter(), true), (offset, true));
+
+        // Doesn't fit in the remaining bits
+        assert_eq!(
+            segment_start(offset, [6].into_iter(), true),
+            (offset.align(), true)
+        );
+
+        // Byte-oriented SubDevice
+        assert_eq!(
+            segment_start(offset, [16].into_iter(), true),
+            (offset.align(), false)
+        );
+
+        // Small, but split across two sync managers
+        assert_eq!(
+            segment_start(offset, [2, 0, 2].into_iter(), true),
+            (offset.align(), false)
+        );
+
+        // No data
+        assert_eq!(
+            segment_start(offset, [0].into_iter(), false),
+            (offset, false)
+        );
+
+        // Not enabled
+        assert_eq!(
+            segment_start(offset, [4].into_iter(), false),
+            (offset.align(), false)
+        );
+    }
+}
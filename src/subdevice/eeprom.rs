@@ -1,6 +1,7 @@
 use crate::{
     eeprom::types::{
-        CategoryType, DefaultMailbox, PdoEntry, SiiGeneral, RX_PDO_RANGE, TX_PDO_RANGE,
+        BootstrapMailbox, CategoryType, DefaultMailbox, PdoEntry, SiiGeneral, RX_PDO_RANGE,
+        TX_PDO_RANGE,
     },
     eeprom::{
         device_reader::SII_FIRST_CATEGORY_START,
@@ -111,6 +112,20 @@ where
         Ok(DefaultMailbox::unpack_from_slice(&buf)?)
     }
 
+    pub(crate) async fn bootstrap_mailbox_config(&self) -> Result<BootstrapMailbox, Error> {
+        // Bootstrap mailbox config directly precedes the standard mailbox config. Raw start address
+        // defined in ETG2010 Table 2. Bootstrap mailbox config is 8 bytes long.
+        let mut reader = self.start_at(0x0014, BootstrapMailbox::PACKED_LEN as u16);
+
+        fmt::trace!("Get bootstrap mailbox config");
+
+        let mut buf = BootstrapMailbox::buffer();
+
+        reader.read_exact(&mut buf).await?;
+
+        Ok(BootstrapMailbox::unpack_from_slice(&buf)?)
+    }
+
     pub(crate) async fn general(&self) -> Result<SiiGeneral, Error> {
         let mut reader = self
             .category(CategoryType::General)
@@ -206,10 +221,12 @@ where
         Ok(mappings)
     }
 
+    /// Read PDOs of the given type, calling `on_entry` with each of their entries in order.
     async fn pdos(
         &self,
         direction: PdoType,
         valid_range: RangeInclusive<u16>,
+        mut on_entry: impl FnMut(&Pdo, &PdoEntry),
     ) -> Result<heapless::Vec<Pdo, 16>, Error> {
         let mut pdos = heapless::Vec::new();
 
@@ -253,6 +270,8 @@ where
                 fmt::debug!("--> PDO entry:\n{:#?}", entry);
 
                 pdo.bit_len += u16::from(entry.data_length_bits);
+
+                on_entry(&pdo, &entry);
             }
 
             pdos.push(pdo).map_err(|_| Error::Capacity(Item::Pdo))?;
@@ -262,13 +281,19 @@ where
     }
 
     /// Transmit PDOs (from device's perspective) - inputs
-    pub(crate) async fn master_read_pdos(&self) -> Result<heapless::Vec<Pdo, 16>, Error> {
-        self.pdos(PdoType::Tx, TX_PDO_RANGE).await
+    pub(crate) async fn master_read_pdos(
+        &self,
+        on_entry: impl FnMut(&Pdo, &PdoEntry),
+    ) -> Result<heapless::Vec<Pdo, 16>, Error> {
+        self.pdos(PdoType::Tx, TX_PDO_RANGE, on_entry).await
     }
 
     /// Receive PDOs (from device's perspective) - outputs
-    pub(crate) async fn master_write_pdos(&self) -> Result<heapless::Vec<Pdo, 16>, Error> {
-        self.pdos(PdoType::Rx, RX_PDO_RANGE).await
+    pub(crate) async fn master_write_pdos(
+        &self,
+        on_entry: impl FnMut(&Pdo, &PdoEntry),
+    ) -> Result<heapless::Vec<Pdo, 16>, Error> {
+        self.pdos(PdoType::Rx, RX_PDO_RANGE, on_entry).await
     }
 
     /// Find a string in the device EEPROM.
@@ -350,6 +375,29 @@ where
         }
     }
 
+    /// Call `f` with the index and raw bytes of every string in the device EEPROM, in order.
+    ///
+    /// Indices start at 1, matching the string indices used in other EEPROM categories.
+    pub(crate) async fn strings(&self, mut f: impl FnMut(u8, &[u8])) -> Result<(), Error> {
+        let Some(mut reader) = self.category(CategoryType::Strings).await? else {
+            return Ok(());
+        };
+
+        let num_strings = reader.read_byte().await?;
+
+        let mut buf = [0u8; 255];
+
+        for index in 1..=num_strings {
+            let string = &mut buf[0..usize::from(reader.read_byte().await?)];
+
+            reader.read_exact(string).await?;
+
+            f(index, string);
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn items<T>(
         &self,
         category: CategoryType,
@@ -636,7 +684,7 @@ mod tests {
         let e = SubDeviceEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
 
         assert_eq!(
-            e.pdos(PdoType::Rx, 0x1000..=0x1010).await,
+            e.pdos(PdoType::Rx, 0x1000..=0x1010, |_, _| ()).await,
             Err(Error::Eeprom(EepromError::Decode))
         );
     }
@@ -646,8 +694,14 @@ mod tests {
     async fn subdevice_no_pdos() {
         let e = SubDeviceEeprom::new(EepromFile::new("dumps/eeprom/ek1100.hex"));
 
-        assert_eq!(e.master_read_pdos().await, Ok(heapless::Vec::new()));
-        assert_eq!(e.master_write_pdos().await, Ok(heapless::Vec::new()));
+        assert_eq!(
+            e.master_read_pdos(|_, _| ()).await,
+            Ok(heapless::Vec::new())
+        );
+        assert_eq!(
+            e.master_write_pdos(|_, _| ()).await,
+            Ok(heapless::Vec::new())
+        );
     }
 
     #[tokio::test]
@@ -706,13 +760,65 @@ mod tests {
             pdo(0x1607, 13, 0x7070),
         ];
 
-        assert_eq!(e.master_read_pdos().await, Ok(heapless::Vec::new()));
+        assert_eq!(
+            e.master_read_pdos(|_, _| ()).await,
+            Ok(heapless::Vec::new())
+        );
         pretty_assertions::assert_eq!(
-            e.master_write_pdos().await,
+            e.master_write_pdos(|_, _| ()).await,
             Ok(heapless::Vec::from_slice(&output_pdos).unwrap())
         );
     }
 
+    #[tokio::test]
+    async fn pdo_entries() {
+        let e = SubDeviceEeprom::new(EepromFile::new("dumps/eeprom/el2828.hex"));
+
+        let mut entries = Vec::new();
+
+        e.master_write_pdos(|pdo, entry| {
+            entries.push((
+                pdo.index,
+                entry.index,
+                entry.sub_index,
+                entry.data_length_bits,
+            ))
+        })
+        .await
+        .expect("PDOs");
+
+        assert_eq!(
+            entries,
+            (0..8)
+                .map(|i| (0x1600 + i, 0x7000 + i * 0x10, 1, 1))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn all_strings() {
+        let e = SubDeviceEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
+
+        let mut strings = Vec::new();
+
+        e.strings(|index, string| strings.push((index, string.to_vec())))
+            .await
+            .expect("Strings");
+
+        assert_eq!(strings.first().map(|(index, _)| *index), Some(1));
+
+        for (index, string) in strings {
+            assert_eq!(
+                e.find_string::<255>(index).await.unwrap().as_deref(),
+                Some(
+                    core::str::from_utf8(&string)
+                        .unwrap()
+                        .trim_end_matches('\0')
+                )
+            );
+        }
+    }
+
     // This exercises the "read from a specific address" codepath as opposed to the "find a category
     // and start reading it" codepath.
     #[tokio::test]
@@ -733,6 +839,33 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_bootstrap_mailbox_config() {
+        let e = SubDeviceEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
+
+        assert_eq!(
+            e.bootstrap_mailbox_config().await,
+            Ok(BootstrapMailbox {
+                subdevice_receive_offset: 0x1800,
+                subdevice_receive_size: 0x0400,
+                subdevice_send_offset: 0x1c00,
+                subdevice_send_size: 0x0400,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn no_bootstrap_mailbox() {
+        let e = SubDeviceEeprom::new(EepromFile::new("dumps/eeprom/ek1100.hex"));
+
+        let mbox = e
+            .bootstrap_mailbox_config()
+            .await
+            .expect("Read bootstrap mailbox config");
+
+        assert!(!mbox.has_mailbox());
+    }
+
     #[tokio::test]
     async fn default_mailbox_config_matches_sms() {
         let e = SubDeviceEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
@@ -0,0 +1,293 @@
+use super::{SubDevice, SubDeviceRef};
+use crate::{
+    eoe::{
+        EoeFrameType, EoeHeader, EoeResult, IpParameters, SetIpParameterRequest, FRAGMENT_ALIGN,
+    },
+    error::{EoeError, Error, MailboxError, PduError},
+    fmt,
+    mailbox::{GenericMailboxHeader, MailboxType},
+};
+use core::{ops::Deref, sync::atomic::Ordering};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWriteSized};
+
+/// The longest Ethernet frame that can be sent over EoE.
+///
+/// The complete frame length is sent in units of 32 bytes in a 6 bit field.
+const MAX_FRAME_LEN: usize = 63 * FRAGMENT_ALIGN;
+
+/// The length of the timestamp appended to the last fragment of a frame if requested.
+const TIMESTAMP_LEN: usize = 4;
+
+/// Ethernet over EtherCAT (EoE) methods.
+///
+/// EoE tunnels whole Ethernet frames through the SubDevice's mailbox, e.g. to reach the web
+/// interface of a drive or gateway. The SubDevice must be in PRE-OP or higher.
+///
+/// Frames sent by the SubDevice are not read automatically, so
+/// [`eoe_receive_frame`](SubDeviceRef::eoe_receive_frame) must be called periodically. The
+/// SubDevice's mailbox is shared with other protocols, so any frame waiting to be received is
+/// discarded when another mailbox request, e.g. [`sdo_read`](SubDeviceRef::sdo_read), is made.
+impl<'a, S> SubDeviceRef<'a, S>
+where
+    S: Deref<Target = SubDevice>,
+{
+    /// Set the IP configuration of the SubDevice's EoE network interface.
+    pub async fn eoe_set_ip_parameters(&self, params: &IpParameters) -> Result<(), Error> {
+        fmt::debug!(
+            "SubDevice {:#06x} EoE set IP parameters",
+            self.configured_address()
+        );
+
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .mailbox_write(
+                MailboxType::Eoe,
+                (
+                    EoeHeader::set_ip_parameter_request().pack().as_ref(),
+                    SetIpParameterRequest::from(params),
+                ),
+            )
+            .await?;
+
+        loop {
+            let (_mailbox_header, response) =
+                self.mailbox_read(&read_mailbox, MailboxType::Eoe).await?;
+
+            let header = EoeHeader::unpack_from_slice(&response)?;
+
+            match header.frame_type {
+                EoeFrameType::SetIpParameterResponse => {
+                    return match header.result() {
+                        EoeResult::Success => Ok(()),
+                        result => {
+                            fmt::error!(
+                                "SubDevice {:#06x} EoE set IP parameters failed: {}",
+                                self.configured_address(),
+                                result
+                            );
+
+                            Err(Error::Mailbox(MailboxError::Eoe(EoeError::Rejected(
+                                result,
+                            ))))
+                        }
+                    };
+                }
+                // The SubDevice may send an Ethernet frame at any time
+                EoeFrameType::FragmentData => {
+                    fmt::debug!(
+                        "SubDevice {:#06x} discarding EoE fragment while waiting for response",
+                        self.configured_address()
+                    );
+
+                    self.set_response_pending(true);
+                }
+                _ => return Err(Error::Mailbox(MailboxError::UnexpectedResponse)),
+            }
+        }
+    }
+
+    /// Send an Ethernet frame to the SubDevice.
+    ///
+    /// `frame` is a complete Ethernet frame starting with the destination MAC address, without
+    /// the trailing FCS. It is split into as many fragments as required to fit in the SubDevice's
+    /// mailbox.
+    pub async fn eoe_send_frame(&self, frame: &[u8]) -> Result<(), Error> {
+        if frame.len() > MAX_FRAME_LEN {
+            fmt::error!(
+                "EoE frame of {} bytes is longer than maximum of {} bytes",
+                frame.len(),
+                MAX_FRAME_LEN
+            );
+
+            return Err(Error::Pdu(PduError::TooLong));
+        }
+
+        let (_read_mailbox, write_mailbox) = self.mailboxes()?;
+
+        // Every fragment except the last must be a multiple of 32 bytes long
+        let max_fragment_len = usize::from(write_mailbox.len)
+            .saturating_sub(GenericMailboxHeader::PACKED_LEN + EoeHeader::PACKED_LEN)
+            / FRAGMENT_ALIGN
+            * FRAGMENT_ALIGN;
+
+        if max_fragment_len == 0 {
+            fmt::error!(
+                "SubDevice {:#06x} mailbox is too small for EoE",
+                self.configured_address()
+            );
+
+            return Err(Error::Pdu(PduError::TooLong));
+        }
+
+        let _lock = self.lock_mailbox().await?;
+
+        let frame_number = self.eoe_frame_number();
+
+        fmt::trace!(
+            "SubDevice {:#06x} EoE send frame {} of {} bytes",
+            self.configured_address(),
+            frame_number,
+            frame.len()
+        );
+
+        let mut offset = 0;
+
+        for (fragment_number, fragment) in frame.chunks(max_fragment_len).enumerate() {
+            let last_fragment = offset + fragment.len() == frame.len();
+
+            // The first fragment holds the complete frame length instead of an offset
+            let offset_units = if fragment_number == 0 {
+                frame.len().div_ceil(FRAGMENT_ALIGN)
+            } else {
+                offset / FRAGMENT_ALIGN
+            };
+
+            let header = EoeHeader::fragment(
+                frame_number,
+                fragment_number as u8,
+                offset_units as u8,
+                last_fragment,
+            );
+
+            // Unlike other mailbox requests, the OUT mailbox is not cleared here as it may hold a
+            // frame sent by the SubDevice.
+            self.wait_write_mailbox(&write_mailbox).await?;
+
+            self.mailbox_send(
+                &write_mailbox,
+                MailboxType::Eoe,
+                self.mailbox_counter(),
+                (header.pack().as_ref(), fragment),
+            )
+            .await?;
+
+            offset += fragment.len();
+        }
+
+        Ok(())
+    }
+
+    /// Receive an Ethernet frame sent by the SubDevice into `buf`, if one is available.
+    ///
+    /// Returns `Ok(None)` immediately if the SubDevice has not sent anything. Otherwise, this
+    /// method reads fragments until the frame is complete and returns its length. The frame does
+    /// not include the trailing FCS.
+    ///
+    /// `buf` should be at least 1514 bytes long to hold a full length Ethernet frame.
+    pub async fn eoe_receive_frame(&self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        let (read_mailbox, _write_mailbox) = self.mailboxes()?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        let mut next_fragment = 0u8;
+        let mut frame_number = 0u8;
+        let mut frame_len = 0usize;
+
+        loop {
+            // Only wait for the SubDevice once a frame has been started
+            if next_fragment == 0 && !self.read_mailbox_full(&read_mailbox).await? {
+                return Ok(None);
+            }
+
+            let (mailbox_header, response) =
+                self.mailbox_read(&read_mailbox, MailboxType::Eoe).await?;
+
+            let header = EoeHeader::unpack_from_slice(&response)?;
+
+            if header.frame_type != EoeFrameType::FragmentData {
+                fmt::debug!(
+                    "SubDevice {:#06x} ignoring EoE frame type {:?}",
+                    self.configured_address(),
+                    header.frame_type
+                );
+
+                continue;
+            }
+
+            if header.fragment_number() == 0 {
+                if next_fragment > 0 {
+                    fmt::warn!(
+                        "SubDevice {:#06x} EoE frame {} incomplete, discarding",
+                        self.configured_address(),
+                        frame_number
+                    );
+                }
+
+                frame_number = header.frame_number();
+                frame_len = 0;
+            } else if next_fragment == 0 {
+                // The rest of a frame that could not be received
+                fmt::debug!(
+                    "SubDevice {:#06x} discarding EoE fragment {} of frame {}",
+                    self.configured_address(),
+                    header.fragment_number(),
+                    header.frame_number()
+                );
+
+                continue;
+            } else if header.fragment_number() != next_fragment
+                || header.frame_number() != frame_number
+                || usize::from(header.offset()) * FRAGMENT_ALIGN != frame_len
+            {
+                fmt::error!(
+                    "SubDevice {:#06x} EoE fragment {} of frame {} at offset {}, expected fragment {} of frame {} at offset {}",
+                    self.configured_address(),
+                    header.fragment_number(),
+                    header.frame_number(),
+                    usize::from(header.offset()) * FRAGMENT_ALIGN,
+                    next_fragment,
+                    frame_number,
+                    frame_len
+                );
+
+                return Err(Error::Mailbox(MailboxError::Eoe(EoeError::Fragment)));
+            }
+
+            let mut data_len =
+                usize::from(mailbox_header.length).saturating_sub(EoeHeader::PACKED_LEN);
+
+            if header.last_fragment && header.time_appended {
+                data_len = data_len.saturating_sub(TIMESTAMP_LEN);
+            }
+
+            let data = response
+                .get(EoeHeader::PACKED_LEN..(EoeHeader::PACKED_LEN + data_len))
+                .ok_or(Error::Internal)?;
+
+            let buf_len = buf.len();
+
+            buf.get_mut(frame_len..(frame_len + data.len()))
+                .ok_or_else(|| {
+                    fmt::error!(
+                        "SubDevice {:#06x} EoE frame is too long for buffer of {} bytes",
+                        self.configured_address(),
+                        buf_len
+                    );
+
+                    Error::Mailbox(MailboxError::Eoe(EoeError::FrameTooLong))
+                })?
+                .copy_from_slice(data);
+
+            frame_len += data.len();
+
+            if header.last_fragment {
+                fmt::trace!(
+                    "SubDevice {:#06x} EoE received frame {} of {} bytes",
+                    self.configured_address(),
+                    frame_number,
+                    frame_len
+                );
+
+                return Ok(Some(frame_len));
+            }
+
+            next_fragment += 1;
+        }
+    }
+
+    /// Get the next EoE frame number, from 0-15.
+    fn eoe_frame_number(&self) -> u8 {
+        self.state.eoe_frame_number.fetch_add(1, Ordering::Relaxed) & 0x0f
+    }
+}
@@ -0,0 +1,331 @@
+use super::{types::Mailbox, SubDevice, SubDeviceRef};
+use crate::{
+    error::{Error, FoeError, MailboxError},
+    fmt,
+    foe::{FoeErrorCode, FoeHeader, FoeOpCode},
+    mailbox::{GenericMailboxHeader, MailboxType},
+};
+use core::ops::Deref;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWriteSized};
+
+/// The maximum amount of file data in a single FoE data request.
+///
+/// This is the longest mailbox that fits in an Ethernet frame (1486 bytes of PDU data), minus the
+/// mailbox and FoE headers.
+const MAX_FOE_DATA: usize = 1486 - GenericMailboxHeader::PACKED_LEN - FoeHeader::PACKED_LEN;
+
+/// File access over EtherCAT (FoE) methods.
+///
+/// FoE is commonly used to update SubDevice firmware, which usually requires the SubDevice to be in
+/// BOOT state, although some SubDevices also allow file access in PRE-OP. See
+/// [`enter_bootstrap`](SubDeviceRef::enter_bootstrap) to put a SubDevice into BOOT.
+impl<'a, S> SubDeviceRef<'a, S>
+where
+    S: Deref<Target = SubDevice>,
+{
+    /// Read a file from the SubDevice using FoE, writing its contents into `writer`.
+    ///
+    /// `password` is SubDevice specific, and is often `0` if not required.
+    ///
+    /// Returns the number of bytes read.
+    pub async fn foe_read<W>(
+        &self,
+        filename: &str,
+        password: u32,
+        mut writer: W,
+    ) -> Result<usize, Error>
+    where
+        W: embedded_io_async::Write,
+    {
+        fmt::debug!(
+            "SubDevice {:#06x} FoE read {}",
+            self.configured_address(),
+            filename
+        );
+
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .mailbox_write(
+                MailboxType::Foe,
+                (
+                    FoeHeader::read_request(password).pack().as_ref(),
+                    filename.as_bytes(),
+                ),
+            )
+            .await?;
+
+        // A data request shorter than this marks the end of the file
+        let max_data = usize::from(read_mailbox.len)
+            .saturating_sub(GenericMailboxHeader::PACKED_LEN + FoeHeader::PACKED_LEN);
+
+        let mut packet_number = 1u32;
+        let mut total_len = 0usize;
+
+        loop {
+            let (mailbox_header, response) =
+                self.mailbox_read(&read_mailbox, MailboxType::Foe).await?;
+
+            let header = FoeHeader::unpack_from_slice(&response)?;
+
+            match header.op_code {
+                FoeOpCode::Data => {
+                    if header.value != packet_number {
+                        self.foe_abort(FoeErrorCode::PacketNumberWrong).await;
+
+                        return Err(Error::Mailbox(MailboxError::Foe(FoeError::PacketNumber {
+                            expected: packet_number,
+                            received: header.value,
+                        })));
+                    }
+
+                    let data_len =
+                        usize::from(mailbox_header.length).saturating_sub(FoeHeader::PACKED_LEN);
+
+                    let data = response
+                        .get(FoeHeader::PACKED_LEN..(FoeHeader::PACKED_LEN + data_len))
+                        .ok_or(Error::Internal)?;
+
+                    if writer.write_all(data).await.is_err() {
+                        fmt::error!("Failed to write FoE data");
+
+                        self.foe_abort(FoeErrorCode::NotDefined).await;
+
+                        return Err(Error::Mailbox(MailboxError::Foe(FoeError::Io)));
+                    }
+
+                    total_len += data.len();
+
+                    self.mailbox_write(
+                        MailboxType::Foe,
+                        FoeHeader::ack(packet_number).pack().as_ref(),
+                    )
+                    .await?;
+
+                    if data_len < max_data {
+                        // The final acknowledgement isn't responded to
+                        self.set_response_pending(false);
+
+                        break;
+                    }
+
+                    packet_number = packet_number.wrapping_add(1);
+                }
+                FoeOpCode::Busy => {
+                    fmt::debug!(
+                        "SubDevice {:#06x} FoE busy, {} of {}",
+                        self.configured_address(),
+                        header.value & 0xffff,
+                        header.value >> 16
+                    );
+
+                    // The data is sent once the SubDevice is ready, without another request
+                    self.set_response_pending(true);
+                }
+                FoeOpCode::ErrorRequest => return Err(foe_error(header)),
+                _ => {
+                    self.foe_abort(FoeErrorCode::Illegal).await;
+
+                    return Err(Error::Mailbox(MailboxError::Foe(
+                        FoeError::UnexpectedOpCode,
+                    )));
+                }
+            }
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|_| Error::Mailbox(MailboxError::Foe(FoeError::Io)))?;
+
+        Ok(total_len)
+    }
+
+    /// Write a file to the SubDevice using FoE, reading its contents from `reader` until it
+    /// returns EOF.
+    ///
+    /// `password` is SubDevice specific, and is often `0` if not required.
+    ///
+    /// Returns the number of bytes written.
+    pub async fn foe_write<R>(
+        &self,
+        filename: &str,
+        password: u32,
+        mut reader: R,
+    ) -> Result<usize, Error>
+    where
+        R: embedded_io_async::Read,
+    {
+        fmt::debug!(
+            "SubDevice {:#06x} FoE write {}",
+            self.configured_address(),
+            filename
+        );
+
+        let _lock = self.lock_mailbox().await?;
+
+        let write_mailbox = self
+            .state
+            .config
+            .mailbox
+            .write
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        let max_data = usize::from(write_mailbox.len)
+            .saturating_sub(GenericMailboxHeader::PACKED_LEN + FoeHeader::PACKED_LEN)
+            .min(MAX_FOE_DATA);
+
+        // The write request is acknowledged with packet number 0, and is sent again if the
+        // SubDevice is busy. Defined in ETG1000.6 Section 5.9.
+        loop {
+            let read_mailbox = self
+                .mailbox_write(
+                    MailboxType::Foe,
+                    (
+                        FoeHeader::write_request(password).pack().as_ref(),
+                        filename.as_bytes(),
+                    ),
+                )
+                .await?;
+
+            if self.foe_ack(&read_mailbox, 0).await? {
+                break;
+            }
+        }
+
+        let mut buf = [0u8; MAX_FOE_DATA];
+        let buf = &mut buf[0..max_data];
+
+        let mut packet_number = 1u32;
+        let mut total_len = 0usize;
+
+        loop {
+            let data_len = match read_full(&mut reader, buf).await {
+                Ok(len) => len,
+                Err(e) => {
+                    self.foe_abort(FoeErrorCode::NotDefined).await;
+
+                    return Err(e);
+                }
+            };
+
+            let data = &buf[0..data_len];
+
+            // Data is sent again if the SubDevice is busy
+            loop {
+                let read_mailbox = self
+                    .mailbox_write(
+                        MailboxType::Foe,
+                        (FoeHeader::data(packet_number).pack().as_ref(), data),
+                    )
+                    .await?;
+
+                if self.foe_ack(&read_mailbox, packet_number).await? {
+                    break;
+                }
+            }
+
+            total_len += data_len;
+
+            // A short (possibly empty) data request marks the end of the file
+            if data_len < max_data {
+                break;
+            }
+
+            packet_number = packet_number.wrapping_add(1);
+        }
+
+        Ok(total_len)
+    }
+
+    /// Wait for an acknowledgement of the given packet number.
+    ///
+    /// Returns `false` if the SubDevice is busy and the last request should be sent again. The
+    /// transfer is aborted if any other response is received.
+    async fn foe_ack(&self, read_mailbox: &Mailbox, packet_number: u32) -> Result<bool, Error> {
+        let (_mailbox_header, response) = self.mailbox_read(read_mailbox, MailboxType::Foe).await?;
+
+        let header = FoeHeader::unpack_from_slice(&response)?;
+
+        match header.op_code {
+            FoeOpCode::Ack if header.value == packet_number => Ok(true),
+            FoeOpCode::Ack => {
+                self.foe_abort(FoeErrorCode::PacketNumberWrong).await;
+
+                Err(Error::Mailbox(MailboxError::Foe(FoeError::PacketNumber {
+                    expected: packet_number,
+                    received: header.value,
+                })))
+            }
+            FoeOpCode::Busy => {
+                fmt::debug!(
+                    "SubDevice {:#06x} FoE busy, {} of {}",
+                    self.configured_address(),
+                    header.value & 0xffff,
+                    header.value >> 16
+                );
+
+                Ok(false)
+            }
+            FoeOpCode::ErrorRequest => Err(foe_error(header)),
+            _ => {
+                self.foe_abort(FoeErrorCode::Illegal).await;
+
+                Err(Error::Mailbox(MailboxError::Foe(
+                    FoeError::UnexpectedOpCode,
+                )))
+            }
+        }
+    }
+
+    /// Tell the SubDevice the transfer has failed on the MainDevice side.
+    ///
+    /// This is best effort, as the transfer has already failed and the original error is more
+    /// useful to return to the caller.
+    async fn foe_abort(&self, code: FoeErrorCode) {
+        let header = FoeHeader {
+            op_code: FoeOpCode::ErrorRequest,
+            value: u32::from(code),
+        };
+
+        match self
+            .mailbox_write(MailboxType::Foe, header.pack().as_ref())
+            .await
+        {
+            // The SubDevice doesn't respond to an error request
+            Ok(_) => self.set_response_pending(false),
+            Err(e) => fmt::error!("Failed to send FoE error request: {}", e),
+        }
+    }
+}
+
+/// Convert an FoE error request sent by a SubDevice into an error.
+fn foe_error(header: FoeHeader) -> Error {
+    let code = FoeErrorCode::from(header.value);
+
+    fmt::error!("FoE error: {}", code);
+
+    Error::Mailbox(MailboxError::Foe(FoeError::Aborted(code)))
+}
+
+/// Read from `reader` until `buf` is full or EOF is reached, returning the number of bytes read.
+async fn read_full<R>(reader: &mut R, buf: &mut [u8]) -> Result<usize, Error>
+where
+    R: embedded_io_async::Read,
+{
+    let mut len = 0;
+
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]).await {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(_) => {
+                fmt::error!("Failed to read FoE data");
+
+                return Err(Error::Mailbox(MailboxError::Foe(FoeError::Io)));
+            }
+        }
+    }
+
+    Ok(len)
+}
@@ -0,0 +1,300 @@
+//! An async lock used to give a single task exclusive access to a SubDevice's mailbox.
+
+use crate::error::Error;
+use atomic_refcell::AtomicRefCell;
+use atomic_waker::AtomicWaker;
+use core::{
+    future::poll_fn,
+    sync::atomic::{AtomicU8, Ordering},
+    task::{Poll, Waker},
+};
+
+/// The number of tasks that can wait for the lock before waiters fall back to polling.
+///
+/// Tasks waiting past this limit have nowhere to store their waker, so they ask to be polled again
+/// straight away until a slot is free or the lock is released. This busy-polls the executor, but
+/// more than a few tasks using the same SubDevice's mailbox at once is unusual.
+///
+/// Must not be more than the number of bits in [`MailboxLock::used_slots`].
+const MAX_WAITERS: usize = 8;
+
+const UNLOCKED: u8 = 0;
+const LOCKED: u8 = 1;
+/// Locked by a guard that is held between calls to public methods, so the task holding it may try
+/// to lock it again. That task is recorded in [`MailboxLock::owner`].
+const LOCKED_OWNED: u8 = 2;
+
+/// A lock held for the duration of a whole mailbox transaction.
+///
+/// This lock is not reentrant: a task holding the lock must not try to lock it again. Guards that
+/// are held outside EtherCrab's control, e.g. by [`ObjectList`](crate::ObjectList), should call
+/// [`MailboxGuard::set_owner`] so that doing so returns an error instead of waiting forever. Other
+/// tasks still wait for the lock as normal.
+#[derive(Default)]
+pub(crate) struct MailboxLock {
+    state: AtomicU8,
+    /// Bit `n` is set if `wakers[n]` belongs to a waiting task.
+    used_slots: AtomicU8,
+    wakers: [AtomicWaker; MAX_WAITERS],
+    /// The task holding the lock if the state is [`LOCKED_OWNED`].
+    owner: AtomicRefCell<Option<Waker>>,
+}
+
+impl MailboxLock {
+    /// Wait for the lock to be released, then take it.
+    ///
+    /// Returns [`Error::Borrow`] if the lock is held by a guard owned by the calling task.
+    pub(crate) async fn lock(&self) -> Result<MailboxGuard<'_>, Error> {
+        let mut slot = WakerSlot {
+            lock: self,
+            index: None,
+        };
+
+        poll_fn(|cx| {
+            if let Some(guard) = self.try_lock() {
+                return Poll::Ready(Ok(guard));
+            }
+
+            if self.is_owned_by(cx.waker()) {
+                return Poll::Ready(Err(Error::Borrow));
+            }
+
+            if slot.index.is_none() {
+                slot.index = self.claim_slot();
+            }
+
+            match slot.index {
+                Some(index) => self.wakers[index].register(cx.waker()),
+                // Too many waiters to store, so poll again as soon as possible instead
+                None => cx.waker().wake_by_ref(),
+            }
+
+            // The lock may have been released before the waker was stored
+            match self.try_lock() {
+                Some(guard) => Poll::Ready(Ok(guard)),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    /// Take the lock if it is not already held.
+    pub(crate) fn try_lock(&self) -> Option<MailboxGuard<'_>> {
+        self.state
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Acquire)
+            .ok()
+            .map(|_| MailboxGuard { lock: self })
+    }
+
+    /// Check if the lock is held by a guard owned by the task with the given waker.
+    fn is_owned_by(&self, waker: &Waker) -> bool {
+        if self.state.load(Ordering::Acquire) != LOCKED_OWNED {
+            return false;
+        }
+
+        self.with_owner(|owner| owner.as_ref().is_some_and(|owner| owner.will_wake(waker)))
+    }
+
+    /// Access the owner of the lock.
+    fn with_owner<T>(&self, f: impl FnOnce(&mut Option<Waker>) -> T) -> T {
+        loop {
+            // Only borrowed for the duration of a few non-blocking operations.
+            match self.owner.try_borrow_mut() {
+                Ok(mut owner) => break f(&mut owner),
+                Err(_) => core::hint::spin_loop(),
+            }
+        }
+    }
+
+    /// Claim a free waker slot, returning its index.
+    fn claim_slot(&self) -> Option<usize> {
+        self.used_slots
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                (used != u8::MAX).then(|| used | (1 << used.trailing_ones()))
+            })
+            .ok()
+            .map(|used| used.trailing_ones() as usize)
+    }
+
+    fn unlock(&self) {
+        if self.state.load(Ordering::Acquire) == LOCKED_OWNED {
+            self.with_owner(|owner| *owner = None);
+        }
+
+        self.state.store(UNLOCKED, Ordering::Release);
+
+        // Wake every waiter. Those that don't get the lock will wait again.
+        for waker in self.wakers.iter() {
+            waker.wake();
+        }
+    }
+}
+
+impl core::fmt::Debug for MailboxLock {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MailboxLock")
+            .field("state", &self.state.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// A waker slot claimed by a task waiting for the lock, released when the task stops waiting.
+struct WakerSlot<'a> {
+    lock: &'a MailboxLock,
+    index: Option<usize>,
+}
+
+impl Drop for WakerSlot<'_> {
+    fn drop(&mut self) {
+        if let Some(index) = self.index {
+            self.lock.wakers[index].take();
+
+            self.lock
+                .used_slots
+                .fetch_and(!(1 << index), Ordering::Release);
+        }
+    }
+}
+
+/// Exclusive access to a SubDevice's mailbox. The lock is released when this guard is dropped.
+pub(crate) struct MailboxGuard<'a> {
+    lock: &'a MailboxLock,
+}
+
+impl MailboxGuard<'_> {
+    /// Record the task using this guard, so that any attempt by that task to take the lock again
+    /// returns [`Error::Borrow`] instead of waiting forever. Other tasks wait for the lock as
+    /// normal.
+    ///
+    /// This must be used if the guard is held between calls to public methods, as the task
+    /// holding it may make another mailbox request in the meantime. It should be called again
+    /// each time the guard is used, in case it has moved to another task.
+    pub(crate) fn set_owner(&self, waker: &Waker) {
+        self.lock.with_owner(|owner| match owner {
+            Some(owner) if owner.will_wake(waker) => (),
+            _ => *owner = Some(waker.clone()),
+        });
+
+        self.lock.state.store(LOCKED_OWNED, Ordering::Release);
+    }
+}
+
+impl Drop for MailboxGuard<'_> {
+    fn drop(&mut self) {
+        self.lock.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{future::Future, pin::pin, task::Context};
+    use futures_lite::future::{block_on, poll_once};
+    use std::{sync::Arc, task::Wake};
+
+    /// A waker for a task which is never woken.
+    fn task() -> Waker {
+        struct Task;
+
+        impl Wake for Task {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        Waker::from(Arc::new(Task))
+    }
+
+    fn poll<F: Future>(future: core::pin::Pin<&mut F>, waker: &Waker) -> Poll<F::Output> {
+        future.poll(&mut Context::from_waker(waker))
+    }
+
+    #[test]
+    fn lock_unlock() {
+        let lock = MailboxLock::default();
+
+        let guard = lock.try_lock();
+
+        assert!(guard.is_some());
+        assert!(lock.try_lock().is_none());
+
+        drop(guard);
+
+        assert!(lock.try_lock().is_some());
+    }
+
+    #[test]
+    fn wait_for_unlock() {
+        let lock = MailboxLock::default();
+
+        let guard = block_on(lock.lock());
+
+        let mut waiting = pin!(lock.lock());
+
+        assert!(block_on(poll_once(&mut waiting)).is_none());
+
+        drop(guard);
+
+        assert!(matches!(block_on(poll_once(&mut waiting)), Some(Ok(_))));
+    }
+
+    #[test]
+    fn one_waker_per_waiter() {
+        let lock = MailboxLock::default();
+
+        let guard = block_on(lock.lock());
+
+        {
+            let mut waiting = pin!(lock.lock());
+            let mut waiting2 = pin!(lock.lock());
+
+            for _ in 0..(MAX_WAITERS * 2) {
+                assert!(block_on(poll_once(&mut waiting)).is_none());
+                assert!(block_on(poll_once(&mut waiting2)).is_none());
+            }
+
+            assert_eq!(lock.used_slots.load(Ordering::Relaxed), 0b11);
+        }
+
+        // Slots are released when waiters are dropped
+        assert_eq!(lock.used_slots.load(Ordering::Relaxed), 0);
+
+        drop(guard);
+    }
+
+    #[test]
+    fn owner_rejected() {
+        let lock = MailboxLock::default();
+
+        let owner = task();
+        let other = task();
+
+        let guard = block_on(lock.lock()).expect("lock");
+
+        guard.set_owner(&owner);
+
+        let mut other_waiting = pin!(lock.lock());
+
+        assert!(poll(other_waiting.as_mut(), &other).is_pending());
+
+        // The owning task would wait for itself forever
+        assert!(matches!(
+            poll(pin!(lock.lock()), &owner),
+            Poll::Ready(Err(Error::Borrow))
+        ));
+
+        drop(guard);
+
+        assert!(lock.owner.borrow().is_none());
+
+        let guard = poll(other_waiting, &other);
+
+        assert!(matches!(guard, Poll::Ready(Ok(_))));
+
+        drop(guard);
+
+        // The previous owner can lock again once the guard is released
+        assert!(matches!(
+            poll(pin!(lock.lock()), &owner),
+            Poll::Ready(Ok(_))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+//! Read mailbox status received in cyclic process data.
+
+use atomic_waker::AtomicWaker;
+use core::{
+    future::poll_fn,
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+    task::Poll,
+};
+
+/// The state of a SubDevice's read mailbox, updated by every process data cycle if its sync manager
+/// status is mapped into the group PDI.
+#[derive(Debug, Default)]
+pub(crate) struct CyclicMailboxStatus {
+    /// Set once the status is mapped into a group PDI.
+    mapped: AtomicBool,
+    /// Cleared if process data isn't being exchanged, so the mailbox status must be polled instead.
+    live: AtomicBool,
+    /// Whether the SubDevice has put data into its read mailbox, as of the last cycle.
+    full: AtomicBool,
+    /// Incremented every cycle the status is received.
+    updates: AtomicU8,
+    /// Woken every cycle the status is received.
+    waker: AtomicWaker,
+}
+
+impl CyclicMailboxStatus {
+    pub(crate) fn set_mapped(&self, mapped: bool) {
+        self.mapped.store(mapped, Ordering::Release);
+        self.live.store(mapped, Ordering::Release);
+    }
+
+    pub(crate) fn is_mapped(&self) -> bool {
+        self.mapped.load(Ordering::Acquire)
+    }
+
+    /// Whether the status is mapped and process data is being exchanged.
+    pub(crate) fn is_live(&self) -> bool {
+        self.is_mapped() && self.live.load(Ordering::Acquire)
+    }
+
+    /// Mark the status as out of date until the next cycle is received.
+    pub(crate) fn set_stale(&self) {
+        self.live.store(false, Ordering::Release);
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.full.load(Ordering::Acquire)
+    }
+
+    /// Store the status received in a process data cycle.
+    pub(crate) fn update(&self, full: bool) {
+        self.full.store(full, Ordering::Release);
+        self.live.store(true, Ordering::Release);
+        self.updates.fetch_add(1, Ordering::AcqRel);
+
+        self.waker.wake();
+    }
+
+    /// Wait for the status from a cycle that was sent after this method was called, returning
+    /// whether the read mailbox is full.
+    pub(crate) async fn next(&self) -> bool {
+        let start = self.updates.load(Ordering::Acquire);
+
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            // The cycle in progress may have been sent before this method was called, so its
+            // status could be out of date. The cycle after it is not.
+            if self.updates.load(Ordering::Acquire).wrapping_sub(start) >= 2 {
+                Poll::Ready(self.is_full())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::pin::pin;
+    use futures_lite::future::{block_on, poll_once};
+
+    #[test]
+    fn wait_two_cycles() {
+        let status = CyclicMailboxStatus::default();
+
+        status.set_mapped(true);
+
+        let mut next = pin!(status.next());
+
+        assert_eq!(block_on(poll_once(&mut next)), None);
+
+        // Possibly sent before the mailbox request
+        status.update(true);
+
+        assert_eq!(block_on(poll_once(&mut next)), None);
+
+        status.update(false);
+
+        assert_eq!(block_on(poll_once(&mut next)), Some(false));
+    }
+
+    #[test]
+    fn stale() {
+        let status = CyclicMailboxStatus::default();
+
+        assert!(!status.is_live());
+
+        status.set_mapped(true);
+
+        assert!(status.is_live());
+
+        status.set_stale();
+
+        assert!(!status.is_live());
+
+        status.update(false);
+
+        assert!(status.is_live());
+    }
+}
@@ -1,27 +1,41 @@
+mod aoe;
 pub(crate) mod configuration;
 mod dc;
 mod eeprom;
+mod eoe;
+mod foe;
+mod mailbox_lock;
+mod mailbox_status;
 pub mod pdi;
+mod pdo_assignment;
 pub mod ports;
+mod sdo_info;
+mod soe;
 mod types;
 
 use crate::{
     al_control::AlControl,
     al_status_code::AlStatusCode,
     coe::{
-        self, abort_code::CoeAbortCode, services::CoeServiceRequest, CoeCommand, SdoExpedited,
-        SubIndex,
+        self,
+        abort_code::CoeAbortCode,
+        emergency::{CoeEmergency, EmergencyQueue},
+        services::CoeServiceRequest,
+        CoeCommand, SdoExpedited, SubIndex,
     },
     command::Command,
     dl_status::DlStatus,
     eeprom::{device_reader::DeviceEeprom, types::SiiOwner},
     error::{Error, MailboxError, PduError},
     fmt,
-    mailbox::{MailboxHeader, MailboxType},
+    mailbox::{GenericMailboxHeader, MailboxErrorCode, MailboxHeader, MailboxType, Priority},
     maindevice::MainDevice,
     pdu_loop::ReceivedPdu,
     register::{DcSupport, RegisterAddress, SupportFlags},
-    subdevice::{ports::Ports, types::SubDeviceConfig},
+    subdevice::{
+        ports::Ports,
+        types::{EntryName, SubDeviceConfig},
+    },
     subdevice_state::SubDeviceState,
     timer_factory::IntoTimeout,
     WrappedRead, WrappedWrite,
@@ -30,19 +44,30 @@ use core::{
     any::type_name,
     fmt::{Debug, Write},
     ops::{Deref, DerefMut},
-    sync::atomic::{AtomicU8, Ordering},
+    sync::atomic::{AtomicBool, AtomicU16, AtomicU8, Ordering},
 };
 use ethercrab_wire::{
     EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireReadWrite, EtherCrabWireSized,
-    EtherCrabWireWrite,
+    EtherCrabWireWrite, EtherCrabWireWriteSized,
 };
 
 pub use self::pdi::SubDevicePdi;
+pub use self::sdo_info::ObjectList;
 pub use self::types::IoRanges;
+pub use self::types::PdoEntryLayout;
 pub use self::types::SubDeviceIdentity;
-use self::{eeprom::SubDeviceEeprom, types::Mailbox};
+use self::{
+    eeprom::SubDeviceEeprom,
+    mailbox_lock::{MailboxGuard, MailboxLock},
+    mailbox_status::CyclicMailboxStatus,
+    types::Mailbox,
+};
 pub use dc::DcSync;
 
+/// The maximum length of an object written with [`SubDeviceRef::sdo_write_entries`] using Complete
+/// Access, including the padded sub-index 0.
+const COMPLETE_ACCESS_MAX_LEN: usize = 256;
+
 /// SubDevice device metadata. See [`SubDeviceRef`] for richer behaviour.
 #[doc(alias = "Slave")]
 #[derive(Debug)]
@@ -88,6 +113,32 @@ pub struct SubDevice {
 
     /// DC config.
     pub(crate) dc_sync: DcSync,
+
+    /// CoE emergency messages received from the SubDevice but not yet read by the application.
+    pub(crate) emergencies: EmergencyQueue,
+
+    /// The 0-15 cyclic counter used to number EoE frames sent to the SubDevice.
+    pub(crate) eoe_frame_number: AtomicU8,
+
+    /// Incremented for every AoE request to match it with its response.
+    pub(crate) aoe_invoke_id: AtomicU16,
+
+    /// Held for the duration of every mailbox transaction with the SubDevice.
+    pub(crate) mailbox_lock: MailboxLock,
+
+    /// Set while the SubDevice may still send a response to a mailbox request that has not been
+    /// read yet, e.g. because the transaction was cancelled.
+    pub(crate) mailbox_response_pending: AtomicBool,
+
+    /// Mailbox counter of the last request sent to the SubDevice.
+    pub(crate) mailbox_request_counter: AtomicU8,
+
+    /// Mailbox counter of a cancelled request whose response may still be sent by the SubDevice,
+    /// or 0 if there is none.
+    pub(crate) mailbox_stale_counter: AtomicU8,
+
+    /// Read mailbox status received in cyclic process data.
+    pub(crate) mailbox_status: CyclicMailboxStatus,
 }
 
 // Only required for tests, also doesn't make much sense - consumers of EtherCrab should be
@@ -108,7 +159,9 @@ impl PartialEq for SubDevice {
             && self.parent_index == other.parent_index
             && self.propagation_delay == other.propagation_delay
             && self.dc_sync == other.dc_sync
-        // NOTE: No mailbox_counter
+        // NOTE: No mailbox_counter, emergencies, eoe_frame_number, aoe_invoke_id, mailbox_lock,
+        // mailbox_response_pending, mailbox_request_counter, mailbox_stale_counter or
+        // mailbox_status
     }
 }
 
@@ -131,6 +184,20 @@ impl Clone for SubDevice {
             propagation_delay: self.propagation_delay,
             dc_sync: self.dc_sync,
             mailbox_counter: AtomicU8::new(self.mailbox_counter.load(Ordering::Acquire)),
+            emergencies: EmergencyQueue::default(),
+            eoe_frame_number: AtomicU8::new(self.eoe_frame_number.load(Ordering::Acquire)),
+            aoe_invoke_id: AtomicU16::new(self.aoe_invoke_id.load(Ordering::Acquire)),
+            mailbox_lock: MailboxLock::default(),
+            mailbox_response_pending: AtomicBool::new(
+                self.mailbox_response_pending.load(Ordering::Acquire),
+            ),
+            mailbox_request_counter: AtomicU8::new(
+                self.mailbox_request_counter.load(Ordering::Acquire),
+            ),
+            mailbox_stale_counter: AtomicU8::new(
+                self.mailbox_stale_counter.load(Ordering::Acquire),
+            ),
+            mailbox_status: CyclicMailboxStatus::default(),
         }
     }
 }
@@ -227,6 +294,14 @@ impl SubDevice {
             dc_sync: DcSync::Disabled,
             // 0 is a reserved value, so we initialise the cycle at 1. The cycle repeats 1 - 7.
             mailbox_counter: AtomicU8::new(1),
+            emergencies: EmergencyQueue::default(),
+            eoe_frame_number: AtomicU8::new(0),
+            aoe_invoke_id: AtomicU16::new(0),
+            mailbox_lock: MailboxLock::default(),
+            mailbox_response_pending: AtomicBool::new(false),
+            mailbox_request_counter: AtomicU8::new(0),
+            mailbox_stale_counter: AtomicU8::new(0),
+            mailbox_status: CyclicMailboxStatus::default(),
         })
     }
 
@@ -267,6 +342,34 @@ impl SubDevice {
         &self.config.io
     }
 
+    /// Get the position of every PDO entry in the SubDevice's inputs, in order.
+    ///
+    /// This list is empty until the group PDI is configured, e.g. by
+    /// [`SubDeviceGroup::into_op`](crate::SubDeviceGroup::into_op), and is always empty if no
+    /// `pdo-entries-*` crate feature is enabled.
+    pub fn input_entries(&self) -> impl Iterator<Item = &PdoEntryLayout> {
+        self.config.io.entries(true)
+    }
+
+    /// Get the position of every PDO entry in the SubDevice's outputs, in order.
+    ///
+    /// This list is empty until the group PDI is configured, e.g. by
+    /// [`SubDeviceGroup::into_op`](crate::SubDeviceGroup::into_op), and is always empty if no
+    /// `pdo-entries-*` crate feature is enabled.
+    pub fn output_entries(&self) -> impl Iterator<Item = &PdoEntryLayout> {
+        self.config.io.entries(false)
+    }
+
+    /// Find the position of an object mapped into the SubDevice's inputs, e.g. `0x6041:00`.
+    pub fn input_entry(&self, index: u16, sub_index: u8) -> Option<PdoEntryLayout> {
+        self.config.io.entry(true, index, sub_index)
+    }
+
+    /// Find the position of an object mapped into the SubDevice's outputs, e.g. `0x6040:00`.
+    pub fn output_entry(&self, index: u16, sub_index: u8) -> Option<PdoEntryLayout> {
+        self.config.io.entry(false, index, sub_index)
+    }
+
     /// Check if the current SubDevice is a child of `parent`.
     ///
     /// A SubDevice is a child of a parent if it is connected to an intermediate port of the
@@ -297,6 +400,19 @@ impl SubDevice {
 /// For example, a `SubDeviceRef<SubDevicePdi>` is returned by
 /// [`SubDeviceGroup`](crate::subdevice_group::SubDeviceGroup) methods to allow the reading and
 /// writing of a SubDevice's process data.
+///
+/// # Mailbox cancellation safety
+///
+/// Mailbox methods such as [`sdo_read`](SubDeviceRef::sdo_read) are cancellation safe: if the
+/// returned future is dropped part way through a transaction, e.g. by a timeout, a later request
+/// to the same SubDevice will never receive the cancelled request's response. The SubDevice keeps
+/// track of requests whose responses have not been read yet, and the next mailbox request first
+/// waits for and discards them. This wait is up to
+/// [`Timeouts::mailbox_response`](crate::Timeouts::mailbox_response) long, so the first request
+/// after a cancellation may take longer than usual.
+///
+/// The cancelled request itself may still have been carried out by the SubDevice, e.g. an SDO
+/// write may have taken effect.
 #[derive(Debug)]
 #[doc(alias = "SlaveRef")]
 pub struct SubDeviceRef<'a, S> {
@@ -364,6 +480,61 @@ where
         self.state.dc_sync
     }
 
+    /// Get the position of every PDO entry in the SubDevice's inputs, in order.
+    ///
+    /// This list is empty until the group PDI is configured, e.g. by
+    /// [`SubDeviceGroup::into_op`](crate::SubDeviceGroup::into_op), and is always empty if no
+    /// `pdo-entries-*` crate feature is enabled.
+    pub fn input_entries(&self) -> impl Iterator<Item = &PdoEntryLayout> {
+        self.state.input_entries()
+    }
+
+    /// Get the position of every PDO entry in the SubDevice's outputs, in order.
+    ///
+    /// This list is empty until the group PDI is configured, e.g. by
+    /// [`SubDeviceGroup::into_op`](crate::SubDeviceGroup::into_op), and is always empty if no
+    /// `pdo-entries-*` crate feature is enabled.
+    pub fn output_entries(&self) -> impl Iterator<Item = &PdoEntryLayout> {
+        self.state.output_entries()
+    }
+
+    /// Find the position of an object mapped into the SubDevice's inputs, e.g. `0x6041:00`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{
+    /// #     error::Error, MainDevice, MainDeviceConfig, PduStorage, Timeouts, std::ethercat_now,
+    /// # };
+    /// # static PDU_STORAGE: PduStorage<8, 32> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let maindevice = MainDevice::new(pdu_loop, Timeouts::default(), MainDeviceConfig::default());
+    /// # async {
+    /// let group = maindevice
+    ///     .init_single_group::<8, 8>(ethercat_now)
+    ///     .await?
+    ///     .into_op(&maindevice)
+    ///     .await?;
+    ///
+    /// let subdevice = group.subdevice(&maindevice, 0)?;
+    ///
+    /// // CiA402 status word
+    /// let status_word = subdevice.input_entry(0x6041, 0).expect("Status word not mapped");
+    ///
+    /// let inputs = subdevice.inputs_raw();
+    /// let status_word = u16::from_le_bytes(inputs[status_word.byte_range()].try_into().unwrap());
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub fn input_entry(&self, index: u16, sub_index: u8) -> Option<PdoEntryLayout> {
+        self.state.input_entry(index, sub_index)
+    }
+
+    /// Find the position of an object mapped into the SubDevice's outputs, e.g. `0x6040:00`.
+    pub fn output_entry(&self, index: u16, sub_index: u8) -> Option<PdoEntryLayout> {
+        self.state.output_entry(index, sub_index)
+    }
+
     /// Return the current cyclic mailbox counter value, from 0-7.
     ///
     /// Calling this method internally increments the counter, so subequent calls will produce a new
@@ -382,8 +553,8 @@ where
         ))
     }
 
-    /// Get CoE read/write mailboxes.
-    async fn coe_mailboxes(&self) -> Result<(Mailbox, Mailbox), Error> {
+    /// Get the read (SubDevice OUT) and write (SubDevice IN) mailboxes.
+    fn mailboxes(&self) -> Result<(Mailbox, Mailbox), Error> {
         let write_mailbox = self
             .state
             .config
@@ -405,37 +576,199 @@ where
                 e
             })?;
 
-        let mailbox_read_sm_status =
-            RegisterAddress::sync_manager_status(read_mailbox.sync_manager);
-        let mailbox_write_sm_status =
-            RegisterAddress::sync_manager_status(write_mailbox.sync_manager);
+        Ok((read_mailbox, write_mailbox))
+    }
 
-        // Ensure SubDevice OUT (master IN) mailbox is empty
-        {
-            let sm_status = self
-                .read(mailbox_read_sm_status)
-                .receive::<crate::sync_manager_channel::Status>(self.maindevice)
+    /// Wait for exclusive access to the SubDevice's mailbox.
+    ///
+    /// The returned guard must be held from the first request of a mailbox transaction until its
+    /// last response is read, so transactions made concurrently by different tasks don't
+    /// interleave.
+    ///
+    /// Returns [`Error::Borrow`] if the mailbox is held by an [`ObjectList`](crate::ObjectList)
+    /// used by the calling task.
+    async fn lock_mailbox(&self) -> Result<MailboxGuard<'_>, Error> {
+        if let Some(guard) = self.state.mailbox_lock.try_lock() {
+            return Ok(guard);
+        }
+
+        fmt::trace!(
+            "SubDevice {:#06x} waiting for mailbox to be free",
+            self.configured_address
+        );
+
+        self.state.mailbox_lock.lock().await
+    }
+
+    /// Get read/write mailboxes, ready to send a new request with the mailbox counter `counter`.
+    ///
+    /// Any stale data in the read mailbox is discarded, then this method waits for the write
+    /// mailbox to be ready. If a previous transaction ended before all of its responses were read,
+    /// e.g. because its future was dropped, its responses may still be sent by the SubDevice.
+    /// They are discarded by [`mailbox_response`](SubDeviceRef::mailbox_response) until a
+    /// response with the new request's counter is received.
+    ///
+    /// The SubDevice is then marked as having a response pending until one is read by
+    /// [`mailbox_response`](SubDeviceRef::mailbox_response). Callers sending a request that isn't
+    /// responded to must clear this with
+    /// [`set_response_pending`](SubDeviceRef::set_response_pending).
+    async fn ready_mailboxes(&self, counter: u8) -> Result<(Mailbox, Mailbox), Error> {
+        let (read_mailbox, write_mailbox) = self.mailboxes()?;
+
+        if self.state.mailbox_response_pending.load(Ordering::Acquire) {
+            let stale_counter = self.state.mailbox_request_counter.load(Ordering::Acquire);
+
+            fmt::debug!(
+                "SubDevice {:#06x} response to mailbox request with counter {} not read, discarding it when received",
+                self.configured_address,
+                stale_counter
+            );
+
+            self.state
+                .mailbox_stale_counter
+                .store(stale_counter, Ordering::Release);
+        } else if self.read_mailbox_full(&read_mailbox).await? {
+            // Ensure SubDevice OUT (master IN) mailbox is empty
+            fmt::debug!(
+                "SubDevice {:#06x} OUT mailbox not empty. Clearing.",
+                self.configured_address()
+            );
+
+            let stale = self
+                .read(read_mailbox.address)
+                .mailbox()
+                .ignore_wkc()
+                .receive_slice(self.maindevice, read_mailbox.len)
                 .await?;
 
-            // If flag is set, read entire mailbox to clear it
-            if sm_status.mailbox_full {
-                fmt::debug!(
-                    "SubDevice {:#06x} OUT mailbox not empty. Clearing.",
-                    self.configured_address()
-                );
+            // Don't lose any emergencies that were waiting in the mailbox
+            self.queue_emergency(&stale);
+        }
 
-                self.read(read_mailbox.address)
-                    .ignore_wkc()
-                    .receive_slice(self.maindevice, read_mailbox.len)
-                    .await?;
+        self.wait_write_mailbox(&write_mailbox).await?;
+
+        // Set before the request is sent, as it may reach the SubDevice even if the future writing
+        // it is dropped.
+        self.state
+            .mailbox_request_counter
+            .store(counter, Ordering::Release);
+        self.set_response_pending(true);
+
+        Ok((read_mailbox, write_mailbox))
+    }
+
+    /// Mark whether the SubDevice may still send a response that hasn't been read yet.
+    fn set_response_pending(&self, pending: bool) {
+        self.state
+            .mailbox_response_pending
+            .store(pending, Ordering::Release);
+    }
+
+    /// Returns `true` if `response` was sent for a cancelled request instead of the last request
+    /// sent, and should be discarded.
+    fn is_stale_response(&self, response: &[u8]) -> Result<bool, Error> {
+        let stale_counter = self.state.mailbox_stale_counter.load(Ordering::Acquire);
+
+        if stale_counter == 0 {
+            return Ok(false);
+        }
+
+        let header = GenericMailboxHeader::unpack_from_slice(response)?;
+        let counter = self.state.mailbox_request_counter.load(Ordering::Acquire);
+
+        if header.counter == counter {
+            self.state.mailbox_stale_counter.store(0, Ordering::Release);
+
+            return Ok(false);
+        }
+
+        fmt::debug!(
+            "SubDevice {:#06x} discarding stale {:?} response with counter {} (expected {})",
+            self.configured_address,
+            header.mailbox_type,
+            header.counter,
+            counter
+        );
+
+        Ok(true)
+    }
+
+    /// Check whether the SubDevice has put data into its OUT mailbox.
+    async fn read_mailbox_full(&self, read_mailbox: &Mailbox) -> Result<bool, Error> {
+        let sm_status = self
+            .read(RegisterAddress::sync_manager_status(
+                read_mailbox.sync_manager,
+            ))
+            .mailbox()
+            .receive::<crate::sync_manager_channel::Status>(self.maindevice)
+            .await?;
+
+        Ok(sm_status.mailbox_full)
+    }
+
+    /// Wait for the SubDevice to put data into its OUT mailbox.
+    ///
+    /// If the mailbox status is mapped into the group PDI and process data is being exchanged, the
+    /// status is taken from the process data for up to the mailbox response timeout, as process
+    /// data may be exchanged much less often than a PDU could be sent. If nothing is received in
+    /// that time, e.g. because process data is no longer being exchanged, the sync manager status
+    /// is checked once more.
+    ///
+    /// Otherwise the sync manager status is polled for up to the mailbox echo timeout.
+    async fn wait_read_mailbox_full(&self, read_mailbox: &Mailbox) -> Result<(), Error> {
+        let status = &self.state.mailbox_status;
+
+        if status.is_live() {
+            let full = async {
+                while !status.next().await {}
+
+                Ok(())
+            }
+            .timeout(self.maindevice.timeouts.mailbox_response)
+            .await;
+
+            return match full {
+                Err(Error::Timeout) => {
+                    fmt::debug!(
+                        "SubDevice {:#06x} no mailbox status received in process data, polling mailbox status",
+                        self.configured_address
+                    );
+
+                    status.set_stale();
+
+                    if self.read_mailbox_full(read_mailbox).await? {
+                        Ok(())
+                    } else {
+                        Err(Error::Timeout)
+                    }
+                }
+                result => result,
+            };
+        }
+
+        async {
+            loop {
+                if self.read_mailbox_full(read_mailbox).await? {
+                    break Ok(());
+                }
+
+                self.maindevice.timeouts.loop_tick().await;
             }
         }
+        .timeout(self.maindevice.timeouts.mailbox_echo)
+        .await
+    }
+
+    /// Wait for SubDevice IN mailbox to be available to receive data from master.
+    async fn wait_write_mailbox(&self, write_mailbox: &Mailbox) -> Result<(), Error> {
+        let mailbox_write_sm_status =
+            RegisterAddress::sync_manager_status(write_mailbox.sync_manager);
 
-        // Wait for SubDevice IN mailbox to be available to receive data from master
         async {
             loop {
                 let sm_status = self
                     .read(mailbox_write_sm_status)
+                    .mailbox()
                     .receive::<crate::sync_manager_channel::Status>(self.maindevice)
                     .await?;
 
@@ -456,51 +789,362 @@ where
             );
 
             e
-        })?;
+        })
+    }
 
-        Ok((read_mailbox, write_mailbox))
+    /// Wait for a mailbox response of any type.
+    ///
+    /// CoE emergencies received while waiting are queued and skipped. Once a response is read,
+    /// the SubDevice is no longer marked as having a response pending. Callers expecting more
+    /// messages for the same request must mark it again with
+    /// [`set_response_pending`](SubDeviceRef::set_response_pending).
+    async fn mailbox_response(&self, read_mailbox: &Mailbox) -> Result<ReceivedPdu, Error> {
+        let mut repeats_left = self.maindevice.config.mailbox_repeats;
+
+        loop {
+            // Wait for SubDevice OUT mailbox to be ready
+            self.wait_read_mailbox_full(read_mailbox)
+                .await
+                .map_err(|e| {
+                    fmt::error!(
+                        "Response mailbox IN error for SubDevice {:#06x}: {}",
+                        self.configured_address,
+                        e
+                    );
+
+                    e
+                })?;
+
+            // Read acknowledgement from SubDevice OUT mailbox
+            let response = match self
+                .read(read_mailbox.address)
+                .mailbox()
+                .receive_slice(self.maindevice, read_mailbox.len)
+                .await
+            {
+                Ok(response) => response,
+                // The SubDevice may have released the mailbox even if the response frame was lost
+                // or corrupted, so we must ask it to put the same data back into the mailbox.
+                Err(e @ (Error::Timeout | Error::WorkingCounter { .. })) if repeats_left > 0 => {
+                    repeats_left -= 1;
+
+                    fmt::warn!(
+                        "SubDevice {:#06x} mailbox read failed ({}), sending repeat request",
+                        self.configured_address,
+                        e
+                    );
+
+                    self.mailbox_repeat_request(read_mailbox).await?;
+
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            // An emergency can be sent by the SubDevice at any time, so it may arrive before the
+            // response to our request. Queue it and keep waiting for the actual response.
+            if self.queue_emergency(&response) || self.is_stale_response(&response)? {
+                continue;
+            }
+
+            self.set_response_pending(false);
+
+            break Ok(response);
+        }
     }
 
-    /// Wait for a mailbox response
-    async fn coe_response(&self, read_mailbox: &Mailbox) -> Result<ReceivedPdu, Error> {
-        let mailbox_read_sm = RegisterAddress::sync_manager_status(read_mailbox.sync_manager);
+    /// Ask the SubDevice to write the last message it sent back into its OUT mailbox.
+    ///
+    /// This toggles the repeat bit of the mailbox sync manager, then waits for the SubDevice to
+    /// acknowledge by setting its repeat ack bit to the same value. Defined in ETG1000.4 Section
+    /// 6.7.2 and ETG1000.6 Section 5.2.
+    async fn mailbox_repeat_request(&self, read_mailbox: &Mailbox) -> Result<(), Error> {
+        let enable_register = RegisterAddress::sync_manager_enable(read_mailbox.sync_manager);
+
+        let enable = self
+            .read(enable_register)
+            .mailbox()
+            .receive::<crate::sync_manager_channel::Enable>(self.maindevice)
+            .await?;
+
+        let repeat = !enable.repeat;
+
+        let request = crate::sync_manager_channel::Enable { repeat, ..enable }.pack();
+
+        // Only the activate register is written. The PDI control register following it is
+        // read-only from the EtherCAT side.
+        self.write(enable_register)
+            .mailbox()
+            .send(self.maindevice, &request[0..1])
+            .await?;
 
-        // Wait for SubDevice OUT mailbox to be ready
         async {
             loop {
-                let sm_status = self
-                    .read(mailbox_read_sm)
-                    .receive::<crate::sync_manager_channel::Status>(self.maindevice)
+                let enable = self
+                    .read(enable_register)
+                    .mailbox()
+                    .receive::<crate::sync_manager_channel::Enable>(self.maindevice)
                     .await?;
 
-                if sm_status.mailbox_full {
+                if enable.repeat_ack == repeat {
                     break Ok(());
                 }
 
                 self.maindevice.timeouts.loop_tick().await;
             }
         }
-        .timeout(self.maindevice.timeouts.mailbox_echo)
+        .timeout(self.maindevice.timeouts.mailbox_response)
         .await
         .map_err(|e| {
             fmt::error!(
-                "Response mailbox IN error for SubDevice {:#06x}: {}",
+                "SubDevice {:#06x} did not acknowledge mailbox repeat request: {}",
                 self.configured_address,
                 e
             );
 
             e
+        })
+    }
+
+    /// If the given mailbox data is a CoE emergency, store it in the SubDevice's emergency queue
+    /// and return `true`.
+    fn queue_emergency(&self, mailbox: &[u8]) -> bool {
+        let Some(emergency) = CoeEmergency::from_mailbox(mailbox) else {
+            return false;
+        };
+
+        fmt::warn!(
+            "SubDevice {:#06x} emergency: error code {:#06x}, error register {:#04x}, data {:?}",
+            self.configured_address,
+            emergency.error_code,
+            emergency.error_register,
+            emergency.data
+        );
+
+        self.state.emergencies.push(emergency);
+
+        true
+    }
+
+    /// Returns `true` if the last process data cycle showed a message waiting in the SubDevice's
+    /// read mailbox, e.g. a CoE emergency or the response to a request in progress.
+    ///
+    /// This is only available if
+    /// [`set_mailbox_status_mapping`](crate::SubDeviceGroup::set_mailbox_status_mapping) was
+    /// enabled for the SubDevice's group, and always returns `false` otherwise. Waiting
+    /// emergencies are read into the queue returned by [`emergencies`](SubDeviceRef::emergencies)
+    /// at the start of the next mailbox request.
+    pub fn mailbox_pending(&self) -> bool {
+        self.state.mailbox_status.is_mapped() && self.state.mailbox_status.is_full()
+    }
+
+    /// Get an iterator over any CoE emergency messages received from this SubDevice.
+    ///
+    /// Emergencies are received while waiting for responses to other mailbox requests, e.g.
+    /// [`sdo_read`](SubDeviceRef::sdo_read), and are removed from the queue as they are iterated
+    /// over. Up to 8 unread messages are stored; if more are received, the oldest are discarded.
+    pub fn emergencies(&self) -> impl Iterator<Item = CoeEmergency> + '_ {
+        core::iter::from_fn(|| self.state.emergencies.pop())
+    }
+
+    /// Send a raw mailbox request of the given type and wait for the SubDevice's response,
+    /// returning the part of `response` that was written to.
+    ///
+    /// The mailbox header, including the mailbox counter, is added to `request`, and removed from
+    /// the response. This allows protocols not otherwise supported by EtherCrab, e.g. vendor
+    /// specific protocols (VoE), to be used. A single response is read for every request.
+    ///
+    /// If the SubDevice replies with a mailbox error, it is returned as
+    /// [`MailboxError::ErrorReply`]. A response of a different type to `mailbox_type` is returned
+    /// as [`MailboxError::UnexpectedResponse`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, error::Error, MailboxType};
+    /// # async fn voe(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+    /// let mut buf = [0u8; 128];
+    ///
+    /// let response = subdevice
+    ///     .mailbox_transaction(MailboxType::VendorSpecific, &[0x01, 0x02, 0x03, 0x04], &mut buf)
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn mailbox_transaction<'buf>(
+        &self,
+        mailbox_type: MailboxType,
+        request: &[u8],
+        response: &'buf mut [u8],
+    ) -> Result<&'buf [u8], Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self.mailbox_write(mailbox_type, request).await?;
+
+        let (header, data) = self.mailbox_read(&read_mailbox, mailbox_type).await?;
+
+        let len = usize::from(header.length)
+            .min(usize::from(read_mailbox.len).saturating_sub(GenericMailboxHeader::PACKED_LEN));
+
+        let data = data.get(0..len).ok_or(Error::Internal)?;
+
+        let buf_len = response.len();
+
+        let response = response.get_mut(0..len).ok_or_else(|| {
+            fmt::error!(
+                "SubDevice {:#06x} mailbox response of {} bytes is too long for buffer of {} bytes",
+                self.configured_address,
+                len,
+                buf_len
+            );
+
+            Error::Pdu(PduError::TooLong)
         })?;
 
-        // Read acknowledgement from SubDevice OUT mailbox
-        let response = self
-            .read(read_mailbox.address)
-            .receive_slice(self.maindevice, read_mailbox.len)
+        response.copy_from_slice(data);
+
+        Ok(response)
+    }
+
+    /// Send a raw mailbox request and copy the data of the response into `response`, returning
+    /// the response's mailbox header and data length.
+    ///
+    /// Unlike [`mailbox_transaction`](SubDeviceRef::mailbox_transaction), the response may be of
+    /// any type, including a mailbox error reply.
+    ///
+    /// If `response` is `None`, the request is one the SubDevice doesn't respond to, so only the
+    /// request is sent and `None` is returned.
+    #[cfg(feature = "std")]
+    pub(crate) async fn mailbox_forward(
+        &self,
+        mailbox_type: MailboxType,
+        request: &[u8],
+        response: Option<&mut [u8]>,
+    ) -> Result<Option<(GenericMailboxHeader, usize)>, Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self.mailbox_write(mailbox_type, request).await?;
+
+        let Some(response) = response else {
+            self.set_response_pending(false);
+
+            return Ok(None);
+        };
+
+        let data = self.mailbox_response(&read_mailbox).await?;
+
+        let header = GenericMailboxHeader::unpack_from_slice(&data)?;
+
+        let len = usize::from(header.length)
+            .min(usize::from(read_mailbox.len).saturating_sub(GenericMailboxHeader::PACKED_LEN));
+
+        let data = data
+            .get(GenericMailboxHeader::PACKED_LEN..(GenericMailboxHeader::PACKED_LEN + len))
+            .ok_or(Error::Internal)?;
+
+        response
+            .get_mut(0..len)
+            .ok_or(Error::Pdu(PduError::TooLong))?
+            .copy_from_slice(data);
+
+        Ok(Some((header, len)))
+    }
+
+    /// Write a message of the given mailbox type to the SubDevice IN mailbox, returning the OUT
+    /// mailbox to read the response from.
+    ///
+    /// The mailbox header is added to the start of `data`.
+    async fn mailbox_write(
+        &self,
+        mailbox_type: MailboxType,
+        data: impl EtherCrabWireWrite,
+    ) -> Result<Mailbox, Error> {
+        let counter = self.mailbox_counter();
+
+        let (read_mailbox, write_mailbox) = self.ready_mailboxes(counter).await?;
+
+        self.mailbox_send(&write_mailbox, mailbox_type, counter, data)
             .await?;
 
-        // TODO: Retries. Refer to SOEM's `ecx_mbxreceive` for inspiration
+        Ok(read_mailbox)
+    }
 
-        Ok(response)
+    /// Add a mailbox header with the given counter to `data` and write it to the given SubDevice
+    /// IN mailbox, which must already be ready to receive data.
+    async fn mailbox_send(
+        &self,
+        write_mailbox: &Mailbox,
+        mailbox_type: MailboxType,
+        counter: u8,
+        data: impl EtherCrabWireWrite,
+    ) -> Result<(), Error> {
+        if GenericMailboxHeader::PACKED_LEN + data.packed_len() > usize::from(write_mailbox.len) {
+            fmt::error!(
+                "Mailbox message of {} bytes is too long for SubDevice {:#06x} mailbox of {} bytes",
+                GenericMailboxHeader::PACKED_LEN + data.packed_len(),
+                self.configured_address,
+                write_mailbox.len
+            );
+
+            return Err(Error::Pdu(PduError::TooLong));
+        }
+
+        let header = GenericMailboxHeader {
+            length: u16::try_from(data.packed_len())?,
+            priority: Priority::Lowest,
+            mailbox_type,
+            counter,
+        };
+
+        self.write(write_mailbox.address)
+            .mailbox()
+            .with_len(write_mailbox.len)
+            .send(self.maindevice, (header.pack().as_ref(), data))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Wait for and read a message of the given mailbox type from the SubDevice OUT mailbox.
+    ///
+    /// The returned data starts after the mailbox header, and may be longer than the length given
+    /// in the header.
+    async fn mailbox_read(
+        &self,
+        read_mailbox: &Mailbox,
+        mailbox_type: MailboxType,
+    ) -> Result<(GenericMailboxHeader, ReceivedPdu), Error> {
+        let mut response = self.mailbox_response(read_mailbox).await?;
+
+        let header = GenericMailboxHeader::unpack_from_slice(&response)?;
+
+        response.trim_front(GenericMailboxHeader::PACKED_LEN);
+
+        if header.mailbox_type == MailboxType::Err {
+            // The first two bytes are always 0x01 for a mailbox command, followed by the error
+            // detail code.
+            let code = MailboxErrorCode::unpack_from_slice(response.get(2..).unwrap_or_default())?;
+
+            fmt::error!(
+                "SubDevice {:#06x} mailbox error reply: {}",
+                self.configured_address,
+                code
+            );
+
+            return Err(Error::Mailbox(MailboxError::ErrorReply(code)));
+        }
+
+        if header.mailbox_type != mailbox_type {
+            fmt::error!(
+                "SubDevice {:#06x} sent mailbox type {:?}, expected {:?}",
+                self.configured_address,
+                header.mailbox_type,
+                mailbox_type
+            );
+
+            return Err(Error::Mailbox(MailboxError::UnexpectedResponse));
+        }
+
+        Ok((header, response))
     }
 
     /// Send a mailbox request, wait for response mailbox to be ready, read response from mailbox
@@ -509,17 +1153,31 @@ where
     where
         R: CoeServiceRequest + Debug,
     {
-        let (read_mailbox, write_mailbox) = self.coe_mailboxes().await?;
+        self.send_coe_service_with_data(request, ()).await
+    }
 
+    /// The same as [`send_coe_service`](SubDeviceRef::send_coe_service), but with additional data
+    /// sent in the mailbox directly after the request headers.
+    async fn send_coe_service_with_data<R>(
+        &'a self,
+        request: R,
+        data: impl EtherCrabWireWrite,
+    ) -> Result<(R, ReceivedPdu), Error>
+    where
+        R: CoeServiceRequest + Debug,
+    {
         let counter = request.counter();
 
+        let (read_mailbox, write_mailbox) = self.ready_mailboxes(counter).await?;
+
         // Send data to SubDevice IN mailbox
         self.write(write_mailbox.address)
+            .mailbox()
             .with_len(write_mailbox.len)
-            .send(self.maindevice, &request.pack().as_ref())
+            .send(self.maindevice, (request.pack().as_ref(), data))
             .await?;
 
-        let mut response = self.coe_response(&read_mailbox).await?;
+        let mut response = self.mailbox_response(&read_mailbox).await?;
 
         /// A super generalised version of the various header shapes for responses, extracting only
         /// what we need in this method.
@@ -587,7 +1245,42 @@ where
 
     /// Write a value to the given SDO index (address) and sub-index.
     ///
-    /// Note that this method currently only supports expedited SDO downloads (4 bytes maximum).
+    /// Values of 4 bytes or less are sent with an expedited SDO download. Longer values are sent
+    /// with a normal SDO download, so must fit in the SubDevice's mailbox. Values that don't fit can
+    /// be packed into a buffer and written with [`sdo_write_slice`](SubDeviceRef::sdo_write_slice),
+    /// which uses a segmented SDO download.
+    ///
+    /// # Complete access
+    ///
+    /// If `sub_index` is [`SubIndex::Complete`], the whole object is written in one go using a
+    /// Complete Access SDO download starting at sub-index 0. `value` must therefore begin with the
+    /// number of sub-indices, padded to 16 bits, followed by every sub-index value in order. For
+    /// example, to assign PDOs `0x1600` and `0x1601` to the outputs sync manager:
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, SubIndex, error::Error};
+    /// # async fn pdo_assign(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+    /// let assignment = heapless::Vec::<u16, 3>::from_slice(&[2, 0x1600, 0x1601]).unwrap();
+    ///
+    /// subdevice.sdo_write(0x1c12, SubIndex::Complete, assignment).await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// The sub-indices in `value` can't be told apart, so there is no fallback for SubDevices that
+    /// don't support Complete Access. To write an object to SubDevices that may not support it, use
+    /// [`sdo_write_entries`](SubDeviceRef::sdo_write_entries) instead, which writes each sub-index
+    /// individually when needed.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an
+    /// [`Error::Mailbox(MailboxError::TooLong)`](crate::error::MailboxError::TooLong) if the value
+    /// doesn't fit in the SubDevice's mailbox.
+    ///
+    /// Writing [`SubIndex::Complete`] to a SubDevice that does not support Complete Access will
+    /// return an
+    /// [`Error::Mailbox(MailboxError::NoCompleteAccess)`](crate::error::MailboxError::NoCompleteAccess)
+    /// without sending anything.
     pub async fn sdo_write<T>(
         &self,
         index: u16,
@@ -597,17 +1290,71 @@ where
     where
         T: EtherCrabWireWrite,
     {
-        let sub_index = sub_index.into();
+        let _lock = self.lock_mailbox().await?;
 
-        let counter = self.mailbox_counter();
+        self.sdo_write_locked(index, sub_index.into(), value).await
+    }
+
+    /// [`sdo_write`](SubDeviceRef::sdo_write), with the mailbox lock already held.
+    async fn sdo_write_locked<T>(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        value: T,
+    ) -> Result<(), Error>
+    where
+        T: EtherCrabWireWrite,
+    {
+        if sub_index.complete_access() && !self.state.config.mailbox.complete_access {
+            fmt::error!(
+                "SubDevice {:#06x} does not support complete access to SDO {:#06x}. Use sdo_write_entries instead.",
+                self.configured_address,
+                index
+            );
+
+            return Err(Error::Mailbox(MailboxError::NoCompleteAccess {
+                address: index,
+            }));
+        }
+
+        self.sdo_write_single(index, sub_index, value).await
+    }
+
+    /// Write a value to an SDO with a single expedited or normal SDO download.
+    async fn sdo_write_single<T>(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        value: T,
+    ) -> Result<(), Error>
+    where
+        T: EtherCrabWireWrite,
+    {
+        if value.packed_len() > 4 || sub_index.complete_access() {
+            let max_len = self.sdo_download_chunk_len()?;
+
+            if value.packed_len() > max_len {
+                fmt::error!(
+                    "SDO {:#06x}:{} value of {} bytes does not fit in the mailbox, max {} bytes. Use sdo_write_slice instead.",
+                    index,
+                    sub_index.sub_index(),
+                    value.packed_len(),
+                    max_len
+                );
 
-        if value.packed_len() > 4 {
-            fmt::error!("Only 4 byte SDO writes or smaller are supported currently.");
+                return Err(Error::Mailbox(MailboxError::TooLong {
+                    address: index,
+                    sub_index: sub_index.sub_index(),
+                }));
+            }
 
-            // TODO: Normal SDO download. Only expedited requests for now
-            return Err(Error::Internal);
+            return self
+                .sdo_download_normal(index, sub_index, value.packed_len(), value)
+                .await;
         }
 
+        let counter = self.mailbox_counter();
+
         let mut buf = [0u8; 4];
 
         value.pack_to_slice(&mut buf)?;
@@ -624,6 +1371,254 @@ where
         Ok(())
     }
 
+    /// Write a slice of raw bytes to the given SDO index (address) and sub-index.
+    ///
+    /// Data that is too long to fit in a single mailbox write is sent using a segmented SDO
+    /// download, so there is no upper limit on the length of `data`. Values too long for
+    /// [`sdo_write`](SubDeviceRef::sdo_write) can be packed into a buffer first:
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{EtherCrabWireWrite, SubDevice, SubDeviceRef, error::Error};
+    /// # async fn write(subdevice: SubDeviceRef<'_, &SubDevice>, value: impl EtherCrabWireWrite) -> Result<(), Error> {
+    /// let mut buf = [0u8; 1024];
+    ///
+    /// let data = value.pack_to_slice(&mut buf)?;
+    ///
+    /// subdevice.sdo_write_slice(0x2000, 0, data).await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// See [`sdo_write`](SubDeviceRef::sdo_write) for the layout of `data` when using
+    /// [`SubIndex::Complete`].
+    pub async fn sdo_write_slice(
+        &self,
+        index: u16,
+        sub_index: impl Into<SubIndex>,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        self.sdo_write_slice_locked(index, sub_index.into(), data)
+            .await
+    }
+
+    /// [`sdo_write_slice`](SubDeviceRef::sdo_write_slice), with the mailbox lock already held.
+    async fn sdo_write_slice_locked(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        if data.len() <= 4
+            || (sub_index.complete_access() && !self.state.config.mailbox.complete_access)
+        {
+            return self.sdo_write_locked(index, sub_index, data).await;
+        }
+
+        self.sdo_download_segmented(index, sub_index, data).await
+    }
+
+    /// Send a normal SDO download request followed by as many download segments as are required
+    /// to send all of `data`.
+    async fn sdo_download_segmented(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let max_len = self.sdo_download_chunk_len()?;
+
+        let (first, mut rest) = data.split_at(data.len().min(max_len));
+
+        self.sdo_download_normal(index, sub_index, data.len(), first)
+            .await?;
+
+        // Segment data follows the 1 byte segment header instead of the 4 byte SDO header and 4
+        // byte complete size, so we can fit a few more bytes into each segment.
+        let segment_len = max_len + 7;
+
+        let mut toggle = false;
+
+        while !rest.is_empty() {
+            let (chunk, remaining) = rest.split_at(rest.len().min(segment_len));
+
+            rest = remaining;
+
+            let request = coe::services::download_segmented(
+                self.mailbox_counter(),
+                toggle,
+                rest.is_empty(),
+                chunk.len() as u16,
+            );
+
+            fmt::trace!("CoE download segment, {} bytes", chunk.len());
+
+            let (response, _data) = self.send_coe_service_with_data(request, chunk).await?;
+
+            if response.sdo_header.command != CoeCommand::Download
+                || response.sdo_header.toggle != toggle
+            {
+                fmt::error!(
+                    "Invalid SDO download segment response. Command {:?} (expected {:?}), toggle {} (expected {})",
+                    response.sdo_header.command,
+                    CoeCommand::Download,
+                    response.sdo_header.toggle,
+                    toggle
+                );
+
+                return Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
+                    address: index,
+                    sub_index: sub_index.sub_index(),
+                }));
+            }
+
+            toggle = !toggle;
+        }
+
+        Ok(())
+    }
+
+    /// Write every sub-index of an object, setting sub-index 0 to the number of entries.
+    ///
+    /// Unlike [`sdo_write`](SubDeviceRef::sdo_write) with [`SubIndex::Complete`], each entry may be
+    /// a different type and length, so this method also works with SubDevices that do not support
+    /// Complete Access. For example, to assign PDOs `0x1600` and `0x1601` to the outputs sync
+    /// manager:
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, error::Error};
+    /// # async fn pdo_assign(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+    /// subdevice.sdo_write_entries(0x1c12, &[&0x1600u16, &0x1601u16]).await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// If the SubDevice supports Complete Access, the object is written in one Complete Access
+    /// download. Otherwise sub-index 0 is first set to zero, then each entry is written to its own
+    /// sub-index, then sub-index 0 is set to the number of entries.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an
+    /// [`Error::Mailbox(MailboxError::TooLong)`](crate::error::MailboxError::TooLong) if there are
+    /// more than 255 entries, or if their total length is more than 254 bytes when using Complete
+    /// Access.
+    pub async fn sdo_write_entries(
+        &self,
+        index: u16,
+        entries: &[&dyn EtherCrabWireWrite],
+    ) -> Result<(), Error> {
+        let too_long = |sub_index| {
+            Error::Mailbox(MailboxError::TooLong {
+                address: index,
+                sub_index,
+            })
+        };
+
+        let num_entries = u8::try_from(entries.len()).map_err(|_| {
+            fmt::error!(
+                "SDO {:#06x} has a maximum of 255 sub-indices, got {}",
+                index,
+                entries.len()
+            );
+
+            too_long(0)
+        })?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        if self.state.config.mailbox.complete_access {
+            let mut buf = [0u8; COMPLETE_ACCESS_MAX_LEN];
+
+            // Sub-index 0 is padded to 16 bits in a Complete Access transfer
+            buf[0] = num_entries;
+
+            let mut len = 2;
+
+            for entry in entries {
+                let packed = buf
+                    .get_mut(len..)
+                    .and_then(|buf| entry.pack_to_slice(buf).ok())
+                    .ok_or_else(|| {
+                        fmt::error!(
+                            "SDO {:#06x} complete access data is too long, max {} bytes",
+                            index,
+                            COMPLETE_ACCESS_MAX_LEN
+                        );
+
+                        too_long(0)
+                    })?;
+
+                len += packed.len();
+            }
+
+            return self
+                .sdo_write_slice_locked(index, SubIndex::Complete, &buf[..len])
+                .await;
+        }
+
+        fmt::debug!(
+            "SubDevice {:#06x} does not support complete access, writing {} sub-indices of SDO {:#06x} individually",
+            self.configured_address,
+            num_entries,
+            index
+        );
+
+        self.sdo_write_single(index, SubIndex::Index(0), 0u8)
+            .await?;
+
+        for (sub_index, entry) in (1..=num_entries).zip(entries) {
+            self.sdo_write_single(index, SubIndex::Index(sub_index), *entry)
+                .await?;
+        }
+
+        self.sdo_write_single(index, SubIndex::Index(0), num_entries)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The maximum number of data bytes that can be sent in a normal SDO download request.
+    fn sdo_download_chunk_len(&self) -> Result<usize, Error> {
+        let write_mailbox = self
+            .state
+            .config
+            .mailbox
+            .write
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        // Mailbox header, CoE header, SDO header and complete size
+        Ok(usize::from(write_mailbox.len).saturating_sub(16))
+    }
+
+    /// Send a normal SDO download request, containing the first `data.packed_len()` bytes of a
+    /// value `complete_size` bytes long.
+    async fn sdo_download_normal(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        complete_size: usize,
+        data: impl EtherCrabWireWrite,
+    ) -> Result<(), Error> {
+        let request = coe::services::download_normal(
+            self.mailbox_counter(),
+            index,
+            sub_index,
+            u16::try_from(data.packed_len())?,
+        );
+
+        fmt::trace!(
+            "CoE download normal, {} of {} bytes",
+            data.packed_len(),
+            complete_size
+        );
+
+        let (_response, _data) = self
+            .send_coe_service_with_data(request, (u32::try_from(complete_size)?, data))
+            .await?;
+
+        Ok(())
+    }
+
     pub(crate) async fn sdo_read_expedited<T>(
         &self,
         index: u16,
@@ -640,6 +1635,8 @@ where
 
         let sub_index = sub_index.into();
 
+        let _lock = self.lock_mailbox().await?;
+
         let request = coe::services::upload(self.mailbox_counter(), index, sub_index);
 
         fmt::trace!("CoE upload {:#06x} {:?}", index, sub_index);
@@ -667,6 +1664,8 @@ where
     {
         let sub_index = sub_index.into();
 
+        let _lock = self.lock_mailbox().await?;
+
         let mut storage = T::buffer();
         let buf = storage.as_mut();
 
@@ -814,10 +1813,36 @@ impl<'a, S> SubDeviceRef<'a, S> {
         futures_lite::future::try_zip(self.state(), code).await
     }
 
-    fn eeprom(&self) -> SubDeviceEeprom<DeviceEeprom> {
+    fn eeprom(&self) -> SubDeviceEeprom<DeviceEeprom<'a>> {
         SubDeviceEeprom::new(DeviceEeprom::new(self.maindevice, self.configured_address))
     }
 
+    /// Read the names of a PDO entry and its PDO from EEPROM and check if they match `name`.
+    ///
+    /// Used to check an entry found by the hash of its name really has that name.
+    pub(crate) async fn entry_name_matches(
+        &self,
+        entry_name: &EntryName,
+        name: &str,
+    ) -> Result<bool, Error> {
+        let mut entry = heapless::Vec::<u8, 255>::new();
+        let mut pdo = None;
+
+        self.eeprom()
+            .strings(|string_idx, string| {
+                if string_idx == entry_name.string_idx {
+                    entry = heapless::Vec::from_slice(string).unwrap_or_default();
+                }
+
+                if string_idx == entry_name.pdo_string_idx {
+                    pdo = heapless::Vec::<u8, 255>::from_slice(string).ok();
+                }
+            })
+            .await?;
+
+        Ok(EntryName::matches_strings(&entry, pdo.as_deref(), name))
+    }
+
     /// Read a register.
     ///
     /// Note that while this method is marked safe, raw alterations to SubDevice config or behaviour can
@@ -928,3 +1953,172 @@ impl<'a, S> SubDeviceRef<'a, S> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MainDeviceConfig, PduStorage, Timeouts};
+    use core::pin::pin;
+    use futures_lite::future::{block_on, or, yield_now};
+
+    // Command codes
+    const FPRD: u8 = 0x04;
+    const FPWR: u8 = 0x05;
+
+    const READ_MAILBOX: Mailbox = Mailbox {
+        address: 0x1080,
+        len: 16,
+        sync_manager: 1,
+    };
+
+    /// VoE message with counter 1 and 4 bytes of data.
+    const MESSAGE: [u8; 10] = [0x04, 0x00, 0x00, 0x00, 0x00, 0x1f, 0xaa, 0xbb, 0xcc, 0xdd];
+
+    /// Read a mailbox response from a SubDevice which loses the first read of its OUT mailbox,
+    /// returning the response and every byte written to the SM1 activate register.
+    fn lossy_mailbox_response(config: MainDeviceConfig) -> (Result<Vec<u8>, Error>, Vec<u8>) {
+        let storage: PduStorage<4, { PduStorage::element_size(32) }> = PduStorage::new();
+        let (mut tx, mut rx, pdu_loop) = storage.try_split().unwrap();
+
+        let maindevice = MainDevice::new(pdu_loop, Timeouts::default(), config);
+
+        let subdevice = SubDevice::default();
+        let subdevice = SubDeviceRef::new(&maindevice, 0x1000, &subdevice);
+
+        let enable_register = RegisterAddress::sync_manager_enable(1);
+        let status_register = RegisterAddress::sync_manager_status(1);
+
+        let mut repeat = false;
+        let mut mailbox_reads = 0;
+        let mut enable_writes = Vec::new();
+
+        let network = async {
+            loop {
+                while let Some(frame) = tx.next_sendable_frame() {
+                    let mut response = Vec::new();
+
+                    frame
+                        .send_blocking(|bytes| {
+                            response = bytes.to_vec();
+
+                            Ok(bytes.len())
+                        })
+                        .unwrap();
+
+                    // Ethernet header, EtherCAT header
+                    let pdu = 14 + 2;
+                    let command = response[pdu];
+                    let register = u16::from_le_bytes([response[pdu + 4], response[pdu + 5]]);
+                    let len = usize::from(
+                        u16::from_le_bytes([response[pdu + 6], response[pdu + 7]]) & 0x07ff,
+                    );
+                    let data = pdu + 10;
+
+                    let mut wkc = 1;
+
+                    match (command, register) {
+                        (FPRD, r) if r == status_register => {
+                            // Mailbox full
+                            response[data] = 0x08;
+                        }
+                        (FPRD, r) if r == enable_register => {
+                            // The SubDevice acknowledges repeat requests immediately
+                            let repeat = u8::from(repeat);
+
+                            response[data] = 0x01 | repeat << 1;
+                            response[data + 1] = repeat << 1;
+                        }
+                        (FPWR, r) if r == enable_register => {
+                            enable_writes.extend_from_slice(&response[data..data + len]);
+
+                            repeat = response[data] & 0x02 != 0;
+                        }
+                        (FPRD, r) if r == READ_MAILBOX.address => {
+                            mailbox_reads += 1;
+
+                            if mailbox_reads == 1 {
+                                // Response frame is lost after the SubDevice released the mailbox
+                                wkc = 0;
+                            } else {
+                                response[data..data + MESSAGE.len()].copy_from_slice(&MESSAGE);
+                            }
+                        }
+                        other => panic!("unexpected PDU {:?}", other),
+                    }
+
+                    response[data + len..data + len + 2].copy_from_slice(&u16::to_le_bytes(wkc));
+
+                    // Make the frame look like it came from a SubDevice
+                    response[6] = 0x12;
+
+                    rx.receive_frame(&response).unwrap();
+                }
+
+                yield_now().await;
+            }
+        };
+
+        let response = block_on(or(
+            pin!(async {
+                subdevice
+                    .mailbox_response(&READ_MAILBOX)
+                    .await
+                    .map(|response| response.to_vec())
+            }),
+            network,
+        ));
+
+        (response, enable_writes)
+    }
+
+    #[test]
+    fn mailbox_repeat_request() {
+        let (response, enable_writes) = lossy_mailbox_response(MainDeviceConfig::default());
+
+        assert_eq!(
+            response.as_ref().map(|r| &r[0..MESSAGE.len()]),
+            Ok(MESSAGE.as_slice())
+        );
+        // Repeat bit toggled once, with the SM left enabled
+        assert_eq!(enable_writes, [0x03]);
+    }
+
+    #[test]
+    fn mailbox_repeat_disabled() {
+        let (response, enable_writes) = lossy_mailbox_response(MainDeviceConfig {
+            mailbox_repeats: 0,
+            ..MainDeviceConfig::default()
+        });
+
+        assert_eq!(
+            response,
+            Err(Error::WorkingCounter {
+                expected: 1,
+                received: 0
+            })
+        );
+        assert!(enable_writes.is_empty());
+    }
+
+    #[test]
+    fn complete_access_unsupported() {
+        let storage: PduStorage<4, { PduStorage::element_size(32) }> = PduStorage::new();
+        let (mut tx, _rx, pdu_loop) = storage.try_split().unwrap();
+
+        let maindevice =
+            MainDevice::new(pdu_loop, Timeouts::default(), MainDeviceConfig::default());
+
+        let subdevice = SubDevice::default();
+        let subdevice = SubDeviceRef::new(&maindevice, 0x1000, &subdevice);
+
+        assert_eq!(
+            block_on(subdevice.sdo_write(0x1c12, SubIndex::Complete, [1u8, 0, 0x00, 0x16])),
+            Err(Error::Mailbox(MailboxError::NoCompleteAccess {
+                address: 0x1c12
+            }))
+        );
+
+        // Nothing was sent to the SubDevice
+        assert!(tx.next_sendable_frame().is_none());
+    }
+}
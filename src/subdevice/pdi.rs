@@ -1,6 +1,13 @@
 use super::{SubDevice, SubDeviceRef};
+use crate::{
+    error::{Error, Item},
+    pdi::{read_bits, write_bits},
+};
 use atomic_refcell::AtomicRefMut;
-use core::ops::Deref;
+use core::{
+    ops::Deref,
+    sync::atomic::{AtomicU8, Ordering},
+};
 
 /// Process Data Image (PDI) segments for a given SubDevice.
 ///
@@ -13,6 +20,9 @@ pub struct SubDevicePdi<'group> {
     inputs: &'group [u8],
 
     outputs: &'group mut [u8],
+
+    /// Bit-packed outputs, which share bytes with other SubDevices so must be accessed atomically.
+    shared_outputs: &'group [AtomicU8],
 }
 
 impl<'group> Deref for SubDevicePdi<'group> {
@@ -28,15 +38,96 @@ impl<'group> SubDevicePdi<'group> {
         subdevice: AtomicRefMut<'group, SubDevice>,
         inputs: &'group [u8],
         outputs: &'group mut [u8],
+        shared_outputs: &'group [AtomicU8],
     ) -> Self {
         Self {
             subdevice,
             inputs,
             outputs,
+            shared_outputs,
+        }
+    }
+
+    /// Copy `bit_len` bits of the SubDevice's inputs, or outputs if `input` is false, into `buf`.
+    ///
+    /// `bit_offset` is counted from the start of the first byte of the inputs or outputs.
+    pub(crate) fn read_bits(
+        &self,
+        input: bool,
+        bit_offset: usize,
+        bit_len: usize,
+        buf: &mut [u8],
+    ) -> Result<(), Error> {
+        let bytes = if input {
+            self.inputs.len()
+        } else {
+            self.outputs.len().max(self.shared_outputs.len())
+        };
+
+        check_bits(bytes, bit_offset, bit_len)?;
+
+        if input {
+            read_bits(self.inputs, bit_offset, bit_len, buf);
+        } else if self.shared_outputs.is_empty() {
+            read_bits(self.outputs, bit_offset, bit_len, buf);
+        } else {
+            for bit in 0..bit_len {
+                let src = bit_offset + bit;
+
+                let value = self.shared_outputs[src / 8].load(Ordering::Relaxed) >> (src % 8) & 1;
+
+                buf[bit / 8] = buf[bit / 8] & !(1 << (bit % 8)) | value << (bit % 8);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy the first `bit_len` bits of `value` into the SubDevice's outputs.
+    ///
+    /// `bit_offset` is counted from the start of the first byte of the outputs. Bit-packed outputs
+    /// are written atomically so bits belonging to other SubDevices are left unchanged.
+    pub(crate) fn write_output_bits(
+        &mut self,
+        bit_offset: usize,
+        bit_len: usize,
+        value: &[u8],
+    ) -> Result<(), Error> {
+        if self.shared_outputs.is_empty() {
+            check_bits(self.outputs.len(), bit_offset, bit_len)?;
+
+            write_bits(self.outputs, bit_offset, bit_len, value);
+        } else {
+            check_bits(self.shared_outputs.len(), bit_offset, bit_len)?;
+
+            for bit in 0..bit_len {
+                let dest = bit_offset + bit;
+                let mask = 1 << (dest % 8);
+
+                if value[bit / 8] >> (bit % 8) & 1 == 1 {
+                    self.shared_outputs[dest / 8].fetch_or(mask, Ordering::Relaxed);
+                } else {
+                    self.shared_outputs[dest / 8].fetch_and(!mask, Ordering::Relaxed);
+                }
+            }
         }
+
+        Ok(())
     }
 }
 
+/// Check that `bit_len` bits starting at `bit_offset` fit in `bytes` bytes.
+fn check_bits(bytes: usize, bit_offset: usize, bit_len: usize) -> Result<(), Error> {
+    if bit_offset + bit_len > bytes * 8 {
+        return Err(Error::NotFound {
+            item: Item::PdoEntry,
+            index: None,
+        });
+    }
+
+    Ok(())
+}
+
 /// Methods used when a SubDevice is part of a group and part of the PDI has been mapped to it.
 impl<'a, 'group> SubDeviceRef<'a, SubDevicePdi<'group>> {
     /// Get a tuple of (&I, &mut O) for this SubDevice in the Process Data Image (PDI).
@@ -121,4 +212,75 @@ impl<'a, 'group> SubDeviceRef<'a, SubDevicePdi<'group>> {
     pub fn outputs_raw_mut(&mut self) -> &mut [u8] {
         self.state.outputs
     }
+
+    /// Get the value of an input bit, counted from the SubDevice's first input bit.
+    ///
+    /// This works for both byte-aligned and
+    /// [bit-packed](crate::SubDeviceGroup::set_bit_packed_mapping) SubDevices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit` is not less than the number of input bits.
+    pub fn input_bit(&self, bit: usize) -> bool {
+        let input = &self.state.io_segments().input;
+
+        assert!(bit < input.bit_len, "input bit {} out of range", bit);
+
+        let bit = usize::from(input.start_bit) + bit;
+
+        self.state.inputs[bit / 8] >> (bit % 8) & 1 == 1
+    }
+
+    /// Get the value of an output bit, counted from the SubDevice's first output bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit` is not less than the number of output bits.
+    pub fn output_bit(&self, bit: usize) -> bool {
+        let (byte, mask) = self.output_bit_position(bit);
+
+        let value = if self.state.shared_outputs.is_empty() {
+            self.state.outputs[byte]
+        } else {
+            self.state.shared_outputs[byte].load(Ordering::Relaxed)
+        };
+
+        value & mask != 0
+    }
+
+    /// Set the value of an output bit, counted from the SubDevice's first output bit.
+    ///
+    /// This works for both byte-aligned and
+    /// [bit-packed](crate::SubDeviceGroup::set_bit_packed_mapping) SubDevices. Bits belonging to
+    /// other SubDevices that share the same byte are left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit` is not less than the number of output bits.
+    pub fn set_output_bit(&mut self, bit: usize, value: bool) {
+        let (byte, mask) = self.output_bit_position(bit);
+
+        if let Some(shared) = self.state.shared_outputs.get(byte) {
+            if value {
+                shared.fetch_or(mask, Ordering::Relaxed);
+            } else {
+                shared.fetch_and(!mask, Ordering::Relaxed);
+            }
+        } else if value {
+            self.state.outputs[byte] |= mask;
+        } else {
+            self.state.outputs[byte] &= !mask;
+        }
+    }
+
+    /// Byte index and bit mask of an output bit.
+    fn output_bit_position(&self, bit: usize) -> (usize, u8) {
+        let output = &self.state.io_segments().output;
+
+        assert!(bit < output.bit_len, "output bit {} out of range", bit);
+
+        let bit = usize::from(output.start_bit) + bit;
+
+        (bit / 8, 1 << (bit % 8))
+    }
 }
@@ -0,0 +1,238 @@
+use super::{SubDevice, SubDeviceRef};
+use crate::{
+    coe::pdo_assignment::{
+        PdoAssignment, PdoMapping, MAX_SUB_INDICES, RX_PDO_ASSIGNMENT, RX_PDO_MAPPING,
+        TX_PDO_ASSIGNMENT, TX_PDO_MAPPING,
+    },
+    error::{Error, Item, PdoError},
+    fmt, SubDeviceState,
+};
+use core::ops::{Deref, RangeInclusive};
+use ethercrab_wire::{EtherCrabWireReadSized, EtherCrabWireWrite};
+
+impl<'a, S> SubDeviceRef<'a, S>
+where
+    S: Deref<Target = SubDevice>,
+{
+    /// Assign and map the SubDevice's RxPDOs and TxPDOs using CoE.
+    ///
+    /// The SubDevice must be in PRE-OP, i.e. in a group returned by
+    /// [`MainDevice::init`](crate::MainDevice::init) before it is moved into SAFE-OP or OP, so
+    /// the new mapping is used when the group PDI is configured.
+    ///
+    /// For each direction set in `assignment`, the assignment object (`0x1c12` or `0x1c13`) is
+    /// cleared, then each PDO mapping object is cleared, filled and committed by writing its entry
+    /// count, and finally the PDOs are assigned and the assignment count is committed. Every value
+    /// is then read back to check the SubDevice accepted it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{
+    /// #     error::Error, MainDevice, MainDeviceConfig, PduStorage, Timeouts, std::ethercat_now,
+    /// #     PdoAssignment, PdoMapping, PdoMappingEntry,
+    /// # };
+    /// # static PDU_STORAGE: PduStorage<8, 32> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let maindevice = MainDevice::new(pdu_loop, Timeouts::default(), MainDeviceConfig::default());
+    /// # async {
+    /// let mut group = maindevice
+    ///     .init_single_group::<8, 8>(ethercat_now)
+    ///     .await
+    ///     .expect("Init");
+    ///
+    /// let subdevice = group.subdevice(&maindevice, 0).expect("No SubDevice!");
+    ///
+    /// subdevice
+    ///     .configure_pdos(
+    ///         &PdoAssignment::new()
+    ///             // Use the fixed 0x1702 RxPDO as-is
+    ///             .rx_pdos(&[PdoMapping::fixed(0x1702)])
+    ///             .tx_pdos(&[PdoMapping::new(
+    ///                 0x1a00,
+    ///                 &[
+    ///                     PdoMappingEntry::new(0x6041, 0, 16),
+    ///                     PdoMappingEntry::new(0x6064, 0, 32),
+    ///                 ],
+    ///             )]),
+    ///     )
+    ///     .await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`Error::InvalidState`] if the SubDevice is not in PRE-OP, and an
+    /// [`Error::Capacity`] if more than 254 PDOs or PDO entries are given for one object.
+    ///
+    /// A PDO index outside `0x1600..=0x17ff` for RxPDOs or `0x1a00..=0x1bff` for TxPDOs returns
+    /// an [`Error::Pdo(PdoError::InvalidIndex)`](PdoError::InvalidIndex). These
+    /// are checked before anything is written.
+    ///
+    /// If a value read back differs from the value written, an
+    /// [`Error::Pdo(PdoError::Verify)`](PdoError::Verify) is returned.
+    pub async fn configure_pdos(&self, assignment: &PdoAssignment<'_>) -> Result<(), Error> {
+        if let Some(pdos) = assignment.rx {
+            check_pdos(pdos, RX_PDO_MAPPING)?;
+        }
+
+        if let Some(pdos) = assignment.tx {
+            check_pdos(pdos, TX_PDO_MAPPING)?;
+        }
+
+        let (state, _status) = self.status().await?;
+
+        if state != SubDeviceState::PreOp {
+            return Err(Error::InvalidState {
+                expected: SubDeviceState::PreOp,
+                actual: state,
+                configured_address: self.configured_address,
+            });
+        }
+
+        if let Some(pdos) = assignment.rx {
+            self.assign_pdos(RX_PDO_ASSIGNMENT, pdos).await?;
+        }
+
+        if let Some(pdos) = assignment.tx {
+            self.assign_pdos(TX_PDO_ASSIGNMENT, pdos).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Write and verify a PDO assignment object and the mapping of every PDO assigned with it.
+    async fn assign_pdos(&self, assignment: u16, pdos: &[PdoMapping<'_>]) -> Result<(), Error> {
+        fmt::debug!(
+            "SubDevice {:#06x} assign {} PDOs with {:#06x}",
+            self.configured_address,
+            pdos.len(),
+            assignment
+        );
+
+        // PDO mappings can only be changed while they are not assigned.
+        self.sdo_write(assignment, 0, 0u8).await?;
+
+        for pdo in pdos {
+            let Some(entries) = pdo.entries else {
+                continue;
+            };
+
+            self.sdo_write(pdo.index, 0, 0u8).await?;
+
+            for (sub_index, entry) in (1u8..).zip(entries) {
+                self.sdo_write(pdo.index, sub_index, entry.raw()).await?;
+            }
+
+            self.sdo_write(pdo.index, 0, entries.len() as u8).await?;
+        }
+
+        for (sub_index, pdo) in (1u8..).zip(pdos) {
+            self.sdo_write(assignment, sub_index, pdo.index).await?;
+        }
+
+        self.sdo_write(assignment, 0, pdos.len() as u8).await?;
+
+        for pdo in pdos {
+            let Some(entries) = pdo.entries else {
+                continue;
+            };
+
+            self.verify_sdo(pdo.index, 0, entries.len() as u8).await?;
+
+            for (sub_index, entry) in (1u8..).zip(entries) {
+                self.verify_sdo(pdo.index, sub_index, entry.raw()).await?;
+            }
+        }
+
+        self.verify_sdo(assignment, 0, pdos.len() as u8).await?;
+
+        for (sub_index, pdo) in (1u8..).zip(pdos) {
+            self.verify_sdo(assignment, sub_index, pdo.index).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Read an SDO back and check it holds the value that was written to it.
+    async fn verify_sdo<T>(&self, index: u16, sub_index: u8, expected: T) -> Result<(), Error>
+    where
+        T: EtherCrabWireReadSized + EtherCrabWireWrite + PartialEq,
+    {
+        let actual = self.sdo_read::<T>(index, sub_index).await?;
+
+        if actual != expected {
+            fmt::error!(
+                "SubDevice {:#06x} PDO object {:#06x}:{} read back a different value to the one written",
+                self.configured_address,
+                index,
+                sub_index
+            );
+
+            return Err(Error::Pdo(PdoError::Verify { index, sub_index }));
+        }
+
+        Ok(())
+    }
+}
+
+/// Check the PDOs for one direction can be written to the SubDevice.
+fn check_pdos(pdos: &[PdoMapping<'_>], indices: RangeInclusive<u16>) -> Result<(), Error> {
+    if pdos.len() > MAX_SUB_INDICES {
+        return Err(Error::Capacity(Item::Pdo));
+    }
+
+    for pdo in pdos {
+        if !indices.contains(&pdo.index) {
+            return Err(Error::Pdo(PdoError::InvalidIndex { index: pdo.index }));
+        }
+
+        if pdo
+            .entries
+            .is_some_and(|entries| entries.len() > MAX_SUB_INDICES)
+        {
+            return Err(Error::Capacity(Item::PdoEntry));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PdoMappingEntry;
+
+    #[test]
+    fn pdo_ranges() {
+        let entries = [PdoMappingEntry::new(0x6040, 0, 16)];
+
+        assert_eq!(
+            check_pdos(&[PdoMapping::new(0x1600, &entries)], RX_PDO_MAPPING),
+            Ok(())
+        );
+        assert_eq!(
+            check_pdos(&[PdoMapping::fixed(0x1bff)], TX_PDO_MAPPING),
+            Ok(())
+        );
+        assert_eq!(
+            check_pdos(&[PdoMapping::new(0x1a00, &entries)], RX_PDO_MAPPING),
+            Err(Error::Pdo(PdoError::InvalidIndex { index: 0x1a00 }))
+        );
+    }
+
+    #[test]
+    fn too_many_entries() {
+        let entries = [PdoMappingEntry::gap(1); MAX_SUB_INDICES + 1];
+
+        assert_eq!(
+            check_pdos(&[PdoMapping::new(0x1600, &entries)], RX_PDO_MAPPING),
+            Err(Error::Capacity(Item::PdoEntry))
+        );
+        assert_eq!(
+            check_pdos(&[PdoMapping::fixed(0x1600); 255], RX_PDO_MAPPING),
+            Err(Error::Capacity(Item::Pdo))
+        );
+    }
+}
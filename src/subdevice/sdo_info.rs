@@ -0,0 +1,360 @@
+use super::{mailbox_lock::MailboxGuard, types::Mailbox, SubDevice, SubDeviceRef};
+use crate::{
+    coe::{
+        abort_code::CoeAbortCode,
+        sdo_info::{
+            self, EntryDescription, ObjectDescription, OdListType, SdoInfoHeaders, SdoInfoOpCode,
+        },
+        CoeService,
+    },
+    error::{Error, MailboxError},
+    fmt,
+    mailbox::{GenericMailboxHeader, MailboxType},
+};
+use core::{future::poll_fn, ops::Deref, task::Poll};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWriteSized};
+
+/// The maximum number of object indices in a single Get OD List response fragment.
+///
+/// This is the longest mailbox that fits in an Ethernet frame (1486 bytes of PDU data), minus the
+/// 12 bytes of mailbox, CoE and SDO Info headers.
+const MAX_FRAGMENT_INDICES: usize = (1486 - SdoInfoHeaders::PACKED_LEN) / 2;
+
+/// An async iterator over object indices in a SubDevice's object dictionary.
+///
+/// Created by calling [`SubDeviceRef::sdo_info_object_list`]. Call
+/// [`next`](ObjectList::next) to get each index in turn.
+///
+/// The list is sent by the SubDevice in fragments which are read as they are needed. The
+/// SubDevice's mailbox is locked from the first call to [`next`](ObjectList::next) until the
+/// iterator returns `None` or is dropped. Mailbox requests to the same SubDevice from other tasks
+/// wait until then. A request made by the task calling [`next`](ObjectList::next) in the
+/// meantime would wait for itself forever, so it returns [`Error::Borrow`] instead.
+pub struct ObjectList<'r, 'a, S> {
+    subdevice: &'r SubDeviceRef<'a, S>,
+    list_type: OdListType,
+    read_mailbox: Option<Mailbox>,
+    lock: Option<MailboxGuard<'r>>,
+    indices: heapless::Vec<u16, MAX_FRAGMENT_INDICES>,
+    pos: usize,
+    done: bool,
+}
+
+impl<'r, 'a, S> ObjectList<'r, 'a, S>
+where
+    S: Deref<Target = SubDevice>,
+{
+    /// Get the next object index, or `None` if all indices have been returned.
+    ///
+    /// If an error is encountered, it is returned once and the iterator then ends.
+    pub async fn next(&mut self) -> Option<Result<u16, Error>> {
+        self.set_lock_owner().await;
+
+        loop {
+            if let Some(index) = self.indices.get(self.pos) {
+                self.pos += 1;
+
+                return Some(Ok(*index));
+            }
+
+            if self.done {
+                // Allow other mailbox requests as soon as the whole list has been read
+                self.lock = None;
+
+                return None;
+            }
+
+            if let Err(e) = self.load_fragment().await {
+                self.done = true;
+                self.lock = None;
+
+                return Some(Err(e));
+            }
+        }
+    }
+
+    /// Record the task calling [`next`](ObjectList::next) as the owner of the mailbox lock.
+    ///
+    /// The lock is held between calls to `next`, during which that task may try to use the
+    /// mailbox again.
+    async fn set_lock_owner(&self) {
+        if let Some(lock) = &self.lock {
+            poll_fn(|cx| {
+                lock.set_owner(cx.waker());
+
+                Poll::Ready(())
+            })
+            .await
+        }
+    }
+
+    /// Send the Get OD List request if required, then load the next response fragment.
+    async fn load_fragment(&mut self) -> Result<(), Error> {
+        let is_first = self.read_mailbox.is_none();
+
+        let read_mailbox = match self.read_mailbox {
+            Some(read_mailbox) => read_mailbox,
+            None => {
+                self.lock = Some(self.subdevice.lock_mailbox().await?);
+
+                self.set_lock_owner().await;
+
+                let request =
+                    sdo_info::get_od_list(self.subdevice.mailbox_counter(), self.list_type);
+
+                fmt::trace!("SDO Info get OD list {:?}", self.list_type);
+
+                let read_mailbox = self.subdevice.send_sdo_info_request(request.pack()).await?;
+
+                self.read_mailbox = Some(read_mailbox);
+
+                read_mailbox
+            }
+        };
+
+        let (headers, data) = self
+            .subdevice
+            .sdo_info_response(&read_mailbox, SdoInfoOpCode::GetOdListResponse, 0, 0)
+            .await?;
+
+        let data = data.get(0..headers.data_len()).unwrap_or(&data);
+
+        // Only the first fragment starts with the list type
+        let data = if is_first {
+            data.get(2..).unwrap_or_default()
+        } else {
+            data
+        };
+
+        self.indices.clear();
+        self.pos = 0;
+
+        for index in data.chunks_exact(2) {
+            // Length is checked by MAX_FRAGMENT_INDICES
+            let _ = self.indices.push(u16::unpack_from_slice(index)?);
+        }
+
+        self.done = headers.sdo_info_header.fragments_left == 0;
+
+        Ok(())
+    }
+}
+
+impl<'a, S> SubDeviceRef<'a, S>
+where
+    S: Deref<Target = SubDevice>,
+{
+    /// Browse the SubDevice's object dictionary using the SDO Information service.
+    ///
+    /// This returns an async iterator over all object indices in the given list.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, error::Error, OdListType};
+    /// # async fn browse(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+    /// let mut objects = subdevice.sdo_info_object_list(OdListType::All);
+    ///
+    /// // The mailbox is locked until the whole list has been read, so store the indices before
+    /// // making any other requests.
+    /// let mut indices = heapless::Vec::<u16, 512>::new();
+    ///
+    /// while let Some(index) = objects.next().await {
+    ///     let _ = indices.push(index?);
+    /// }
+    ///
+    /// for index in indices {
+    ///     let desc = subdevice.sdo_info_object_description(index).await?;
+    ///
+    ///     println!("{:#06x} {}", index, desc.name);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn sdo_info_object_list(&self, list_type: OdListType) -> ObjectList<'_, 'a, S> {
+        ObjectList {
+            subdevice: self,
+            list_type,
+            read_mailbox: None,
+            lock: None,
+            indices: heapless::Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    /// Get the description of an object in the SubDevice's object dictionary using the SDO
+    /// Information service.
+    pub async fn sdo_info_object_description(
+        &self,
+        index: u16,
+    ) -> Result<ObjectDescription, Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        let request = sdo_info::get_object_description(self.mailbox_counter(), index);
+
+        fmt::trace!("SDO Info get object description {:#06x}", index);
+
+        let read_mailbox = self.send_sdo_info_request(request.pack()).await?;
+
+        let data = self
+            .sdo_info_complete_response(
+                &read_mailbox,
+                SdoInfoOpCode::GetObjectDescriptionResponse,
+                index,
+                0,
+            )
+            .await?;
+
+        Ok(ObjectDescription::unpack_from_slice(&data)?)
+    }
+
+    /// Get the description of a single sub-index of an object in the SubDevice's object
+    /// dictionary using the SDO Information service.
+    pub async fn sdo_info_entry_description(
+        &self,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<EntryDescription, Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        let request = sdo_info::get_entry_description(self.mailbox_counter(), index, sub_index);
+
+        fmt::trace!(
+            "SDO Info get entry description {:#06x}:{}",
+            index,
+            sub_index
+        );
+
+        let read_mailbox = self.send_sdo_info_request(request.pack()).await?;
+
+        let data = self
+            .sdo_info_complete_response(
+                &read_mailbox,
+                SdoInfoOpCode::GetEntryDescriptionResponse,
+                index,
+                sub_index,
+            )
+            .await?;
+
+        Ok(EntryDescription::unpack_from_slice(&data)?)
+    }
+
+    /// Write an SDO Information request to the SubDevice's IN mailbox, returning the mailbox to
+    /// read the response from.
+    async fn send_sdo_info_request(&self, request: impl AsRef<[u8]>) -> Result<Mailbox, Error> {
+        let counter = GenericMailboxHeader::unpack_from_slice(request.as_ref())?.counter;
+
+        let (read_mailbox, write_mailbox) = self.ready_mailboxes(counter).await?;
+
+        self.write(write_mailbox.address)
+            .mailbox()
+            .with_len(write_mailbox.len)
+            .send(self.maindevice, request.as_ref())
+            .await?;
+
+        Ok(read_mailbox)
+    }
+
+    /// Read a response that fits in a single fragment, discarding any further fragments.
+    ///
+    /// Descriptions are only fragmented if the name is very long, so any data in later fragments
+    /// would be truncated anyway.
+    async fn sdo_info_complete_response(
+        &self,
+        read_mailbox: &Mailbox,
+        op_code: SdoInfoOpCode,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<heapless::Vec<u8, 128>, Error> {
+        let (headers, data) = self
+            .sdo_info_response(read_mailbox, op_code, index, sub_index)
+            .await?;
+
+        let data = data.get(0..headers.data_len()).unwrap_or(&data);
+
+        let data = heapless::Vec::from_slice(&data[0..data.len().min(128)])
+            .map_err(|_| Error::Internal)?;
+
+        let mut fragments_left = headers.sdo_info_header.fragments_left;
+
+        while fragments_left > 0 {
+            let (headers, _data) = self
+                .sdo_info_response(read_mailbox, op_code, index, sub_index)
+                .await?;
+
+            fragments_left = headers.sdo_info_header.fragments_left;
+        }
+
+        Ok(data)
+    }
+
+    /// Read a single SDO Information response fragment from the SubDevice's OUT mailbox.
+    ///
+    /// The returned data starts after the SDO Info header.
+    async fn sdo_info_response(
+        &self,
+        read_mailbox: &Mailbox,
+        op_code: SdoInfoOpCode,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<(SdoInfoHeaders, crate::pdu_loop::ReceivedPdu), Error> {
+        let mut response = self.mailbox_response(read_mailbox).await?;
+
+        let headers = SdoInfoHeaders::unpack_from_slice(&response)?;
+
+        // The SubDevice sends the remaining fragments without any further requests
+        if headers.sdo_info_header.fragments_left > 0 {
+            self.set_response_pending(true);
+        }
+
+        response.trim_front(SdoInfoHeaders::PACKED_LEN);
+
+        if headers.header.mailbox_type != MailboxType::Coe
+            || headers.header.service != CoeService::SdoInformation
+        {
+            fmt::error!(
+                "Invalid SDO Info response. Type: {:?} (expected {:?}), service {:?} (expected {:?})",
+                headers.header.mailbox_type,
+                MailboxType::Coe,
+                headers.header.service,
+                CoeService::SdoInformation
+            );
+
+            return Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
+                address: index,
+                sub_index,
+            }));
+        }
+
+        if headers.sdo_info_header.op_code == SdoInfoOpCode::ErrorRequest {
+            let code = CoeAbortCode::unpack_from_slice(&response)?;
+
+            fmt::error!(
+                "SDO Info error for SubDevice {:#06x}: {}",
+                self.configured_address(),
+                code
+            );
+
+            return Err(Error::Mailbox(MailboxError::Aborted {
+                code,
+                address: index,
+                sub_index,
+            }));
+        }
+
+        if headers.sdo_info_header.op_code != op_code {
+            fmt::error!(
+                "Invalid SDO Info response opcode {:?}, expected {:?}",
+                headers.sdo_info_header.op_code,
+                op_code
+            );
+
+            return Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
+                address: index,
+                sub_index,
+            }));
+        }
+
+        Ok((headers, response))
+    }
+}
@@ -0,0 +1,288 @@
+use super::{types::Mailbox, SubDevice, SubDeviceRef};
+use crate::{
+    error::{Error, MailboxError, SoeError},
+    fmt,
+    mailbox::{GenericMailboxHeader, MailboxType},
+    pdu_loop::ReceivedPdu,
+    soe::{SoeElement, SoeErrorCode, SoeHeader, SoeOpCode},
+};
+use core::ops::Deref;
+use ethercrab_wire::{
+    EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireSized, EtherCrabWireWriteSized,
+};
+
+/// Servo drive profile over EtherCAT (SoE) methods.
+///
+/// Parameters are addressed by IDN (identification number). Standard `S-x-yyyy` IDNs are given
+/// as `(x << 12) | yyyy`, and product specific `P-x-yyyy` IDNs additionally set bit 15, so e.g.
+/// `P-0-0001` is `0x8001`.
+///
+/// `drive_number` selects the drive to address in SubDevices which control more than one, and is
+/// `0` otherwise.
+impl<'a, S> SubDeviceRef<'a, S>
+where
+    S: Deref<Target = SubDevice>,
+{
+    /// Read an element of an IDN from the SubDevice using SoE.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, error::Error, SoeElement};
+    /// # async fn read(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+    /// // S-0-0044, velocity data scaling type
+    /// let scaling = subdevice.soe_read::<u16>(0, 44, SoeElement::Value).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn soe_read<T>(
+        &self,
+        drive_number: u8,
+        idn: u16,
+        element: SoeElement,
+    ) -> Result<T, Error>
+    where
+        T: EtherCrabWireReadSized,
+    {
+        let mut storage = T::buffer();
+
+        let data = self
+            .soe_read_slice(drive_number, idn, element, storage.as_mut())
+            .await?;
+
+        Ok(T::unpack_from_slice(data)?)
+    }
+
+    /// Read an element of an IDN from the SubDevice using SoE into `buf`, returning the part of
+    /// `buf` that was written to.
+    ///
+    /// This method should be used for elements that are variable length or too long for
+    /// [`soe_read`](SubDeviceRef::soe_read), e.g. names or list IDNs. Data sent by the SubDevice
+    /// in multiple fragments is joined together.
+    pub async fn soe_read_slice<'buf>(
+        &self,
+        drive_number: u8,
+        idn: u16,
+        element: SoeElement,
+        buf: &'buf mut [u8],
+    ) -> Result<&'buf [u8], Error> {
+        fmt::trace!(
+            "SoE read drive {} IDN {:#06x} {:?}",
+            drive_number,
+            idn,
+            element
+        );
+
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .mailbox_write(
+                MailboxType::Soe,
+                SoeHeader::read(drive_number, idn, element).pack().as_ref(),
+            )
+            .await?;
+
+        let mut len = 0usize;
+
+        loop {
+            let (header, response, data_len) = self
+                .soe_response(&read_mailbox, SoeOpCode::ReadResponse, drive_number, idn)
+                .await?;
+
+            let data = response.get(0..data_len).ok_or(Error::Internal)?;
+
+            buf.get_mut(len..(len + data.len()))
+                .ok_or(Error::Mailbox(MailboxError::Soe(SoeError::TooLong { idn })))?
+                .copy_from_slice(data);
+
+            len += data.len();
+
+            if !header.incomplete {
+                break;
+            }
+
+            self.set_response_pending(true);
+
+            fmt::trace!(
+                "SoE read IDN {:#06x}: {} fragments left",
+                idn,
+                header.idn_or_fragments_left
+            );
+        }
+
+        Ok(&buf[0..len])
+    }
+
+    /// Write an element of an IDN in the SubDevice using SoE.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{SubDevice, SubDeviceRef, error::Error, SoeElement};
+    /// # async fn write(subdevice: SubDeviceRef<'_, &SubDevice>) -> Result<(), Error> {
+    /// // S-0-0001, NC cycle time of 1000us
+    /// subdevice.soe_write(0, 1, SoeElement::Value, 1000u16).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn soe_write<T>(
+        &self,
+        drive_number: u8,
+        idn: u16,
+        element: SoeElement,
+        value: T,
+    ) -> Result<(), Error>
+    where
+        T: EtherCrabWireWriteSized,
+    {
+        self.soe_write_slice(drive_number, idn, element, value.pack().as_ref())
+            .await
+    }
+
+    /// Write raw data to an element of an IDN in the SubDevice using SoE.
+    ///
+    /// Data too long to fit in the SubDevice's mailbox is sent in multiple fragments. List IDNs
+    /// must start with the current and maximum list length as two `u16`s.
+    pub async fn soe_write_slice(
+        &self,
+        drive_number: u8,
+        idn: u16,
+        element: SoeElement,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        fmt::trace!(
+            "SoE write drive {} IDN {:#06x} {:?}, {} bytes",
+            drive_number,
+            idn,
+            element,
+            data.len()
+        );
+
+        let _lock = self.lock_mailbox().await?;
+
+        let (_read_mailbox, write_mailbox) = self.mailboxes()?;
+
+        let max_fragment_len = usize::from(write_mailbox.len)
+            .saturating_sub(GenericMailboxHeader::PACKED_LEN + SoeHeader::PACKED_LEN);
+
+        if max_fragment_len == 0 {
+            fmt::error!(
+                "SubDevice {:#06x} mailbox of {} bytes is too small for SoE",
+                self.configured_address(),
+                write_mailbox.len
+            );
+
+            return Err(Error::Mailbox(MailboxError::TooLong {
+                address: idn,
+                sub_index: 0,
+            }));
+        }
+
+        let mut offset = 0;
+
+        // Always send at least one request, even if there is no data
+        let read_mailbox = loop {
+            let end = (offset + max_fragment_len).min(data.len());
+
+            let fragment = &data[offset..end];
+
+            let fragments_left = u16::try_from((data.len() - end).div_ceil(max_fragment_len))?;
+
+            let read_mailbox = self
+                .mailbox_write(
+                    MailboxType::Soe,
+                    (
+                        SoeHeader::write(drive_number, idn, element, fragments_left)
+                            .pack()
+                            .as_ref(),
+                        fragment,
+                    ),
+                )
+                .await?;
+
+            offset = end;
+
+            // Only the last fragment is responded to
+            if fragments_left == 0 {
+                break read_mailbox;
+            }
+
+            self.set_response_pending(false);
+        };
+
+        self.soe_response(&read_mailbox, SoeOpCode::WriteResponse, drive_number, idn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Wait for an SoE response, returning its header, data and the length of the data.
+    ///
+    /// Error responses are returned as [`SoeError::Aborted`].
+    async fn soe_response(
+        &self,
+        read_mailbox: &Mailbox,
+        op_code: SoeOpCode,
+        drive_number: u8,
+        idn: u16,
+    ) -> Result<(SoeHeader, ReceivedPdu, usize), Error> {
+        loop {
+            let (mailbox_header, mut response) =
+                self.mailbox_read(read_mailbox, MailboxType::Soe).await?;
+
+            let header = SoeHeader::unpack_from_slice(&response)?;
+
+            response.trim_front(SoeHeader::PACKED_LEN);
+
+            if matches!(
+                header.op_code,
+                SoeOpCode::Notification | SoeOpCode::SubDeviceInfo
+            ) {
+                fmt::debug!(
+                    "SubDevice {:#06x} ignoring SoE {:?}",
+                    self.configured_address(),
+                    header.op_code
+                );
+
+                self.set_response_pending(true);
+
+                continue;
+            }
+
+            if header.error {
+                let code = SoeErrorCode::unpack_from_slice(&response)?;
+
+                fmt::error!(
+                    "SubDevice {:#06x} SoE error for drive {} IDN {:#06x}: {}",
+                    self.configured_address(),
+                    drive_number,
+                    idn,
+                    code
+                );
+
+                return Err(Error::Mailbox(MailboxError::Soe(SoeError::Aborted {
+                    code,
+                    idn,
+                })));
+            }
+
+            if header.op_code != op_code || header.drive_number != drive_number {
+                fmt::error!(
+                    "SubDevice {:#06x} invalid SoE response {:?} for drive {}, expected {:?} for drive {}",
+                    self.configured_address(),
+                    header.op_code,
+                    header.drive_number,
+                    op_code,
+                    drive_number
+                );
+
+                return Err(Error::Mailbox(MailboxError::UnexpectedResponse));
+            }
+
+            let data_len = usize::from(mailbox_header.length).saturating_sub(SoeHeader::PACKED_LEN);
+
+            // The mailbox is usually longer than the response in it
+            let data_len = data_len.min(response.len());
+
+            return Ok((header, response, data_len));
+        }
+    }
+}
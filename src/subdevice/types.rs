@@ -1,8 +1,12 @@
 use crate::{
+    base_data_types::PrimitiveDataType,
     eeprom::types::{MailboxProtocols, SyncManagerType},
     pdi::PdiSegment,
 };
-use core::fmt::{self, Debug};
+use core::{
+    fmt::{self, Debug},
+    ops::Range,
+};
 
 /// SubDevice identity information (vendor ID, product ID, etc).
 #[derive(Default, Copy, Clone, PartialEq, ethercrab_wire::EtherCrabWireRead)]
@@ -72,4 +76,280 @@ pub struct Mailbox {
 pub struct IoRanges {
     pub input: PdiSegment,
     pub output: PdiSegment,
+    /// Byte offset in the group PDI of the read mailbox sync manager status, if it is mapped.
+    pub mailbox_status: Option<usize>,
+    /// Position of every discovered PDO entry in the input and output segments.
+    pub entries: PdoEntries,
+}
+
+impl IoRanges {
+    /// Entries mapped into either the inputs or outputs, in PDI order.
+    pub(crate) fn entries(&self, input: bool) -> impl Iterator<Item = &PdoEntryLayout> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.input == input)
+    }
+
+    /// Entries whose name hashes match `name`, searching inputs then outputs.
+    ///
+    /// Hashes can collide, so the real names must be checked with
+    /// [`EntryName::matches_strings`].
+    pub(crate) fn entries_by_name<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a PdoEntryLayout> + 'a {
+        self.entries(true)
+            .chain(self.entries(false))
+            .filter(move |entry| entry.name.matches(name))
+    }
+
+    /// Find the first entry in the inputs or outputs that maps the given object.
+    pub(crate) fn entry(&self, input: bool, index: u16, sub_index: u8) -> Option<PdoEntryLayout> {
+        self.entries(input)
+            .find(|entry| entry.index == index && entry.sub_index == sub_index)
+            .copied()
+    }
+
+    /// The amount this SubDevice adds to the working counter of the PDUs sending the group PDI
+    /// `range` when in OP.
+    ///
+    /// This is 1 if the range contains inputs or the mapped mailbox status. If it contains outputs,
+    /// 2 is added for an `LRW`, or 1 if the outputs are sent in a separate `LWR` when `lrw` is
+    /// false.
+    pub(crate) fn working_counter(&self, range: &Range<usize>, lrw: bool) -> u16 {
+        let overlaps = |bytes: &Range<usize>| bytes.start < range.end && range.start < bytes.end;
+
+        let read = overlaps(&self.input.bytes)
+            || self
+                .mailbox_status
+                .is_some_and(|offset| range.contains(&offset));
+
+        let write = overlaps(&self.output.bytes);
+
+        u16::from(read) + u16::from(write) * if lrw { 2 } else { 1 }
+    }
+}
+
+/// The maximum number of PDO entries recorded for each SubDevice, set by the largest enabled
+/// `pdo-entries-*` feature.
+///
+/// If no `pdo-entries-*` feature is enabled, no entries are recorded.
+pub(crate) const MAX_PDO_ENTRIES: usize = if cfg!(feature = "pdo-entries-256") {
+    256
+} else if cfg!(feature = "pdo-entries-64") {
+    64
+} else if cfg!(feature = "pdo-entries-16") {
+    16
+} else {
+    0
+};
+
+pub(crate) type PdoEntries = heapless::Vec<PdoEntryLayout, MAX_PDO_ENTRIES>;
+
+/// The position of a PDO entry in a SubDevice's inputs or outputs.
+///
+/// Entries are discovered from CoE if the SubDevice supports it, otherwise from its EEPROM, when
+/// the group PDI is configured.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PdoEntryLayout {
+    /// Index of the PDO containing this entry, e.g. `0x1a00`.
+    pub pdo: u16,
+    /// Object index, e.g. `0x6041`. Gaps in a PDO have an index of `0`.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Length of the entry in bits.
+    pub bit_len: u8,
+    /// Offset of the entry's first bit from the start of the first byte of the SubDevice's inputs
+    /// or outputs.
+    ///
+    /// For [bit-packed](crate::SubDeviceGroup::set_bit_packed_mapping) SubDevices this includes
+    /// the position of the SubDevice's first bit in its shared byte.
+    pub bit_offset: u16,
+    /// Data type of the entry read from the SubDevice EEPROM, or [`PrimitiveDataType::Unknown`] if
+    /// the entry is not listed there.
+    pub data_type: PrimitiveDataType,
+    /// True if the entry is in the inputs, false for outputs.
+    pub(crate) input: bool,
+    pub(crate) name: EntryName,
+}
+
+impl PdoEntryLayout {
+    /// The bytes of the SubDevice's inputs or outputs that hold this entry.
+    ///
+    /// Entries that do not start or end on a byte boundary share their first or last byte with
+    /// neighbouring entries.
+    pub fn byte_range(&self) -> Range<usize> {
+        let start = usize::from(self.bit_offset);
+        let end = start + usize::from(self.bit_len);
+
+        (start / 8)..end.div_ceil(8)
+    }
+}
+
+/// The name of a PDO entry and the PDO containing it, read from the SubDevice EEPROM.
+///
+/// Only hashes of the names are kept to save memory. The hashes are used to quickly find
+/// candidate entries, whose names are then read from EEPROM again to check they really match.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct EntryName {
+    /// EEPROM string index of the PDO name.
+    pub pdo_string_idx: u8,
+    /// EEPROM string index of the entry name.
+    pub string_idx: u8,
+    pub pdo_hash: Option<u32>,
+    pub hash: Option<u32>,
+}
+
+impl EntryName {
+    /// Check if the hash of this entry's name matches `name`, either on its own like `Value` or
+    /// prefixed with the PDO name like `AI Standard Channel 1.Value`.
+    ///
+    /// A different name may have the same hash, so a match must be checked with
+    /// [`matches_strings`](EntryName::matches_strings).
+    fn matches(&self, name: &str) -> bool {
+        let Some(hash) = self.hash else {
+            return false;
+        };
+
+        names_match(
+            name,
+            |entry_name| name_hash(entry_name.as_bytes()) == hash,
+            |pdo_name| {
+                self.pdo_hash
+                    .is_some_and(|pdo_hash| name_hash(pdo_name.as_bytes()) == pdo_hash)
+            },
+        )
+    }
+
+    /// Check if `name` matches the entry name `entry` and PDO name `pdo` read from EEPROM, in the
+    /// same way as [`matches`](EntryName::matches).
+    pub(crate) fn matches_strings(entry: &[u8], pdo: Option<&[u8]>, name: &str) -> bool {
+        names_match(
+            name,
+            |entry_name| name_eq(entry, entry_name),
+            |pdo_name| pdo.is_some_and(|pdo| name_eq(pdo, pdo_name)),
+        )
+    }
+}
+
+/// Check if `name` is an entry name on its own, or a PDO name and entry name separated by a `.`.
+fn names_match(
+    name: &str,
+    mut entry_eq: impl FnMut(&str) -> bool,
+    mut pdo_eq: impl FnMut(&str) -> bool,
+) -> bool {
+    entry_eq(name)
+        || name
+            .match_indices('.')
+            .any(|(idx, _)| pdo_eq(&name[..idx]) && entry_eq(&name[(idx + 1)..]))
+}
+
+/// Compare a raw EEPROM string to a name, ignoring any C null terminators.
+fn name_eq(raw: &[u8], name: &str) -> bool {
+    raw.iter()
+        .copied()
+        .filter(|byte| *byte != 0x00)
+        .eq(name.bytes())
+}
+
+/// 32 bit FNV-1a hash of a name, ignoring any C null terminators.
+pub(crate) fn name_hash(name: &[u8]) -> u32 {
+    name.iter()
+        .filter(|byte| **byte != 0x00)
+        .fold(0x811c_9dc5, |hash, byte| {
+            (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn working_counter() {
+        let io = IoRanges {
+            input: PdiSegment {
+                bytes: 0..2,
+                ..PdiSegment::default()
+            },
+            output: PdiSegment {
+                bytes: 6..8,
+                ..PdiSegment::default()
+            },
+            mailbox_status: Some(4),
+            ..IoRanges::default()
+        };
+
+        assert_eq!(io.working_counter(&(0..8), true), 3);
+        assert_eq!(io.working_counter(&(1..2), true), 1);
+        assert_eq!(io.working_counter(&(2..4), true), 0);
+        assert_eq!(io.working_counter(&(4..5), true), 1);
+        assert_eq!(io.working_counter(&(5..7), true), 2);
+
+        // Separate LRD and LWR
+        assert_eq!(io.working_counter(&(0..8), false), 2);
+        assert_eq!(io.working_counter(&(5..7), false), 1);
+
+        // SubDevice with no IO
+        assert_eq!(IoRanges::default().working_counter(&(0..8), true), 0);
+    }
+
+    #[test]
+    fn match_entry_name() {
+        let name = EntryName {
+            pdo_string_idx: 1,
+            string_idx: 2,
+            pdo_hash: Some(name_hash(b"AI Standard Channel 1")),
+            // Some EEPROMs include a C null terminator in their strings, which is ignored
+            hash: Some(name_hash(b"Value\0")),
+        };
+
+        assert!(name.matches("Value"));
+        assert!(name.matches("AI Standard Channel 1.Value"));
+        assert!(!name.matches("AI Standard Channel 2.Value"));
+        assert!(!name.matches("AI Standard Channel 1"));
+        assert!(!EntryName::default().matches(""));
+    }
+
+    #[test]
+    fn match_entry_name_strings() {
+        let pdo = Some(b"AI Standard Channel 1".as_slice());
+
+        assert!(EntryName::matches_strings(b"Value\0", pdo, "Value"));
+        assert!(EntryName::matches_strings(
+            b"Value",
+            pdo,
+            "AI Standard Channel 1.Value"
+        ));
+        assert!(!EntryName::matches_strings(
+            b"Value",
+            pdo,
+            "AI Standard Channel 2.Value"
+        ));
+        assert!(!EntryName::matches_strings(
+            b"Value",
+            None,
+            "AI Standard Channel 1.Value"
+        ));
+        assert!(!EntryName::matches_strings(b"Status", pdo, "Value"));
+    }
+
+    #[test]
+    fn colliding_entry_name() {
+        // "costarring" and "liquid" have the same 32 bit FNV-1a hash
+        assert_eq!(name_hash(b"costarring"), name_hash(b"liquid"));
+
+        let name = EntryName {
+            string_idx: 1,
+            hash: Some(name_hash(b"costarring")),
+            ..EntryName::default()
+        };
+
+        // Only the hash is checked here, so the real names must be compared too
+        assert!(name.matches("liquid"));
+        assert!(!EntryName::matches_strings(b"costarring", None, "liquid"));
+    }
 }
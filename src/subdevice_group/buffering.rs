@@ -0,0 +1,289 @@
+use super::{HasPdi, SubDeviceGroup};
+use crate::{error::Error, fmt};
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// How a group's Process Data Image (PDI) is shared between the application and the process data
+/// cycle, set with [`SubDeviceGroup::set_pdi_buffering`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PdiBuffering {
+    /// The application reads and writes the same image that is sent by
+    /// [`tx_rx`](SubDeviceGroup::tx_rx).
+    ///
+    /// Inputs change as soon as a response is received, and outputs are sent as soon as they are
+    /// written. This is the default.
+    #[default]
+    Single,
+
+    /// The application has its own image, which is exchanged with the image sent by `tx_rx` by
+    /// [`snapshot`](SubDeviceGroup::snapshot) and
+    /// [`commit_outputs`](SubDeviceGroup::commit_outputs).
+    ///
+    /// The sent image is locked while `tx_rx` is in progress, so `snapshot` and `commit_outputs`
+    /// return `false` until it completes. `tx_rx` returns [`Error::Borrow`] if the image is
+    /// locked by a `snapshot` or `commit_outputs` in progress on another thread, or by another
+    /// call to `tx_rx`.
+    Double,
+
+    /// The application and `tx_rx` each have their own image, and exchange data through a third.
+    ///
+    /// Neither side waits for the other. `tx_rx` sends the last committed outputs and publishes
+    /// received inputs whenever the exchange image is not being copied to or from by the
+    /// application.
+    Triple,
+}
+
+impl PdiBuffering {
+    /// The number of copies of the PDI stored by the group.
+    pub(crate) fn images(self) -> usize {
+        match self {
+            PdiBuffering::Single => 1,
+            PdiBuffering::Double => 2,
+            PdiBuffering::Triple => 3,
+        }
+    }
+}
+
+/// Exclusive access to the image shared between the application and the process data cycle.
+pub(super) struct ImageLock<'a>(&'a AtomicBool);
+
+impl<'a> ImageLock<'a> {
+    pub(super) fn try_lock(locked: &'a AtomicBool) -> Option<Self> {
+        locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| Self(locked))
+    }
+}
+
+impl Drop for ImageLock<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, S, DC>
+    SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, S, DC>
+where
+    S: HasPdi,
+{
+    /// Copy the latest received inputs into the application's image of the group PDI.
+    ///
+    /// The inputs read through [`subdevice`](SubDeviceGroup::subdevice),
+    /// [`read_var`](SubDeviceGroup::read_var), etc then stay the same until the next snapshot,
+    /// even if [`tx_rx`](SubDeviceGroup::tx_rx) is called from another task in the meantime.
+    ///
+    /// Returns `true` if the image was updated, or `false` if no new inputs have been received
+    /// since the last snapshot or the shared image is currently in use. Always returns `true` if
+    /// [`PdiBuffering::Single`] is used, as the application's image is updated directly.
+    pub fn snapshot(&self) -> bool {
+        let inner = self.inner();
+
+        let Some(shared) = self.shared_image() else {
+            return true;
+        };
+
+        let Some(_lock) = ImageLock::try_lock(&inner.image_locked) else {
+            return false;
+        };
+
+        if !inner.inputs_fresh.swap(false, Ordering::Relaxed) {
+            return false;
+        }
+
+        let inputs = self.inputs_range();
+
+        self.pdi_mut()[inputs.clone()].copy_from_slice(&shared[inputs]);
+
+        true
+    }
+
+    /// Copy the outputs written to the application's image of the group PDI so they are sent by
+    /// the next call to [`tx_rx`](SubDeviceGroup::tx_rx).
+    ///
+    /// Outputs written through [`subdevice`](SubDeviceGroup::subdevice),
+    /// [`write_var`](SubDeviceGroup::write_var), etc are not sent until they are committed, so a
+    /// set of outputs is always sent together.
+    ///
+    /// Returns `false` if the shared image is currently in use, in which case nothing is committed
+    /// and this method should be called again. Always returns `true` if [`PdiBuffering::Single`]
+    /// is used, as outputs are sent directly from the application's image.
+    pub fn commit_outputs(&self) -> bool {
+        let Some(shared) = self.shared_image() else {
+            return true;
+        };
+
+        let Some(_lock) = ImageLock::try_lock(&self.inner().image_locked) else {
+            return false;
+        };
+
+        let outputs = self.outputs_range();
+
+        shared[outputs.clone()].copy_from_slice(&self.pdi()[outputs]);
+
+        true
+    }
+
+    /// Prepare the image sent by the process data cycle.
+    ///
+    /// With [`PdiBuffering::Double`], the returned lock must be held until the response is
+    /// received. If the image is already locked, [`Error::Borrow`] is returned, as waiting for a
+    /// lock held across an `await` by another `tx_rx` could block forever. With
+    /// [`PdiBuffering::Triple`], the last committed outputs are copied into the sent image if the
+    /// exchange image isn't in use.
+    pub(super) fn latch_outputs(&self) -> Result<Option<ImageLock<'_>>, Error> {
+        let inner = self.inner();
+
+        match inner.buffering {
+            PdiBuffering::Single => Ok(None),
+            PdiBuffering::Double => ImageLock::try_lock(&inner.image_locked)
+                .map(Some)
+                .ok_or_else(|| {
+                    fmt::error!("Group PDI image is already in use");
+
+                    Error::Borrow
+                }),
+            PdiBuffering::Triple => {
+                if let (Some(shared), Some(_lock)) = (
+                    self.shared_image(),
+                    ImageLock::try_lock(&inner.image_locked),
+                ) {
+                    let outputs = self.outputs_range();
+
+                    self.wire_image()[outputs.clone()].copy_from_slice(&shared[outputs]);
+                }
+
+                Ok(None)
+            }
+        }
+    }
+
+    /// Make the inputs received by the process data cycle available to
+    /// [`snapshot`](SubDeviceGroup::snapshot).
+    pub(super) fn publish_inputs(&self) {
+        let inner = self.inner();
+
+        match inner.buffering {
+            PdiBuffering::Single => (),
+            // The lock returned by `latch_outputs` is still held
+            PdiBuffering::Double => inner.inputs_fresh.store(true, Ordering::Relaxed),
+            PdiBuffering::Triple => {
+                let (Some(shared), Some(_lock)) = (
+                    self.shared_image(),
+                    ImageLock::try_lock(&inner.image_locked),
+                ) else {
+                    return;
+                };
+
+                let inputs = self.inputs_range();
+
+                shared[inputs.clone()].copy_from_slice(&self.wire_image()[inputs]);
+
+                inner.inputs_fresh.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// The image exchanged between the application and the process data cycle, or `None` if the
+    /// PDI is not buffered.
+    #[allow(clippy::mut_from_ref)]
+    fn shared_image(&self) -> Option<&mut [u8]> {
+        match self.inner().buffering {
+            PdiBuffering::Single => None,
+            PdiBuffering::Double => Some(self.wire_image()),
+            PdiBuffering::Triple => Some(self.image(1)),
+        }
+    }
+
+    fn inputs_range(&self) -> Range<usize> {
+        0..self.read_pdi_len
+    }
+
+    fn outputs_range(&self) -> Range<usize> {
+        self.read_pdi_len..self.pdi_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pdi::PdiOffset,
+        subdevice_group::{GroupId, GroupInner, NoDc, Op},
+    };
+    use core::{cell::UnsafeCell, marker::PhantomData};
+
+    fn group(buffering: PdiBuffering) -> SubDeviceGroup<1, 12, Op> {
+        SubDeviceGroup {
+            id: GroupId(0),
+            pdi: UnsafeCell::new([0u8; 12]),
+            read_pdi_len: 2,
+            pdi_len: 4,
+            inner: UnsafeCell::new(GroupInner {
+                pdi_start: PdiOffset::default(),
+                buffering,
+                ..GroupInner::default()
+            }),
+            dc_conf: NoDc,
+            _state: PhantomData,
+        }
+    }
+
+    /// Simulate a process data cycle receiving `inputs`, returning the outputs that were sent.
+    fn cycle(group: &SubDeviceGroup<1, 12, Op>, inputs: [u8; 2]) -> [u8; 2] {
+        let lock = group.latch_outputs().expect("latch");
+
+        let sent = group.wire_image()[2..4].try_into().unwrap();
+
+        group.wire_image()[0..2].copy_from_slice(&inputs);
+
+        group.publish_inputs();
+
+        drop(lock);
+
+        sent
+    }
+
+    #[test]
+    fn triple_buffered() {
+        let group = group(PdiBuffering::Triple);
+
+        group.pdi_mut()[2..4].copy_from_slice(&[0xaa, 0xbb]);
+
+        // Outputs aren't sent until they're committed
+        assert_eq!(cycle(&group, [1, 2]), [0, 0]);
+        assert!(group.commit_outputs());
+        assert_eq!(cycle(&group, [3, 4]), [0xaa, 0xbb]);
+
+        // Inputs don't change until a snapshot is taken
+        assert_eq!(group.pdi()[0..2], [0, 0]);
+        assert!(group.snapshot());
+        assert_eq!(group.pdi()[0..2], [3, 4]);
+        assert!(!group.snapshot());
+    }
+
+    #[test]
+    fn double_buffered() {
+        let group = group(PdiBuffering::Double);
+
+        group.pdi_mut()[2..4].copy_from_slice(&[0xaa, 0xbb]);
+
+        let lock = group.latch_outputs().expect("latch");
+
+        // Image is in use by the cycle
+        assert!(!group.commit_outputs());
+        assert!(!group.snapshot());
+        assert!(matches!(group.latch_outputs(), Err(Error::Borrow)));
+
+        drop(lock);
+
+        assert!(group.commit_outputs());
+        assert_eq!(cycle(&group, [1, 2]), [0xaa, 0xbb]);
+
+        assert!(group.snapshot());
+        assert_eq!(group.pdi()[0..2], [1, 2]);
+    }
+}
@@ -47,11 +47,13 @@ where
             return None;
         }
 
-        let subdevice = fmt::unwrap!(self.group.subdevice(self.maindevice, self.idx).map_err(|e| {
+        let subdevice = self.group.subdevice(self.maindevice, self.idx).map_err(|e| {
             fmt::error!("Failed to get SubDevice at index {} from group with {} SubDevices: {}. This is very wrong. Please open an issue.", self.idx, self.group.len(), e);
 
             e
-        }));
+        });
+
+        let subdevice = fmt::unwrap!(subdevice);
 
         self.idx += 1;
 
@@ -73,11 +75,13 @@ where
             return None;
         }
 
-        let subdevice = fmt::unwrap!(self.group.subdevice(self.maindevice, self.idx).map_err(|e| {
+        let subdevice = self.group.subdevice(self.maindevice, self.idx).map_err(|e| {
             fmt::error!("Failed to get SubDevice at index {} from group with {} SubDevices: {}. This is very wrong. Please open an issue.", self.idx, self.group.len(), e);
 
             e
-        }));
+        });
+
+        let subdevice = fmt::unwrap!(subdevice);
 
         self.idx += 1;
 
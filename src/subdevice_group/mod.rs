@@ -3,31 +3,47 @@
 //! SubDevices can be divided into multiple groups to allow multiple tasks to run concurrently,
 //! potentially at different tick rates.
 
+mod buffering;
 mod configurator;
 mod group_id;
 mod handle;
 mod iterator;
+mod process_variable;
 
 use crate::{
+    al_control::AlControl,
     command::Command,
     error::{DistributedClockError, Error, Item, PduError},
     fmt,
+    mailbox_scheduler::MailboxDelivery,
     pdi::PdiOffset,
+    pdu_loop::{CreatedFrame, PduResponseHandle, ReceiveFrameFut},
     subdevice::{
         configuration::PdoDirection, pdi::SubDevicePdi, IoRanges, SubDevice, SubDeviceRef,
     },
+    sync_manager_channel::Status,
     timer_factory::IntoTimeout,
     DcSync, MainDevice, RegisterAddress, SubDeviceState,
 };
-use atomic_refcell::{AtomicRefCell, AtomicRefMut};
+use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
 use core::{
-    cell::UnsafeCell, marker::PhantomData, slice, sync::atomic::AtomicUsize, time::Duration,
+    cell::UnsafeCell,
+    future::{poll_fn, Future},
+    marker::PhantomData,
+    ops::Range,
+    pin::Pin,
+    slice,
+    sync::atomic::{AtomicBool, AtomicU8, AtomicUsize},
+    task::Poll,
+    time::Duration,
 };
-use ethercrab_wire::EtherCrabWireRead;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
 
+pub use self::buffering::PdiBuffering;
 pub use self::group_id::GroupId;
 pub use self::handle::SubDeviceGroupHandle;
 pub use self::iterator::GroupSubDeviceIterator;
+pub use self::process_variable::ProcessVariable;
 pub use configurator::SubDeviceGroupRef;
 
 static GROUP_ID: AtomicUsize = AtomicUsize::new(0);
@@ -86,10 +102,48 @@ pub trait IsPreOp {}
 impl IsPreOp for PreOp {}
 impl IsPreOp for PreOpPdi {}
 
+/// The maximum number of frames a group's PDI is sent in before waiting for responses.
+const MAX_PDI_FRAMES: usize = 8;
+
+/// A chunk of the group PDI sent in its own frame, waiting for a response.
+struct PdiFrame<'sto> {
+    frame: ReceiveFrameFut<'sto>,
+    /// The `FRMW` distributing DC system time, if sent in this frame.
+    dc_handle: Option<PduResponseHandle>,
+    /// Each PDU sent in this frame with the part of the group PDI it contains.
+    pdus: heapless::Vec<(PduResponseHandle, Range<usize>), 2>,
+    mailbox: Option<MailboxDelivery<'sto>>,
+}
+
+/// The result of sending the group PDI.
+pub(crate) struct PdiResponse {
+    /// EtherCAT system time in nanoseconds, if an `FRMW` was sent.
+    pub(crate) time: Option<u64>,
+    /// Sum of the working counters of every PDU containing part of the PDI.
+    pub(crate) wkc: u16,
+    /// The working counter expected if every SubDevice is in OP.
+    pub(crate) expected_wkc: u16,
+}
+
 #[derive(Default)]
 struct GroupInner<const MAX_SUBDEVICES: usize> {
     subdevices: heapless::Vec<AtomicRefCell<SubDevice>, MAX_SUBDEVICES>,
     pdi_start: PdiOffset,
+    /// Map SubDevice read mailbox status into the PDI.
+    map_mailbox_status: bool,
+    /// Pack SubDevices with less than 8 bits of inputs or outputs into shared PDI bytes.
+    bit_packed: bool,
+    /// Working counter of the whole PDI when every SubDevice in the group is in OP.
+    expected_wkc: u16,
+    /// A SubDevice in the group does not support LRW, so the PDI is sent in separate `LRD` and
+    /// `LWR` PDUs.
+    lrw_unsupported: bool,
+    /// How many copies of the PDI are kept for the application and the process data cycle.
+    buffering: PdiBuffering,
+    /// The image shared by the application and the process data cycle is being copied to or from.
+    image_locked: AtomicBool,
+    /// The shared image contains inputs that haven't been copied into the application's image yet.
+    inputs_fresh: AtomicBool,
 }
 
 const CYCLIC_OP_ENABLE: u8 = 0b0000_0001;
@@ -131,6 +185,55 @@ pub struct CycleInfo {
     pub cycle_start_offset: Duration,
 }
 
+/// An error returned by [`SubDeviceGroup::tx_rx_checked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxRxError<const MAX_SUBDEVICES: usize> {
+    /// The error that occurred.
+    pub error: Error,
+
+    /// Indices in the group of the SubDevices that did not respond or are not in OP, if `error` is
+    /// an [`Error::WorkingCounter`].
+    ///
+    /// Each SubDevice's AL status is read to find these after the working counter check fails. Use
+    /// [`SubDeviceGroup::subdevice`] to get more information about them.
+    pub subdevices: heapless::Vec<usize, MAX_SUBDEVICES>,
+}
+
+impl<const MAX_SUBDEVICES: usize> From<Error> for TxRxError<MAX_SUBDEVICES> {
+    fn from(error: Error) -> Self {
+        Self {
+            error,
+            subdevices: heapless::Vec::new(),
+        }
+    }
+}
+
+impl<const MAX_SUBDEVICES: usize> From<TxRxError<MAX_SUBDEVICES>> for Error {
+    fn from(value: TxRxError<MAX_SUBDEVICES>) -> Self {
+        value.error
+    }
+}
+
+impl<const MAX_SUBDEVICES: usize> core::fmt::Display for TxRxError<MAX_SUBDEVICES> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        if !self.subdevices.is_empty() {
+            write!(
+                f,
+                ", SubDevices not responding: {:?}",
+                self.subdevices.as_slice()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const MAX_SUBDEVICES: usize> std::error::Error for TxRxError<MAX_SUBDEVICES> {}
+
 /// A group of one or more EtherCAT SubDevices.
 ///
 /// Groups are created during EtherCrab initialisation, and are the only way to access individual
@@ -171,10 +274,24 @@ impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, DC>
                     pdi_position,
                     inner.pdi_start.start_address,
                     PdoDirection::MasterRead,
+                    inner.bit_packed,
                 )
                 .await?;
         }
 
+        // Bit-packed inputs may end part way through a byte
+        pdi_position = pdi_position.align();
+
+        // Mailbox statuses are read by the MainDevice, so are mapped at the end of the inputs
+        if inner.map_mailbox_status {
+            for subdevice in inner.subdevices.iter_mut().map(AtomicRefCell::get_mut) {
+                pdi_position =
+                    SubDeviceRef::new(maindevice, subdevice.configured_address(), subdevice)
+                        .configure_mailbox_status_fmmu(pdi_position, inner.pdi_start.start_address)
+                        .await?;
+            }
+        }
+
         self.read_pdi_len = (pdi_position.start_address - inner.pdi_start.start_address) as usize;
 
         fmt::debug!("SubDevice mailboxes configured and init hooks called");
@@ -193,10 +310,13 @@ impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, DC>
                     pdi_position,
                     inner.pdi_start.start_address,
                     PdoDirection::MasterWrite,
+                    inner.bit_packed,
                 )
                 .await?;
         }
 
+        pdi_position = pdi_position.align();
+
         fmt::debug!("SubDevice FMMUs configured for group. Able to move to SAFE-OP");
 
         self.pdi_len = (pdi_position.start_address - inner.pdi_start.start_address) as usize;
@@ -208,13 +328,44 @@ impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, DC>
             self.read_pdi_len
         );
 
-        if self.pdi_len > MAX_PDI {
+        // Every copy of a buffered PDI is stored in the same array
+        let desired_length = self.pdi_len * inner.buffering.images();
+
+        if desired_length > MAX_PDI {
             return Err(Error::PdiTooLong {
                 max_length: MAX_PDI,
-                desired_length: self.pdi_len,
+                desired_length,
             });
         }
 
+        inner.lrw_unsupported = inner
+            .subdevices
+            .iter_mut()
+            .map(AtomicRefCell::get_mut)
+            .any(|subdevice| subdevice.flags.lrw_not_supported);
+
+        if inner.lrw_unsupported {
+            fmt::warn!(
+                "Group contains SubDevices without LRW support. Falling back to LRD and LWR"
+            );
+        }
+
+        let pdi = 0..self.pdi_len;
+
+        inner.expected_wkc = inner
+            .subdevices
+            .iter_mut()
+            .map(|subdevice| {
+                subdevice
+                    .get_mut()
+                    .config
+                    .io
+                    .working_counter(&pdi, !inner.lrw_unsupported)
+            })
+            .fold(0u16, u16::wrapping_add);
+
+        fmt::debug!("Group expected working counter {}", inner.expected_wkc);
+
         Ok(())
     }
 
@@ -257,6 +408,64 @@ impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, DC>
         ))
     }
 
+    /// Detect mailbox responses from cyclic process data instead of polling each SubDevice.
+    ///
+    /// When enabled, the read mailbox sync manager status of every SubDevice with a mailbox is
+    /// mapped into the group's inputs using a spare FMMU when the PDI is configured. Each call to
+    /// [`tx_rx`](SubDeviceGroup::tx_rx) or similar then updates whether each SubDevice has a
+    /// mailbox message waiting, so mailbox requests like
+    /// [`sdo_read`](SubDeviceRef::sdo_read) wait for their response without sending any extra
+    /// PDUs. Unsolicited messages like CoE emergencies are also detected, and can be checked for
+    /// with [`SubDeviceRef::mailbox_pending`].
+    ///
+    /// Each mapped SubDevice adds one byte to the group PDI, and increases the working counter
+    /// returned by `tx_rx` by one. SubDevices without a spare FMMU are polled as normal. If the
+    /// group's process data isn't being exchanged, e.g. before the process data loop is started,
+    /// mailbox requests also fall back to polling. A request made just after process data stops
+    /// being exchanged may take up to
+    /// [`Timeouts::mailbox_response`](crate::Timeouts::mailbox_response) to fall back.
+    ///
+    /// This is disabled by default.
+    pub fn set_mailbox_status_mapping(&mut self, enabled: bool) {
+        self.inner.get_mut().map_mailbox_status = enabled;
+    }
+
+    /// Pack the process data of bit-oriented SubDevices into shared bytes of the group PDI.
+    ///
+    /// When enabled, SubDevices with less than 8 bits of inputs or outputs, like 4 channel digital
+    /// IO terminals, are mapped with bit-granular FMMUs into the free bits of the previous
+    /// SubDevice's byte instead of each taking a whole byte. Packed data never spans two bytes,
+    /// and SubDevices with 8 or more bits still start on a byte boundary.
+    ///
+    /// As packed SubDevices share PDI bytes, their raw outputs from
+    /// [`outputs_raw_mut`](SubDeviceRef::outputs_raw_mut) and similar methods are empty. Use
+    /// [`output_bit`](SubDeviceRef::output_bit) and
+    /// [`set_output_bit`](SubDeviceRef::set_output_bit) instead. Raw inputs contain the whole
+    /// shared byte, so use [`input_bit`](SubDeviceRef::input_bit) to read them.
+    ///
+    /// This is disabled by default.
+    pub fn set_bit_packed_mapping(&mut self, enabled: bool) {
+        self.inner.get_mut().bit_packed = enabled;
+    }
+
+    /// Give the application its own copy of the group PDI, separate from the one sent by
+    /// [`tx_rx`](SubDeviceGroup::tx_rx).
+    ///
+    /// With [`PdiBuffering::Double`] or [`PdiBuffering::Triple`], received inputs are only copied
+    /// into the application's image by [`snapshot`](SubDeviceGroup::snapshot), and outputs
+    /// written by the application are only sent after
+    /// [`commit_outputs`](SubDeviceGroup::commit_outputs). This allows a slower task to work with
+    /// a consistent set of inputs and outputs while another task calls `tx_rx` every cycle. See
+    /// [`PdiBuffering`] for the differences between the two modes.
+    ///
+    /// Every copy of the PDI is stored in the group, so the `MAX_PDI` const parameter must be
+    /// at least two or three times the length of the PDI.
+    ///
+    /// This is [`PdiBuffering::Single`] by default.
+    pub fn set_pdi_buffering(&mut self, buffering: PdiBuffering) {
+        self.inner.get_mut().buffering = buffering;
+    }
+
     /// Transition the group from PRE-OP -> SAFE-OP -> OP.
     ///
     /// To transition individually from PRE-OP to SAFE-OP, then SAFE-OP to OP, see
@@ -572,6 +781,61 @@ impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, DC>
     pub async fn all_op(&self, maindevice: &MainDevice<'_>) -> Result<bool, Error> {
         self.is_state(maindevice, SubDeviceState::Op).await
     }
+
+    /// The same as [`tx_rx`](SubDeviceGroup::tx_rx), but checks the working counter against the
+    /// [expected value](SubDeviceGroup::expected_working_counter).
+    ///
+    /// # Errors
+    ///
+    /// As well as the errors returned by `tx_rx`, this method will return an
+    /// [`Error::WorkingCounter`] if the working counter does not match. The AL status of every
+    /// SubDevice in the group is then read to find the ones that did not respond or are no longer
+    /// in OP, which are returned in [`TxRxError::subdevices`].
+    ///
+    /// Received inputs are still copied into the PDI if the working counter does not match.
+    pub async fn tx_rx_checked<'sto>(
+        &self,
+        maindevice: &'sto MainDevice<'sto>,
+    ) -> Result<u16, TxRxError<MAX_SUBDEVICES>> {
+        let PdiResponse {
+            wkc, expected_wkc, ..
+        } = self.exchange_pdi(maindevice, None).await?;
+
+        if wkc == expected_wkc {
+            return Ok(wkc);
+        }
+
+        fmt::warn!(
+            "Group working counter expected {}, got {}",
+            expected_wkc,
+            wkc
+        );
+
+        let mut subdevices = heapless::Vec::new();
+
+        for (index, subdevice) in self.subdevices_shared().enumerate() {
+            let op = Command::fprd(
+                subdevice.configured_address(),
+                RegisterAddress::AlStatus.into(),
+            )
+            .receive::<AlControl>(maindevice)
+            .await
+            .is_ok_and(|status| status.state == SubDeviceState::Op && !status.error);
+
+            if !op {
+                // Can't overflow; there is one entry at most per SubDevice
+                let _ = subdevices.push(index);
+            }
+        }
+
+        Err(TxRxError {
+            error: Error::WorkingCounter {
+                expected: expected_wkc,
+                received: wkc,
+            },
+            subdevices,
+        })
+    }
 }
 
 unsafe impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, S> Sync
@@ -619,6 +883,45 @@ impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, S, DC>
         self.inner().subdevices.is_empty()
     }
 
+    /// Borrow an individual SubDevice for mailbox communication, without access to its process
+    /// data.
+    ///
+    /// Unlike [`subdevice`](SubDeviceGroup::subdevice), this takes a shared borrow of the
+    /// SubDevice, so it can be held across mailbox requests while the group's state is checked.
+    /// Borrowing the same SubDevice with [`subdevice`](SubDeviceGroup::subdevice) returns
+    /// [`Error::Borrow`] until the returned reference is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the given index is out of range of the current group, or
+    /// if the SubDevice at the given index is mutably borrowed.
+    pub fn mailbox_subdevice<'maindevice, 'group>(
+        &'group self,
+        maindevice: &'maindevice MainDevice<'maindevice>,
+        index: usize,
+    ) -> Result<SubDeviceRef<'maindevice, AtomicRef<'group, SubDevice>>, Error> {
+        let subdevice = self
+            .inner()
+            .subdevices
+            .get(index)
+            .ok_or(Error::NotFound {
+                item: Item::SubDevice,
+                index: Some(index),
+            })?
+            .try_borrow()
+            .map_err(|_e| {
+                fmt::error!("SubDevice index {} already mutably borrowed", index);
+
+                Error::Borrow
+            })?;
+
+        Ok(SubDeviceRef::new(
+            maindevice,
+            subdevice.configured_address(),
+            subdevice,
+        ))
+    }
+
     #[allow(clippy::mut_from_ref)]
     fn pdi_mut(&self) -> &mut [u8] {
         let all_buf = unsafe { &mut *self.pdi.get() };
@@ -632,6 +935,21 @@ impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, S, DC>
         &all_buf[0..self.pdi_len]
     }
 
+    /// One copy of a buffered PDI. Image `0` is the application's, returned by
+    /// [`pdi`](SubDeviceGroup::pdi).
+    #[allow(clippy::mut_from_ref)]
+    fn image(&self, index: usize) -> &mut [u8] {
+        let all_buf = unsafe { &mut *self.pdi.get() };
+
+        &mut all_buf[(index * self.pdi_len)..((index + 1) * self.pdi_len)]
+    }
+
+    /// The image sent and received by the process data cycle.
+    #[allow(clippy::mut_from_ref)]
+    fn wire_image(&self) -> &mut [u8] {
+        self.image(self.inner().buffering.images() - 1)
+    }
+
     /// Check if all SubDevices in the group are the given desired state.
     async fn is_state(
         &self,
@@ -739,6 +1057,13 @@ where
         maindevice: &'maindevice MainDevice<'maindevice>,
         index: usize,
     ) -> Result<SubDeviceRef<'maindevice, SubDevicePdi<'group>>, Error> {
+        let pdi = self.subdevice_pdi(index)?;
+
+        Ok(SubDeviceRef::new(maindevice, pdi.configured_address(), pdi))
+    }
+
+    /// Borrow a SubDevice along with its inputs and outputs in the group PDI.
+    fn subdevice_pdi(&self, index: usize) -> Result<SubDevicePdi<'_>, Error> {
         let subdevice = self
             .inner()
             .subdevices
@@ -757,6 +1082,7 @@ where
         let IoRanges {
             input: input_range,
             output: output_range,
+            ..
         } = subdevice.io_segments();
 
         // SAFETY: Multiple references are ok as long as I and O ranges do not overlap.
@@ -787,22 +1113,43 @@ where
                 .ok_or(Error::Internal)?
         };
 
-        let outputs = if output_range.is_empty() {
+        let (outputs, shared_outputs): (_, &[AtomicU8]) = if output_range.is_empty() {
             // SAFETY: Slice is empty so can never be mutated
-            unsafe { slice::from_raw_parts_mut(EMPTY_PDI_SLICE.as_ptr().cast_mut(), 0) }
+            let outputs =
+                unsafe { slice::from_raw_parts_mut(EMPTY_PDI_SLICE.as_ptr().cast_mut(), 0) };
+
+            (outputs, &[])
+        } else if output_range.bit_packed {
+            let shared = o_data
+                .get(output_range.bytes.clone())
+                .ok_or(Error::Internal)?;
+
+            // SAFETY: Slice is empty so can never be mutated
+            let outputs =
+                unsafe { slice::from_raw_parts_mut(EMPTY_PDI_SLICE.as_ptr().cast_mut(), 0) };
+
+            // SAFETY: `AtomicU8` has the same size and alignment as `u8`. Bit-packed outputs share
+            // bytes with other SubDevices, so are only accessed atomically through `SubDevicePdi`.
+            let shared =
+                unsafe { slice::from_raw_parts(shared.as_ptr().cast::<AtomicU8>(), shared.len()) };
+
+            (outputs, shared)
         } else {
-            o_data
+            let outputs = o_data
                 .get_mut(output_range.bytes.clone())
-                .ok_or(Error::Internal)?
+                .ok_or(Error::Internal)?;
+
+            (outputs, &[])
         };
 
-        Ok(SubDeviceRef::new(
-            maindevice,
-            subdevice.configured_address(),
-            // SAFETY: A given SubDevice contained in a `SubDevicePdi` MUST only be borrowed once
-            // (currently enforced by `AtomicRefCell`). If it is borrowed more than once, immutable
-            // APIs in `SubDeviceRef<SubDevicePdi>` will be unsound.
-            SubDevicePdi::new(subdevice, inputs, outputs),
+        // SAFETY: A given SubDevice contained in a `SubDevicePdi` MUST only be borrowed once
+        // (currently enforced by `AtomicRefCell`). If it is borrowed more than once, immutable
+        // APIs in `SubDeviceRef<SubDevicePdi>` will be unsound.
+        Ok(SubDevicePdi::new(
+            subdevice,
+            inputs,
+            outputs,
+            shared_outputs,
         ))
     }
 
@@ -814,24 +1161,51 @@ where
         GroupSubDeviceIterator::new(maindevice, self)
     }
 
+    /// The working counter expected from [`tx_rx`](SubDeviceGroup::tx_rx) when every SubDevice in
+    /// the group is in OP.
+    ///
+    /// This is calculated when the PDI is configured. Each SubDevice adds 1 if it has inputs or a
+    /// [mapped mailbox status](SubDeviceGroup::set_mailbox_status_mapping), and 2 if it has
+    /// outputs. If the group is sent in separate `LRD` and `LWR` PDUs because a SubDevice does not
+    /// support LRW, outputs add 1 instead.
+    ///
+    /// If the PDI is too long to fit in one frame, a SubDevice whose IO is split between two frames
+    /// is counted in both, so `tx_rx` may return a larger value. The working counter checked by
+    /// [`tx_rx_checked`](SubDeviceGroup::tx_rx_checked) accounts for this.
+    pub fn expected_working_counter(&self) -> u16 {
+        self.inner().expected_wkc
+    }
+
     /// Drive the SubDevice group's inputs and outputs.
     ///
     /// A `SubDeviceGroup` will not process any inputs or outputs unless this method is called
     /// periodically. It will send an `LRW` to update SubDevice outputs and read SubDevice inputs.
     ///
-    /// This method returns the working counter on success.
+    /// If the group PDI is too long to fit in one frame, it is split into multiple `LRW`s which are
+    /// sent in separate frames at the same time.
+    ///
+    /// If any SubDevice in the group reports that it does not support LRW, inputs are read with an
+    /// `LRD` and outputs are written with an `LWR` in the same frame instead.
+    ///
+    /// This method returns the working counter on success. If more than one `LRW` is sent, this is
+    /// the sum of all of their working counters.
     ///
     /// # Errors
     ///
     /// This method will return with an error if the PDU could not be sent over the network, or the
     /// response times out.
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if the frame data length of the group is too large to fit in the
-    /// configured maximum PDU length set by the `DATA` const generic of
-    /// [`PduStorage`](crate::PduStorage).
     pub async fn tx_rx<'sto>(&self, maindevice: &'sto MainDevice<'sto>) -> Result<u16, Error> {
+        self.tx_rx_response(maindevice)
+            .await
+            .map(|response| response.wkc)
+    }
+
+    /// The same as [`tx_rx`](SubDeviceGroup::tx_rx), but also returns the working counter expected
+    /// for the PDUs that were sent.
+    pub(crate) async fn tx_rx_response<'sto>(
+        &self,
+        maindevice: &'sto MainDevice<'sto>,
+    ) -> Result<PdiResponse, Error> {
         fmt::trace!(
             "Group TX/RX, start address {:#010x}, data len {}, of which read bytes: {}",
             self.inner().pdi_start.start_address,
@@ -839,19 +1213,7 @@ where
             self.read_pdi_len
         );
 
-        assert!(
-            self.len() <= maindevice.max_frame_data(),
-            "Chunked sends not yet supported. Buffer len {} B too long to send in {} B frame",
-            self.len(),
-            maindevice.max_frame_data()
-        );
-
-        let data = Command::lrw(self.inner().pdi_start.start_address)
-            .ignore_wkc()
-            .send_receive_slice(maindevice, self.pdi())
-            .await?;
-
-        self.process_pdi_response(&data)
+        self.exchange_pdi(maindevice, None).await
     }
 
     /// Drive the SubDevice group's inputs and outputs and synchronise EtherCAT system time with
@@ -867,23 +1229,10 @@ where
     ///
     /// This method will return with an error if the PDU could not be sent over the network, or the
     /// response times out.
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if the frame data length of the group is too large to fit in the
-    /// configured maximum PDU length set by the `DATA` const generic of
-    /// [`PduStorage`](crate::PduStorage).
     pub async fn tx_rx_sync_system_time<'sto>(
         &self,
         maindevice: &'sto MainDevice<'sto>,
     ) -> Result<(u16, Option<u64>), Error> {
-        assert!(
-            self.len() <= maindevice.max_frame_data(),
-            "Chunked sends not yet supported. Buffer len {} B too long to send in {} B frame",
-            self.len(),
-            maindevice.max_frame_data()
-        );
-
         fmt::trace!(
             "Group TX/RX with DC sync, start address {:#010x}, data len {}, of which read bytes: {}",
             self.inner().pdi_start.start_address,
@@ -892,21 +1241,102 @@ where
         );
 
         if let Some(dc_ref) = maindevice.dc_ref_address() {
-            let mut frame = maindevice.pdu_loop.alloc_frame()?;
+            let response = self.exchange_pdi(maindevice, Some(dc_ref)).await?;
 
-            let dc_handle = frame.push_pdu(
-                Command::frmw(dc_ref, RegisterAddress::DcSystemTime.into()).into(),
-                0u64,
-                None,
-                true,
-            )?;
+            Ok((response.wkc, response.time))
+        } else {
+            self.tx_rx(maindevice).await.map(|wkc| (wkc, None))
+        }
+    }
 
-            let pdu_handle = frame.push_pdu(
-                Command::lrw(self.inner().pdi_start.start_address).into(),
-                self.pdi(),
-                None,
-                false,
-            )?;
+    /// Send the group PDI in one or more `LRW`s, preceded by an `FRMW` to distribute the system
+    /// time of the DC reference SubDevice at `dc_ref`, if given.
+    ///
+    /// If the PDI doesn't fit in a single frame, it is split into chunks which are each sent in
+    /// their own frame at the matching logical address. Each chunk is sent in an `LRW`, or an `LRD`
+    /// and `LWR` if LRW is not supported by the group. Up to [`MAX_PDI_FRAMES`] frames are sent
+    /// at once before waiting for their responses.
+    ///
+    /// A queued mailbox PDU may be sent in the last frame if
+    /// [`MailboxScheduling::Cyclic`](crate::MailboxScheduling::Cyclic) is configured.
+    ///
+    /// Returns the current EtherCAT system time in nanoseconds if `dc_ref` is given, the sum of the
+    /// working counters of every PDI PDU, and the sum expected for the chunks that were sent.
+    async fn exchange_pdi<'sto>(
+        &self,
+        maindevice: &'sto MainDevice<'sto>,
+        dc_ref: Option<u16>,
+    ) -> Result<PdiResponse, Error> {
+        let pdi_len = self.pdi().len();
+        let start_address = self.inner().pdi_start.start_address;
+
+        // Held until the whole PDI is received if it is double buffered
+        let _image_lock = self.latch_outputs()?;
+
+        let mut time = None;
+        let mut wkc = 0u16;
+        let mut expected_wkc = 0u16;
+        let mut offset = 0;
+        let mut in_flight = heapless::Vec::<Option<PdiFrame<'sto>>, MAX_PDI_FRAMES>::new();
+
+        loop {
+            // Only the first frame carries the `FRMW`.
+            let dc_ref = dc_ref.filter(|_| offset == 0);
+
+            let dc_len = dc_ref.map_or(0, |_| CreatedFrame::pdu_len(u64::PACKED_LEN));
+
+            // A queued mailbox PDU is only picked up once the rest of the PDI fits alongside it.
+            let (mut frame, mailbox) =
+                maindevice.alloc_cyclic_frame(dc_len + self.pdi_pdus_len(offset..pdi_len))?;
+
+            let dc_handle = dc_ref
+                .map(|dc_ref| {
+                    frame.push_pdu(
+                        Command::frmw(dc_ref, RegisterAddress::DcSystemTime.into()).into(),
+                        0u64,
+                        None,
+                        true,
+                    )
+                })
+                .transpose()?;
+
+            let mut range = offset
+                ..pdi_len.min(offset + frame.remaining().saturating_sub(CreatedFrame::pdu_len(0)));
+
+            // Separate LRD and LWR PDUs need room for another PDU header
+            if self.pdi_pdus_len(range.clone()) > frame.remaining() {
+                range.end = range
+                    .end
+                    .saturating_sub(CreatedFrame::pdu_len(0))
+                    .max(range.start);
+            }
+
+            expected_wkc = expected_wkc.wrapping_add(self.expected_wkc(&range));
+
+            let mut pdus = heapless::Vec::new();
+
+            for range in self.pdi_pdu_ranges(range.clone()) {
+                let data = self
+                    .wire_image()
+                    .get(range.clone())
+                    .ok_or(Error::Internal)?;
+
+                let address = start_address + range.start as u32;
+
+                let command = if !self.inner().lrw_unsupported {
+                    Command::lrw(address).into()
+                } else if range.start < self.read_pdi_len {
+                    Command::lrd(address).into()
+                } else {
+                    Command::lwr(address).into()
+                };
+
+                let handle = frame.push_pdu(command, data, None, false)?;
+
+                pdus.push((handle, range)).map_err(|_| Error::Internal)?;
+            }
+
+            offset = range.end;
 
             let frame = frame.mark_sendable(
                 &maindevice.pdu_loop,
@@ -916,51 +1346,231 @@ where
 
             maindevice.pdu_loop.wake_sender();
 
-            let received = frame.await?;
+            in_flight
+                .push(Some(PdiFrame {
+                    frame,
+                    dc_handle,
+                    pdus,
+                    mailbox,
+                }))
+                .map_err(|_| Error::Internal)?;
 
-            let (time, wkc) = self.process_pdi_response_with_time(
-                &received.pdu(dc_handle)?,
-                &received.pdu(pdu_handle)?,
-            )?;
+            let done = offset >= pdi_len;
+
+            if in_flight.is_full() || done {
+                let (batch_time, batch_wkc) = self.receive_pdi_frames(&mut in_flight).await?;
+
+                time = time.or(batch_time);
+                wkc = wkc.wrapping_add(batch_wkc);
+            }
+
+            if done {
+                break;
+            }
+        }
+
+        if self.inner().map_mailbox_status {
+            self.update_mailbox_status();
+        }
+
+        self.publish_inputs();
+
+        Ok(PdiResponse {
+            time,
+            wkc,
+            expected_wkc,
+        })
+    }
 
-            Ok((wkc, Some(time)))
+    /// Split a chunk of the group PDI into the ranges sent in each PDU.
+    ///
+    /// This is the whole chunk in one `LRW`, or if LRW is not supported by every SubDevice in the
+    /// group, an `LRD` of any inputs followed by an `LWR` of any outputs.
+    fn pdi_pdu_ranges(&self, range: Range<usize>) -> impl Iterator<Item = Range<usize>> {
+        let split = if !self.inner().lrw_unsupported {
+            range.end
         } else {
-            self.tx_rx(maindevice).await.map(|wkc| (wkc, None))
+            self.read_pdi_len.clamp(range.start, range.end)
+        };
+
+        let outputs = split..range.end;
+
+        // An empty PDI is still sent in one PDU
+        let inputs =
+            Some(range.start..split).filter(|inputs| !inputs.is_empty() || outputs.is_empty());
+
+        inputs
+            .into_iter()
+            .chain(Some(outputs).filter(|outputs| !outputs.is_empty()))
+    }
+
+    /// The number of bytes in a frame taken up by the PDUs for a chunk of the group PDI.
+    fn pdi_pdus_len(&self, range: Range<usize>) -> usize {
+        self.pdi_pdu_ranges(range)
+            .map(|range| CreatedFrame::pdu_len(range.len()))
+            .sum()
+    }
+
+    /// The working counter expected from sending the group PDI `range` when every SubDevice is in
+    /// OP.
+    ///
+    /// SubDevices whose IO is split across multiple chunks are counted in each of them.
+    fn expected_wkc(&self, range: &Range<usize>) -> u16 {
+        if *range == (0..self.pdi().len()) {
+            return self.inner().expected_wkc;
         }
+
+        let lrw = !self.inner().lrw_unsupported;
+
+        self.subdevices_shared()
+            .map(|subdevice| subdevice.config.io.working_counter(range, lrw))
+            .fold(0u16, u16::wrapping_add)
+    }
+
+    /// Shared references to every SubDevice in the group, regardless of whether they are borrowed.
+    fn subdevices_shared(&self) -> impl Iterator<Item = &SubDevice> {
+        self.inner().subdevices.iter().map(|subdevice| {
+            // SAFETY: SubDevices in a group with a PDI are only borrowed through `SubDevicePdi`,
+            // which only allows shared access, or by `iter` which needs exclusive access to the
+            // group. A shared reference is therefore always valid here. Only atomic fields are
+            // written through it.
+            unsafe { &*subdevice.as_ptr() }
+        })
     }
 
-    fn process_pdi_response_with_time(
+    /// Wait for the responses to every in-flight PDI frame and copy the received inputs into the
+    /// group's memory.
+    ///
+    /// All frames are polled at once so responses can be received in any order. Every frame is
+    /// waited for even if another one fails so that responses to any mailbox PDUs are still
+    /// delivered. The first error is returned.
+    async fn receive_pdi_frames<'sto>(
         &self,
-        dc: &crate::pdu_loop::ReceivedPdu,
-        data: &crate::pdu_loop::ReceivedPdu,
-    ) -> Result<(u64, u16), Error> {
-        let time = u64::unpack_from_slice(dc)?;
+        in_flight: &mut heapless::Vec<Option<PdiFrame<'sto>>, MAX_PDI_FRAMES>,
+    ) -> Result<(Option<u64>, u16), Error> {
+        let mut result = Ok((None, 0u16));
+
+        poll_fn(|cx| {
+            for slot in in_flight.iter_mut() {
+                let Some(pdi_frame) = slot else {
+                    continue;
+                };
+
+                let Poll::Ready(received) = Pin::new(&mut pdi_frame.frame).poll(cx) else {
+                    continue;
+                };
+
+                let Some(PdiFrame {
+                    dc_handle,
+                    pdus,
+                    mailbox,
+                    ..
+                }) = slot.take()
+                else {
+                    continue;
+                };
+
+                let received = match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        if let Some(mailbox) = mailbox {
+                            mailbox.deliver(Err(e));
+                        }
+
+                        result = result.and(Err(e));
+
+                        continue;
+                    }
+                };
+
+                let frame_result = dc_handle
+                    .map(|handle| Ok(u64::unpack_from_slice(&received.pdu(handle)?)?))
+                    .transpose()
+                    .and_then(|time| {
+                        let mut wkc = 0u16;
+
+                        for (handle, range) in pdus {
+                            wkc = wkc.wrapping_add(
+                                self.process_pdi_response(range, &received.pdu(handle)?)?,
+                            );
+                        }
+
+                        Ok((time, wkc))
+                    });
+
+                // The mailbox PDU's response is still valid even if processing the PDI failed.
+                if let Some(mailbox) = mailbox {
+                    mailbox.deliver(Ok(received));
+                }
+
+                result = result.and_then(|(time, wkc)| {
+                    frame_result.map(|(frame_time, frame_wkc)| {
+                        (time.or(frame_time), wkc.wrapping_add(frame_wkc))
+                    })
+                });
+            }
+
+            if in_flight.iter().all(Option::is_none) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        in_flight.clear();
 
-        Ok((time, self.process_pdi_response(data)?))
+        result
     }
 
-    /// Take a received PDI and copy its inputs into the group's memory.
+    /// Take a received chunk of the PDI at `range` and copy any inputs it contains into the
+    /// group's memory.
     ///
     /// Returns working counter on success.
-    fn process_pdi_response(&self, data: &crate::pdu_loop::ReceivedPdu) -> Result<u16, Error> {
-        if data.len() != self.pdi().len() {
+    fn process_pdi_response(
+        &self,
+        range: Range<usize>,
+        data: &crate::pdu_loop::ReceivedPdu,
+    ) -> Result<u16, Error> {
+        if data.len() != range.len() {
             fmt::error!(
                 "Data length {} does not match value length {}",
                 data.len(),
-                self.pdi().len()
+                range.len()
             );
 
             return Err(Error::Pdu(PduError::Decode));
         }
 
-        let wkc = data.working_counter;
+        let inputs = range.start..range.end.min(self.read_pdi_len);
+
+        if !inputs.is_empty() {
+            self.wire_image()
+                .get_mut(inputs.clone())
+                .ok_or(Error::Internal)?
+                .copy_from_slice(data.get(0..inputs.len()).ok_or(Error::Internal)?);
+        }
+
+        Ok(data.working_counter)
+    }
+
+    /// Store the mailbox status of every SubDevice whose read mailbox status is mapped into the
+    /// PDI.
+    fn update_mailbox_status(&self) {
+        let pdi = self.wire_image();
+
+        for subdevice in self.subdevices_shared() {
+            let Some(offset) = subdevice.config.io.mailbox_status else {
+                continue;
+            };
 
-        self.pdi_mut()
-            .get_mut(0..self.read_pdi_len)
-            .ok_or(Error::Internal)?
-            .copy_from_slice(data.get(0..self.read_pdi_len).ok_or(Error::Internal)?);
+            let full = pdi
+                .get(offset..(offset + 1))
+                .and_then(|status| Status::unpack_from_slice(status).ok())
+                .is_some_and(|status| status.mailbox_full);
 
-        Ok(wkc)
+            subdevice.mailbox_status.update(full);
+        }
     }
 }
 
@@ -984,12 +1594,6 @@ where
     /// This method will return with an error if the PDU could not be sent over the network, or the
     /// response times out.
     ///
-    /// # Panics
-    ///
-    /// This method will panic if the frame data length of the group is too large to fit in the
-    /// configured maximum PDU length set by the `DATA` const generic of
-    /// [`PduStorage`](crate::PduStorage).
-    ///
     /// # Examples
     ///
     /// This example sends process data at 2.5ms offset into a 5ms cycle.
@@ -1081,13 +1685,17 @@ where
         &self,
         maindevice: &'sto MainDevice<'sto>,
     ) -> Result<(u16, CycleInfo), Error> {
-        assert!(
-            self.len() <= maindevice.max_frame_data(),
-            "Chunked sends not yet supported. Buffer len {} B too long to send in {} B frame",
-            self.len(),
-            maindevice.max_frame_data()
-        );
+        self.tx_rx_dc_response(maindevice)
+            .await
+            .map(|(response, cycle_info)| (response.wkc, cycle_info))
+    }
 
+    /// The same as [`tx_rx_dc`](SubDeviceGroup::tx_rx_dc), but also returns the working counter
+    /// expected for the PDUs that were sent.
+    pub(crate) async fn tx_rx_dc_response<'sto>(
+        &self,
+        maindevice: &'sto MainDevice<'sto>,
+    ) -> Result<(PdiResponse, CycleInfo), Error> {
         fmt::trace!(
             "Group TX/RX with DC sync, start address {:#010x}, data len {}, of which read bytes: {}",
             self.inner().pdi_start.start_address,
@@ -1095,36 +1703,11 @@ where
             self.read_pdi_len
         );
 
-        let mut frame = maindevice.pdu_loop.alloc_frame()?;
-
-        let dc_handle = frame.push_pdu(
-            Command::frmw(self.dc_conf.reference, RegisterAddress::DcSystemTime.into()).into(),
-            0u64,
-            None,
-            true,
-        )?;
-
-        let pdu_handle = frame.push_pdu(
-            Command::lrw(self.inner().pdi_start.start_address).into(),
-            self.pdi(),
-            None,
-            false,
-        )?;
-
-        let frame = frame.mark_sendable(
-            &maindevice.pdu_loop,
-            maindevice.timeouts.pdu,
-            maindevice.config.retry_behaviour.retry_count(),
-        );
-
-        maindevice.pdu_loop.wake_sender();
-
-        let received = frame.await?;
+        let response = self
+            .exchange_pdi(maindevice, Some(self.dc_conf.reference))
+            .await?;
 
-        let (time, wkc) = self.process_pdi_response_with_time(
-            &received.pdu(dc_handle)?,
-            &received.pdu(pdu_handle)?,
-        )?;
+        let time = response.time.ok_or(Error::Internal)?;
 
         // Nanoseconds from the start of the cycle. This works because the first SYNC0 pulse
         // time is rounded to a whole number of `sync0_period`-length cycles.
@@ -1134,7 +1717,7 @@ where
             (self.dc_conf.sync0_period - cycle_start_offset) + self.dc_conf.sync0_shift;
 
         Ok((
-            wkc,
+            response,
             CycleInfo {
                 dc_system_time: time,
                 cycle_start_offset: Duration::from_nanos(cycle_start_offset),
@@ -1143,3 +1726,150 @@ where
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MainDeviceConfig, PduStorage, Timeouts};
+    use core::pin::pin;
+    use futures_lite::future::{block_on, or, yield_now};
+
+    const INPUTS: usize = 200;
+    const PDI_LEN: usize = 300;
+
+    // Command codes
+    const LRD: u8 = 0x0a;
+    const LWR: u8 = 0x0b;
+    const LRW: u8 = 0x0c;
+
+    /// Send a 300 byte PDI in 128 byte frames, returning the command codes of the PDUs in each
+    /// frame.
+    fn exchange_chunked(lrw_unsupported: bool) -> (Vec<Vec<u8>>, PdiResponse) {
+        let storage: PduStorage<16, { PduStorage::element_size(128) }> = PduStorage::new();
+        let (mut tx, mut rx, pdu_loop) = storage.try_split().unwrap();
+
+        let maindevice =
+            MainDevice::new(pdu_loop, Timeouts::default(), MainDeviceConfig::default());
+
+        let subdevice = |input: Range<usize>, output: Range<usize>| {
+            let mut subdevice = SubDevice::default();
+
+            subdevice.config.io.input.bytes = input;
+            subdevice.config.io.output.bytes = output;
+
+            AtomicRefCell::new(subdevice)
+        };
+
+        // The PDI is sent in 116 byte chunks. The first SubDevice's inputs and outputs are both
+        // split between two chunks.
+        let subdevices = [subdevice(0..150, 200..250), subdevice(150..200, 250..300)];
+
+        let group = SubDeviceGroup::<2, 512, Op> {
+            id: GroupId(0),
+            pdi: UnsafeCell::new([0xff; 512]),
+            read_pdi_len: INPUTS,
+            pdi_len: PDI_LEN,
+            inner: UnsafeCell::new(GroupInner {
+                pdi_start: PdiOffset {
+                    start_address: 0x1000,
+                    start_bit: 0,
+                },
+                subdevices: heapless::Vec::from_iter(subdevices),
+                expected_wkc: 6,
+                lrw_unsupported,
+                ..GroupInner::default()
+            }),
+            dc_conf: NoDc,
+            _state: PhantomData,
+        };
+
+        let mut frames = Vec::new();
+
+        // Respond to each PDU by filling inputs with the low byte of their logical address.
+        let network = async {
+            loop {
+                while let Some(frame) = tx.next_sendable_frame() {
+                    let mut response = Vec::new();
+
+                    frame
+                        .send_blocking(|bytes| {
+                            response = bytes.to_vec();
+
+                            Ok(bytes.len())
+                        })
+                        .unwrap();
+
+                    // Ethernet header, EtherCAT header
+                    let mut pdu = 14 + 2;
+                    let mut commands = Vec::new();
+
+                    loop {
+                        commands.push(response[pdu]);
+
+                        let address =
+                            u32::from_le_bytes(response[pdu + 2..pdu + 6].try_into().unwrap());
+                        let flags = u16::from_le_bytes([response[pdu + 6], response[pdu + 7]]);
+                        let len = usize::from(flags & 0x07ff);
+                        let data = pdu + 10;
+
+                        for (i, byte) in response[data..data + len].iter_mut().enumerate() {
+                            let logical = address as usize - 0x1000 + i;
+
+                            if logical < INPUTS {
+                                *byte = logical as u8;
+                            }
+                        }
+
+                        response[data + len] = 1;
+
+                        pdu = data + len + 2;
+
+                        if flags & 0x8000 == 0 {
+                            break;
+                        }
+                    }
+
+                    // Make the frame look like it came from a SubDevice
+                    response[6] = 0x12;
+
+                    rx.receive_frame(&response).unwrap();
+
+                    frames.push(commands);
+                }
+
+                yield_now().await;
+            }
+        };
+
+        let response = block_on(or(pin!(group.exchange_pdi(&maindevice, None)), network)).unwrap();
+
+        assert!(group.pdi()[0..INPUTS]
+            .iter()
+            .enumerate()
+            .all(|(i, byte)| *byte == i as u8));
+        assert!(group.pdi()[INPUTS..].iter().all(|byte| *byte == 0xff));
+
+        (frames, response)
+    }
+
+    #[test]
+    fn chunked_lrw() {
+        let (frames, response) = exchange_chunked(false);
+
+        assert_eq!(frames, [[LRW], [LRW], [LRW]]);
+        assert_eq!(response.wkc, 3);
+        // 1 + (3 + 1) + (2 + 2)
+        assert_eq!(response.expected_wkc, 9);
+    }
+
+    #[test]
+    fn chunked_lrd_lwr() {
+        let (frames, response) = exchange_chunked(true);
+
+        // The second frame is shortened to make room for the `LWR` header
+        assert_eq!(frames, [vec![LRD], vec![LRD, LWR], vec![LWR]]);
+        assert_eq!(response.wkc, 4);
+        // 1 + (1 + 1 + 1) + (1 + 1)
+        assert_eq!(response.expected_wkc, 6);
+    }
+}
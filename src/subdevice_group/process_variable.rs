@@ -0,0 +1,399 @@
+use super::{HasPdi, SubDeviceGroup};
+use crate::{
+    base_data_types::PrimitiveDataType,
+    error::{Error, Item},
+    fmt, MainDevice, SubDevice, SubDeviceRef,
+};
+use ethercrab_wire::{
+    EtherCrabWireReadSized, EtherCrabWireSized, EtherCrabWireWriteSized, WireError,
+};
+
+/// A named PDO entry in a group's Process Data Image (PDI), found with
+/// [`SubDeviceGroup::symbol`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ProcessVariable {
+    subdevice: usize,
+    input: bool,
+    bit_offset: usize,
+    bit_len: u8,
+    data_type: PrimitiveDataType,
+}
+
+impl ProcessVariable {
+    /// Index of the SubDevice in the group this variable belongs to.
+    pub fn subdevice_index(&self) -> usize {
+        self.subdevice
+    }
+
+    /// True if this variable is one of the SubDevice's inputs, false if it is an output.
+    pub fn is_input(&self) -> bool {
+        self.input
+    }
+
+    /// Offset of the variable's first bit from the start of the group PDI.
+    pub fn bit_offset(&self) -> usize {
+        self.bit_offset
+    }
+
+    /// Length of the variable in bits.
+    pub fn bit_len(&self) -> u8 {
+        self.bit_len
+    }
+
+    /// Data type of the variable read from the SubDevice EEPROM.
+    pub fn data_type(&self) -> PrimitiveDataType {
+        self.data_type
+    }
+
+    /// Offset of the variable's first bit from the start of the first byte of the SubDevice's
+    /// inputs or outputs.
+    fn segment_bit_offset(&self, subdevice: &SubDevice) -> Result<usize, Error> {
+        let io = subdevice.io_segments();
+
+        let segment = if self.input { &io.input } else { &io.output };
+
+        self.bit_offset
+            .checked_sub(segment.bytes.start * 8)
+            .ok_or(Error::NotFound {
+                item: Item::PdoEntry,
+                index: None,
+            })
+    }
+
+    /// Check that `T` packs into the same number of bytes as this variable occupies.
+    fn check_len<T>(&self) -> Result<(), Error>
+    where
+        T: EtherCrabWireSized,
+    {
+        if usize::from(self.bit_len).div_ceil(8) != T::PACKED_LEN {
+            fmt::error!(
+                "Process variable of {} bits cannot be accessed as a {} byte type",
+                self.bit_len,
+                T::PACKED_LEN
+            );
+
+            return Err(Error::Wire(WireError::InvalidValue));
+        }
+
+        Ok(())
+    }
+}
+
+impl<const MAX_SUBDEVICES: usize, const MAX_PDI: usize, S, DC>
+    SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, S, DC>
+where
+    S: HasPdi,
+{
+    /// Build the group's symbol table by reading the names of every SubDevice's PDO entries
+    /// from EEPROM.
+    ///
+    /// This must be called once before process variables can be found by name with
+    /// [`symbol`](SubDeviceGroup::symbol), [`var`](SubDeviceGroup::var) or
+    /// [`set_var`](SubDeviceGroup::set_var). Only hashes of the names are stored.
+    ///
+    /// Entries are only recorded if a `pdo-entries-*` crate feature is enabled.
+    pub async fn load_symbols(&self, maindevice: &MainDevice<'_>) -> Result<(), Error> {
+        for (index, subdevice) in self.inner().subdevices.iter().enumerate() {
+            let subdevice = subdevice.try_borrow_mut().map_err(|_e| {
+                fmt::error!("SubDevice index {} already borrowed", index);
+
+                Error::Borrow
+            })?;
+
+            SubDeviceRef::new(maindevice, subdevice.configured_address(), subdevice)
+                .load_entry_names()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Find a PDO entry in the group PDI by SubDevice and entry name.
+    ///
+    /// [`load_symbols`](SubDeviceGroup::load_symbols) must be called first.
+    ///
+    /// `subdevice` is compared to [`SubDevice::name`](crate::SubDevice::name). If more than one
+    /// SubDevice has this name, the first one with a matching entry is used.
+    ///
+    /// `name` is the entry name read from the SubDevice EEPROM, either on its own like `Value`
+    /// or prefixed with the name of the PDO containing it like `AI Standard Channel 1.Value`. If
+    /// more than one entry matches, the first is returned, searching inputs then outputs.
+    ///
+    /// Only hashes of the names are kept in memory, so the names of entries with a matching hash
+    /// are read from the SubDevice EEPROM again to check they really match.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::NotFound`] if no entry matches or the symbol table has not been loaded,
+    /// or an [`Error::Borrow`] if a SubDevice
+    /// in the group is currently borrowed with [`subdevice`](SubDeviceGroup::subdevice).
+    pub async fn symbol(
+        &self,
+        maindevice: &MainDevice<'_>,
+        subdevice: &str,
+        name: &str,
+    ) -> Result<ProcessVariable, Error> {
+        for (index, subdevice_cell) in self.inner().subdevices.iter().enumerate() {
+            // Position of the next entry with a matching hash to check
+            let mut candidate = 0;
+
+            loop {
+                // The SubDevice isn't borrowed while reading its EEPROM
+                let (configured_address, variable, entry_name) = {
+                    let sd = subdevice_cell.try_borrow().map_err(|_e| {
+                        fmt::error!("SubDevice index {} already borrowed", index);
+
+                        Error::Borrow
+                    })?;
+
+                    if sd.name() != subdevice {
+                        break;
+                    }
+
+                    let io = sd.io_segments();
+
+                    let Some(entry) = io.entries_by_name(name).nth(candidate) else {
+                        break;
+                    };
+
+                    let segment = if entry.input { &io.input } else { &io.output };
+
+                    let variable = ProcessVariable {
+                        subdevice: index,
+                        input: entry.input,
+                        bit_offset: segment.bytes.start * 8 + usize::from(entry.bit_offset),
+                        bit_len: entry.bit_len,
+                        data_type: entry.data_type,
+                    };
+
+                    (sd.configured_address(), variable, entry.name)
+                };
+
+                if SubDeviceRef::new(maindevice, configured_address, ())
+                    .entry_name_matches(&entry_name, name)
+                    .await?
+                {
+                    return Ok(variable);
+                }
+
+                fmt::debug!(
+                    "Entry name hash collision for {} on SubDevice index {}",
+                    name,
+                    index
+                );
+
+                candidate += 1;
+            }
+        }
+
+        Err(Error::NotFound {
+            item: Item::PdoEntry,
+            index: None,
+        })
+    }
+
+    /// Read a process variable by SubDevice and entry name.
+    ///
+    /// See [`symbol`](SubDeviceGroup::symbol) for how names are matched. When reading the same
+    /// variable every cycle, prefer looking it up once with `symbol` then reading it with
+    /// [`read_var`](SubDeviceGroup::read_var).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{
+    /// #     error::Error, MainDevice, MainDeviceConfig, PduStorage, Timeouts, std::ethercat_now,
+    /// # };
+    /// # static PDU_STORAGE: PduStorage<8, 32> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let maindevice = MainDevice::new(pdu_loop, Timeouts::default(), MainDeviceConfig::default());
+    /// # async {
+    /// let group = maindevice
+    ///     .init_single_group::<8, 8>(ethercat_now)
+    ///     .await?
+    ///     .into_op(&maindevice)
+    ///     .await?;
+    ///
+    /// group.load_symbols(&maindevice).await?;
+    ///
+    /// group.tx_rx(&maindevice).await?;
+    ///
+    /// let value = group
+    ///     .var::<i16>(&maindevice, "EL3004", "AI Standard Channel 1.Value")
+    ///     .await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors from [`symbol`](SubDeviceGroup::symbol) and
+    /// [`read_var`](SubDeviceGroup::read_var).
+    pub async fn var<T>(
+        &self,
+        maindevice: &MainDevice<'_>,
+        subdevice: &str,
+        name: &str,
+    ) -> Result<T, Error>
+    where
+        T: EtherCrabWireReadSized,
+    {
+        self.read_var(&self.symbol(maindevice, subdevice, name).await?)
+    }
+
+    /// Write a process variable by SubDevice and entry name.
+    ///
+    /// The new value is sent to the SubDevice by the next call to
+    /// [`tx_rx`](SubDeviceGroup::tx_rx).
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors from [`symbol`](SubDeviceGroup::symbol) and
+    /// [`write_var`](SubDeviceGroup::write_var).
+    pub async fn set_var<T>(
+        &self,
+        maindevice: &MainDevice<'_>,
+        subdevice: &str,
+        name: &str,
+        value: T,
+    ) -> Result<(), Error>
+    where
+        T: EtherCrabWireWriteSized,
+    {
+        self.write_var(&self.symbol(maindevice, subdevice, name).await?, value)
+    }
+
+    /// Read a process variable found with [`symbol`](SubDeviceGroup::symbol).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Wire`] if `T` does not have the same length in bytes as the variable,
+    /// e.g. reading a 16 bit entry as a `u32`. A [`bool`] can be used for 1 bit entries. Returns an
+    /// [`Error::Borrow`] if the variable's SubDevice is currently borrowed with
+    /// [`subdevice`](SubDeviceGroup::subdevice).
+    pub fn read_var<T>(&self, var: &ProcessVariable) -> Result<T, Error>
+    where
+        T: EtherCrabWireReadSized,
+    {
+        var.check_len::<T>()?;
+
+        let subdevice = self.subdevice_pdi(var.subdevice)?;
+
+        let mut buf = T::buffer();
+
+        subdevice.read_bits(
+            var.input,
+            var.segment_bit_offset(&subdevice)?,
+            var.bit_len.into(),
+            buf.as_mut(),
+        )?;
+
+        Ok(T::unpack_from_slice(buf.as_ref())?)
+    }
+
+    /// Write a process variable found with [`symbol`](SubDeviceGroup::symbol).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Wire`] if `T` does not have the same length in bytes as the variable,
+    /// or an [`Error::NotFound`] if the variable is one of the SubDevice's inputs. Returns an
+    /// [`Error::Borrow`] if the variable's SubDevice is currently borrowed with
+    /// [`subdevice`](SubDeviceGroup::subdevice).
+    pub fn write_var<T>(&self, var: &ProcessVariable, value: T) -> Result<(), Error>
+    where
+        T: EtherCrabWireWriteSized,
+    {
+        var.check_len::<T>()?;
+
+        if var.input {
+            fmt::error!(
+                "Process variable at bit {} is not an output",
+                var.bit_offset
+            );
+
+            return Err(Error::NotFound {
+                item: Item::PdoEntry,
+                index: None,
+            });
+        }
+
+        let mut subdevice = self.subdevice_pdi(var.subdevice)?;
+
+        let bit_offset = var.segment_bit_offset(&subdevice)?;
+
+        subdevice.write_output_bits(bit_offset, var.bit_len.into(), value.pack().as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pdi::PdiSegment,
+        subdevice_group::{GroupId, GroupInner, NoDc, Op},
+    };
+    use atomic_refcell::AtomicRefCell;
+    use core::{cell::UnsafeCell, marker::PhantomData};
+
+    /// Two SubDevices with 4 bit-packed outputs each, sharing the group's only output byte.
+    fn group() -> SubDeviceGroup<2, 4, Op> {
+        let subdevice = |start_bit: u8| {
+            let mut subdevice = SubDevice::default();
+
+            subdevice.config.io.output = PdiSegment {
+                bytes: 1..2,
+                bit_len: 4,
+                start_bit,
+                bit_packed: true,
+            };
+
+            AtomicRefCell::new(subdevice)
+        };
+
+        SubDeviceGroup {
+            id: GroupId(0),
+            pdi: UnsafeCell::new([0u8; 4]),
+            read_pdi_len: 1,
+            pdi_len: 2,
+            inner: UnsafeCell::new(GroupInner {
+                subdevices: [subdevice(0), subdevice(4)].into_iter().collect(),
+                ..GroupInner::default()
+            }),
+            dc_conf: NoDc,
+            _state: PhantomData,
+        }
+    }
+
+    fn var(subdevice: usize, bit_offset: usize) -> ProcessVariable {
+        ProcessVariable {
+            subdevice,
+            input: false,
+            bit_offset,
+            bit_len: 4,
+            data_type: PrimitiveDataType::Unknown,
+        }
+    }
+
+    #[test]
+    fn bit_packed_vars() {
+        let group = group();
+
+        group.write_var(&var(0, 8), 0x5u8).unwrap();
+        group.write_var(&var(1, 12), 0xau8).unwrap();
+
+        assert_eq!(group.pdi()[1], 0xa5);
+        assert_eq!(group.read_var::<u8>(&var(1, 12)), Ok(0xa));
+    }
+
+    #[test]
+    fn borrowed_subdevice() {
+        let group = group();
+
+        let _subdevice = group.subdevice_pdi(1).unwrap();
+
+        assert_eq!(group.write_var(&var(1, 12), 0xau8), Err(Error::Borrow));
+        assert_eq!(group.read_var::<u8>(&var(1, 12)), Err(Error::Borrow));
+        assert_eq!(group.write_var(&var(0, 8), 0x5u8), Ok(()));
+    }
+}
@@ -169,7 +169,26 @@ pub enum BufferState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWrite};
+    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWrite, EtherCrabWireWriteSized};
+
+    #[test]
+    fn enable_repeat_bits() {
+        let enable = Enable {
+            enable: true,
+            repeat: true,
+            ..Enable::default()
+        };
+
+        assert_eq!(enable.pack(), [0x03, 0x00]);
+        assert_eq!(
+            Enable::unpack_from_slice(&[0x01, 0x02]),
+            Ok(Enable {
+                enable: true,
+                repeat_ack: true,
+                ..Enable::default()
+            })
+        );
+    }
 
     #[test]
     fn issue_49_decode_timeout_response() {
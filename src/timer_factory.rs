@@ -88,6 +88,11 @@ pub struct Timeouts {
     pub mailbox_echo: Duration,
 
     /// How long to wait for a response to be read from the SubDevice's response mailbox.
+    ///
+    /// This is also how long to wait for a SubDevice to acknowledge a mailbox repeat request, and
+    /// how long to wait for a response to be indicated in cyclic process data if
+    /// [`SubDeviceGroup::set_mailbox_status_mapping`](crate::SubDeviceGroup::set_mailbox_status_mapping)
+    /// is used.
     pub mailbox_response: Duration,
 }
 
@@ -30,6 +30,7 @@ async fn replay_ek1914_no_complete_access() -> Result<(), Error> {
         MainDeviceConfig {
             dc_static_sync_iterations: 100,
             retry_behaviour: RetryBehaviour::None,
+            ..MainDeviceConfig::default()
         },
     );
 
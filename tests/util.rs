@@ -71,12 +71,15 @@ impl core::hash::Hash for PreambleHash {
     }
 }
 
+/// Captured frames with their packet number in the dump, keyed by PDU preamble.
+type FrameQueues = HashMap<PreambleHash, VecDeque<(EthernetFrame<Vec<u8>>, usize)>>;
+
 struct DummyTxRxFut<'a> {
     tx: PduTx<'a>,
     rx: PduRx<'a>,
     // The hashmap here is an optimisation over just a straight vec to improve popping performance.
-    pdu_sends: HashMap<PreambleHash, VecDeque<(EthernetFrame<Vec<u8>>, usize)>>,
-    pdu_responses: HashMap<PreambleHash, VecDeque<(EthernetFrame<Vec<u8>>, usize)>>,
+    pdu_sends: FrameQueues,
+    pdu_responses: FrameQueues,
 }
 
 impl Future for DummyTxRxFut<'_> {
@@ -157,14 +160,14 @@ pub fn dummy_tx_rx_task(
 
         let (raw, preamble) = match block {
             Block::EnhancedPacket(block) => {
-                let buf = block.data.to_owned();
+                let buf = block.data;
 
                 let buf2 = buf.iter().copied().collect::<Vec<_>>();
 
                 let mut f = EthernetFrame::new_checked(buf2).expect("Failed to parse block");
 
                 assert_eq!(
-                    u16::from(f.ethertype()),
+                    f.ethertype(),
                     0x88a4,
                     "packet {} is not an EtherCAT frame",
                     packet_number